@@ -0,0 +1,208 @@
+//! The number theory behind the CM method for class number one: square roots
+//! mod p, the norm equation t² + D y² = 4p, and curves with a given
+//! j-invariant, shared by the curve constructions and by ECPP.
+
+use integer_factorization::jacobi;
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+/// Discriminants -D of class number one with the j-invariant of the curve
+/// with complex multiplication by the corresponding order.
+pub const CLASS_NUMBER_ONE: [(u64, i64); 13] = [
+    (3, 0),
+    (4, 1728),
+    (7, -3375),
+    (8, 8000),
+    (11, -32768),
+    (12, 54000),
+    (16, 287496),
+    (19, -884736),
+    (27, -12288000),
+    (28, 16581375),
+    (43, -884736000),
+    (67, -147197952000),
+    (163, -262537412640768000),
+];
+
+/// Coefficients tried for j = 0 and j = 1728, enough to meet every twist.
+const TWIST_ATTEMPTS: u32 = 30;
+
+/// Square root mod a probable prime p by Tonelli–Shanks, `None` for non-residues.
+pub fn sqrt_mod(a: &BigUint, p: &BigUint) -> Option<BigUint> {
+    let a = a % p;
+    if a.is_zero() {
+        return Some(a);
+    }
+    if jacobi(&BigInt::from(a.clone()), p) != 1 {
+        return None;
+    }
+    let p_minus_1 = p - 1u32;
+    let s = p_minus_1.trailing_zeros().unwrap_or(0);
+    let q = &p_minus_1 >> s;
+
+    let mut z = BigUint::from(2u32);
+    while jacobi(&BigInt::from(z.clone()), p) != -1 {
+        z += 1u32;
+    }
+
+    let mut m = s;
+    let mut c = z.modpow(&q, p);
+    let mut t = a.modpow(&q, p);
+    let mut r = a.modpow(&((&q + 1u32) >> 1u32), p);
+    while !t.is_one() {
+        let mut i = 0;
+        let mut t2i = t.clone();
+        while !t2i.is_one() {
+            t2i = &t2i * &t2i % p;
+            i += 1;
+            if i == m {
+                return None;
+            }
+        }
+        let b = c.modpow(&(BigUint::one() << (m - i - 1)), p);
+        m = i;
+        c = &b * &b % p;
+        t = t * &c % p;
+        r = r * b % p;
+    }
+    Some(r)
+}
+
+/// Solves t² + D y² = 4p with the modified Cornacchia algorithm, returning
+/// (t, y).
+pub fn cornacchia(d: u64, p: &BigUint) -> Option<(BigUint, BigUint)> {
+    let minus_d = (p - (BigUint::from(d) % p)) % p;
+    let mut x0 = sqrt_mod(&minus_d, p)?;
+    if x0.is_odd() != (d % 2 == 1) {
+        x0 = p - x0;
+    }
+
+    let four_p: BigUint = p * 4u32;
+    let limit = four_p.sqrt();
+    let (mut a, mut b) = (p * 2u32, x0);
+    while b > limit {
+        let r = &a % &b;
+        a = b;
+        b = r;
+    }
+
+    let rest = &four_p - &b * &b;
+    if !(&rest % d).is_zero() {
+        return None;
+    }
+    let c = rest / d;
+    let y = c.sqrt();
+    if &y * &y == c {
+        Some((b, y))
+    } else {
+        None
+    }
+}
+
+/// The coefficients (a, b) of curves y² = x³ + ax + b over F_p with
+/// j-invariant j: for j = 0 and 1728 a run of coefficients covering the
+/// sextic and quartic twists, otherwise y² = x³ + 3kx + 2k with
+/// k = j / (1728 - j) and its quadratic twist.
+pub fn curves_with_j(j: &BigUint, p: &BigUint) -> Vec<(BigUint, BigUint)> {
+    let j = j % p;
+    let j1728 = BigUint::from(1728u32) % p;
+    if j.is_zero() {
+        return (1..=TWIST_ATTEMPTS)
+            .map(|b| (BigUint::zero(), BigUint::from(b)))
+            .collect();
+    }
+    if j == j1728 {
+        return (1..=TWIST_ATTEMPTS)
+            .map(|a| (BigUint::from(a), BigUint::zero()))
+            .collect();
+    }
+    let k = &j * (&j1728 + p - &j).modpow(&(p - 2u32), p) % p;
+    let mut c = BigUint::from(2u32);
+    while jacobi(&BigInt::from(c.clone()), p) != -1 {
+        c += 1u32;
+    }
+    let c2 = &c * &c % p;
+    let c3 = &c2 * &c % p;
+    let a = &k * 3u32 % p;
+    let b = &k * 2u32 % p;
+    vec![(a.clone(), b.clone()), (a * c2 % p, b * c3 % p)]
+}
+
+/// j mod p for a j-invariant given as a signed integer, as in
+/// [`CLASS_NUMBER_ONE`].
+pub fn reduce_j(j: i64, p: &BigUint) -> BigUint {
+    BigInt::from(j)
+        .mod_floor(&BigInt::from(p.clone()))
+        .to_biguint()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(n: u64) -> BigUint {
+        BigUint::from(n)
+    }
+
+    #[test]
+    fn square_roots() {
+        // 1000003 ≡ 3 (mod 4) and 1000000009 ≡ 1 (mod 8), the easy and
+        // the general Tonelli–Shanks case
+        for p in [big(1_000_003), big(1_000_000_009)] {
+            assert_eq!(sqrt_mod(&BigUint::zero(), &p), Some(BigUint::zero()));
+            for x in [1u64, 2, 12_345, 999_999] {
+                let square = big(x) * big(x) % &p;
+                let root = sqrt_mod(&square, &p).unwrap();
+                assert_eq!(&root * &root % &p, square);
+            }
+            let non_residue = (2u64..)
+                .find(|&a| jacobi(&BigInt::from(a), &p) == -1)
+                .unwrap();
+            assert_eq!(sqrt_mod(&big(non_residue), &p), None);
+        }
+    }
+
+    #[test]
+    fn norm_equation() {
+        let p = big(1_000_000_009);
+        let mut solved = 0;
+        for &(d, _) in CLASS_NUMBER_ONE.iter() {
+            if let Some((t, y)) = cornacchia(d, &p) {
+                assert_eq!(&t * &t + &y * &y * d, &p * 4u32, "D = {}", d);
+                solved += 1;
+            }
+        }
+        assert!(solved > 0);
+        // 28 = 5² + 3 · 1²
+        assert_eq!(cornacchia(3, &big(7)), Some((big(5), big(1))));
+        // -3 is not a square mod 5
+        assert_eq!(cornacchia(3, &big(5)), None);
+    }
+
+    #[test]
+    fn curves_have_the_requested_j_invariant() {
+        let p = big(1_000_003);
+        let j_of = |a: &BigUint, b: &BigUint| {
+            let a3 = a.modpow(&big(3), &p) * 4u32 % &p;
+            let denominator = (&a3 + b * b * 27u32) % &p;
+            a3 * 1728u32 * denominator.modpow(&(&p - 2u32), &p) % &p
+        };
+        for &(_, j) in CLASS_NUMBER_ONE.iter() {
+            let j = reduce_j(j, &p);
+            let curves = curves_with_j(&j, &p);
+            assert!(!curves.is_empty());
+            for (a, b) in &curves {
+                assert_eq!(j_of(a, b), j);
+            }
+        }
+    }
+
+    #[test]
+    fn reduces_negative_j() {
+        let p = big(1_000_003);
+        assert_eq!(reduce_j(-3375, &p), big(1_000_003 - 3375));
+        assert_eq!(reduce_j(1728, &p), big(1728));
+    }
+}
@@ -0,0 +1,172 @@
+use cm_curves::{cornacchia, curves_with_j, sqrt_mod};
+use integer_factorization::is_probable_prime;
+use num_bigint::{BigInt, BigUint};
+use num_traits::Zero;
+use std::fmt;
+
+use crate::hilbert::hilbert_class_polynomial;
+use crate::polynomial::Polynomial;
+
+/// Points that must be killed by the expected order before a curve is accepted.
+const ORDER_CHECKS: usize = 3;
+
+/// An affine point, `None` being the point at infinity.
+pub type Point = Option<(BigUint, BigUint)>;
+
+/// y² = x³ + ax + b over F_p.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Curve {
+    pub a: BigUint,
+    pub b: BigUint,
+    pub p: BigUint,
+}
+
+impl Curve {
+    pub fn rhs(&self, x: &BigUint) -> BigUint {
+        (x * x % &self.p * x + &self.a * x + &self.b) % &self.p
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        match point {
+            None => true,
+            Some((x, y)) => y * y % &self.p == self.rhs(x),
+        }
+    }
+
+    /// The j-invariant 1728 · 4a³ / (4a³ + 27b²).
+    pub fn j_invariant(&self) -> BigUint {
+        let p = &self.p;
+        let a3 = &self.a * &self.a % p * &self.a * 4u32 % p;
+        let discriminant = (&a3 + &self.b * &self.b * 27u32) % p;
+        a3 * 1728u32 % p * discriminant.modpow(&(p - 2u32), p) % p
+    }
+
+    fn inverse(&self, value: &BigUint) -> BigUint {
+        value.modpow(&(&self.p - 2u32), &self.p)
+    }
+
+    pub fn add(&self, p1: &Point, p2: &Point) -> Point {
+        let (Some((x1, y1)), Some((x2, y2))) = (p1, p2) else {
+            return if p1.is_none() { p2.clone() } else { p1.clone() };
+        };
+        let p = &self.p;
+        let lambda = if x1 != x2 {
+            (y2 + p - y1) * self.inverse(&((x2 + p - x1) % p)) % p
+        } else if y1 == y2 && !y1.is_zero() {
+            (x1 * x1 * 3u32 + &self.a) * self.inverse(&(y1 * 2u32 % p)) % p
+        } else {
+            return None;
+        };
+        let x3 = (&lambda * &lambda + p * 2u32 - x1 - x2) % p;
+        let y3 = (lambda * ((x1 + p - &x3) % p) + p - y1) % p;
+        Some((x3, y3))
+    }
+
+    pub fn multiply(&self, k: &BigUint, point: &Point) -> Point {
+        let mut result = None;
+        for i in (0..k.bits()).rev() {
+            result = self.add(&result, &result);
+            if k.bit(i) {
+                result = self.add(&result, point);
+            }
+        }
+        result
+    }
+
+    /// The points with x = 0, 1, 2, ..., one y for each.
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0u64..)
+            .map(BigUint::from)
+            .take_while(|x| x < &self.p)
+            .filter_map(|x| sqrt_mod(&self.rhs(&x), &self.p).map(|y| Some((x, y))))
+    }
+
+    /// Whether the curve has n points, judged by n killing a few points.
+    fn has_order(&self, n: &BigUint) -> bool {
+        self.points()
+            .take(ORDER_CHECKS)
+            .all(|point| self.multiply(n, &point).is_none())
+    }
+}
+
+impl fmt::Display for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "y² = x³ + {}x + {} over F_{}", self.a, self.b, self.p)
+    }
+}
+
+/// The result of the CM method for a discriminant -D and a prime p with
+/// 4p = t² + Dy²: a curve with p + 1 - t points and its twist with p + 1 + t.
+#[derive(Debug)]
+pub struct CmConstruction {
+    pub d: u64,
+    pub t: BigUint,
+    pub y: BigUint,
+    pub class_polynomial: Vec<BigInt>,
+    pub j: BigUint,
+    pub curve: Curve,
+    pub twist: Curve,
+}
+
+impl CmConstruction {
+    pub fn order(&self) -> BigUint {
+        &self.curve.p + 1u32 - &self.t
+    }
+
+    pub fn twist_order(&self) -> BigUint {
+        &self.curve.p + 1u32 + &self.t
+    }
+}
+
+impl fmt::Display for CmConstruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "D = {}, h(-D) = {}, 4p = t² + Dy² with t = {}, y = {}",
+            self.d,
+            self.class_polynomial.len() - 1,
+            self.t,
+            self.y
+        )?;
+        writeln!(f, "j = {}", self.j)?;
+        writeln!(f, "E:  {}, #E = {}", self.curve, self.order())?;
+        write!(f, "E': {}, #E' = {}", self.twist, self.twist_order())
+    }
+}
+
+/// The CM method: with 4p = t² + Dy², a root j of H_D mod p is the
+/// j-invariant of a curve with complex multiplication by the order of
+/// discriminant -D, and the curves with that j have p + 1 ± t points (for
+/// j = 0 and 1728 there are further twists, which are skipped).
+pub fn construct(d: u64, p: &BigUint) -> Result<CmConstruction, &'static str> {
+    if *p <= BigUint::from(3u32) || !is_probable_prime(p) {
+        return Err("p must be a prime larger than 3");
+    }
+    let (t, y) = cornacchia(d, p).ok_or("p is not of the form (t² + Dy²) / 4")?;
+    let class_polynomial = hilbert_class_polynomial(d)?;
+    let roots = Polynomial::reduce(&class_polynomial, p).roots();
+    let j = roots.into_iter().next().ok_or("H_D has no root mod p")?;
+
+    let (order, twist_order) = (p + 1u32 - &t, p + 1u32 + &t);
+    let curves: Vec<Curve> = curves_with_j(&j, p)
+        .into_iter()
+        .map(|(a, b)| Curve { a, b, p: p.clone() })
+        .collect();
+    let curve = curves
+        .iter()
+        .find(|curve| curve.has_order(&order))
+        .ok_or("no curve with p + 1 - t points")?;
+    let twist = curves
+        .iter()
+        .find(|curve| curve.has_order(&twist_order))
+        .ok_or("no curve with p + 1 + t points")?;
+    Ok(CmConstruction {
+        d,
+        t,
+        y,
+        class_polynomial,
+        j,
+        curve: curve.clone(),
+        twist: twist.clone(),
+    })
+}
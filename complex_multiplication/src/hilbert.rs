@@ -0,0 +1,311 @@
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+
+/// Hilbert class polynomials H_D(X), constant term first, for the
+/// discriminants -D of class number one and a few of class number two and
+/// three. Everything else is computed from the reduced forms.
+const TABLE: [(u64, &[&str]); 25] = [
+    (3, &["0", "1"]),
+    (4, &["-1728", "1"]),
+    (7, &["3375", "1"]),
+    (8, &["-8000", "1"]),
+    (11, &["32768", "1"]),
+    (12, &["-54000", "1"]),
+    (16, &["-287496", "1"]),
+    (19, &["884736", "1"]),
+    (27, &["12288000", "1"]),
+    (28, &["-16581375", "1"]),
+    (43, &["884736000", "1"]),
+    (67, &["147197952000", "1"]),
+    (163, &["262537412640768000", "1"]),
+    (15, &["-121287375", "191025", "1"]),
+    (20, &["-681472000", "-1264000", "1"]),
+    (23, &["12771880859375", "-5151296875", "3491750", "1"]),
+    (24, &["14670139392", "-4834944", "1"]),
+    (31, &["1566028350940383", "-58682638134", "39491307", "1"]),
+    (35, &["-134217728000", "117964800", "1"]),
+    (40, &["9103145472000", "-425692800", "1"]),
+    (51, &["6262062317568", "5541101568", "1"]),
+    (52, &["-567663552000000", "-6896880000", "1"]),
+    (
+        59,
+        &["374643194001883136", "-140811576541184", "30197678080", "1"],
+    ),
+    (88, &["15798135578688000000", "-6294842640000", "1"]),
+    (91, &["-3845689020776448", "10359073013760", "1"]),
+];
+
+/// The reduced primitive forms ax² + bxy + cy² of discriminant -D:
+/// |b| ≤ a ≤ c, with b ≥ 0 when |b| = a or a = c. There are h(-D) of them.
+pub fn reduced_forms(d: u64) -> Vec<(u64, i64, u64)> {
+    let mut forms = Vec::new();
+    let mut a = 1u64;
+    while 3 * a * a <= d {
+        for b in -(a as i64) + 1..=a as i64 {
+            let b2 = (b * b) as u64;
+            if !(b2 + d).is_multiple_of(4 * a) {
+                continue;
+            }
+            let c = (b2 + d) / (4 * a);
+            if c < a || (b < 0 && a == c) || a.gcd(&b.unsigned_abs()).gcd(&c) != 1 {
+                continue;
+            }
+            forms.push((a, b, c));
+        }
+        a += 1;
+    }
+    forms
+}
+
+/// Complex numbers in fixed point, both parts scaled by 2^precision.
+#[derive(Clone)]
+struct Complex {
+    re: BigInt,
+    im: BigInt,
+}
+
+/// Fixed-point arithmetic at a given precision, carrying π.
+struct FixedPoint {
+    precision: u64,
+    pi: BigInt,
+}
+
+impl FixedPoint {
+    fn new(precision: u64) -> Self {
+        let mut fixed = FixedPoint {
+            precision,
+            pi: BigInt::zero(),
+        };
+        // Machin: π = 16 atan(1/5) - 4 atan(1/239)
+        fixed.pi = fixed.atan_inverse(5) * 16 - fixed.atan_inverse(239) * 4;
+        fixed
+    }
+
+    fn one(&self) -> BigInt {
+        BigInt::one() << self.precision
+    }
+
+    fn integer(&self, n: i64) -> BigInt {
+        BigInt::from(n) << self.precision
+    }
+
+    /// The product, truncated towards zero so that series terms reach 0.
+    fn mul(&self, x: &BigInt, y: &BigInt) -> BigInt {
+        let product = x * y;
+        if product.is_negative() {
+            -((-product) >> self.precision)
+        } else {
+            product >> self.precision
+        }
+    }
+
+    fn div(&self, x: &BigInt, y: &BigInt) -> BigInt {
+        (x << self.precision) / y
+    }
+
+    fn sqrt(&self, n: u64) -> BigInt {
+        (BigInt::from(n) << (2 * self.precision)).sqrt()
+    }
+
+    /// atan(1/n) = Σ (-1)^i / ((2i + 1) n^(2i+1)).
+    fn atan_inverse(&self, n: i64) -> BigInt {
+        let mut power = self.one() / n;
+        let mut sum = power.clone();
+        let mut i = 1i64;
+        while !power.is_zero() {
+            power /= n * n;
+            let term = &power / (2 * i + 1);
+            sum = if i % 2 == 1 { sum - term } else { sum + term };
+            i += 1;
+        }
+        sum
+    }
+
+    /// e^x, as (e^(x / 2^s))^(2^s) with the Taylor series at |x / 2^s| < 1.
+    fn exp(&self, x: &BigInt) -> BigInt {
+        let s = (x.abs() >> self.precision).bits() + 1;
+        let reduced = x / (BigInt::one() << s);
+        let mut term = self.one();
+        let mut sum = self.one();
+        let mut i = 1u32;
+        while !term.is_zero() {
+            term = self.mul(&term, &reduced) / i;
+            sum += &term;
+            i += 1;
+        }
+        for _ in 0..s {
+            sum = self.mul(&sum, &sum);
+        }
+        sum
+    }
+
+    /// e^(iθ) for |θ| ≤ π, summing θ^k / k! into cos or sin by k mod 4.
+    fn unit(&self, theta: &BigInt) -> Complex {
+        let (mut cos, mut sin) = (BigInt::zero(), BigInt::zero());
+        let mut term = self.one();
+        let mut k = 0u32;
+        while !term.is_zero() {
+            match k % 4 {
+                0 => cos += &term,
+                1 => sin += &term,
+                2 => cos -= &term,
+                _ => sin -= &term,
+            }
+            k += 1;
+            term = self.mul(&term, theta) / k;
+        }
+        Complex { re: cos, im: sin }
+    }
+
+    fn complex_mul(&self, x: &Complex, y: &Complex) -> Complex {
+        Complex {
+            re: self.mul(&x.re, &y.re) - self.mul(&x.im, &y.im),
+            im: self.mul(&x.re, &y.im) + self.mul(&x.im, &y.re),
+        }
+    }
+
+    fn complex_inverse(&self, x: &Complex) -> Complex {
+        let norm = self.mul(&x.re, &x.re) + self.mul(&x.im, &x.im);
+        Complex {
+            re: self.div(&x.re, &norm),
+            im: self.div(&-&x.im, &norm),
+        }
+    }
+
+    fn scale(&self, x: &Complex, factor: &BigInt) -> Complex {
+        Complex {
+            re: self.mul(&x.re, factor),
+            im: self.mul(&x.im, factor),
+        }
+    }
+
+    /// j(τ) for τ = (-b + √-D) / 2a, from q = e^(2πiτ) as
+    ///   j = E4³ / Δ, E4 = 1 + 240 Σ σ3(n) qⁿ, Δ = q Π (1 - qⁿ)^24.
+    /// 1/q is formed directly since |q| is tiny and would lose its digits.
+    fn j_invariant(&self, d: u64, a: u64, b: i64) -> Complex {
+        let angle = &self.pi * b / a as i64;
+        let modulus = self.mul(&self.pi, &self.sqrt(d)) / a;
+        let q = self.scale(&self.unit(&-&angle), &self.exp(&-&modulus));
+        let q_inverse = self.scale(&self.unit(&angle), &self.exp(&modulus));
+
+        let one = Complex {
+            re: self.one(),
+            im: BigInt::zero(),
+        };
+        let (mut e4, mut product) = (one.clone(), one);
+        let mut power = q.clone();
+        let mut n = 1i64;
+        while !power.re.is_zero() || !power.im.is_zero() {
+            let sigma3: i64 = (1..=n).filter(|k| n % k == 0).map(|k| k * k * k).sum();
+            let term = self.scale(&power, &self.integer(240 * sigma3));
+            e4.re += term.re;
+            e4.im += term.im;
+            let factor = Complex {
+                re: self.one() - &power.re,
+                im: -&power.im,
+            };
+            product = self.complex_mul(&product, &factor);
+            power = self.complex_mul(&power, &q);
+            n += 1;
+        }
+
+        let cube = self.complex_mul(&product, &self.complex_mul(&product, &product));
+        let mut product24 = cube;
+        for _ in 0..3 {
+            product24 = self.complex_mul(&product24, &product24);
+        }
+        let e4_cubed = self.complex_mul(&e4, &self.complex_mul(&e4, &e4));
+        self.complex_mul(
+            &self.complex_mul(&e4_cubed, &q_inverse),
+            &self.complex_inverse(&product24),
+        )
+    }
+}
+
+/// H_D(X) from the table if present, otherwise computed.
+pub fn hilbert_class_polynomial(d: u64) -> Result<Vec<BigInt>, &'static str> {
+    match TABLE.iter().find(|(entry, _)| *entry == d) {
+        Some((_, coefficients)) => Ok(coefficients.iter().map(|c| c.parse().unwrap()).collect()),
+        None => compute_hilbert_class_polynomial(d),
+    }
+}
+
+/// H_D(X) = Π (X - j((-b + √-D) / 2a)) over the reduced forms (a, b, c),
+/// multiplied out in fixed point and rounded to integers. Each root is
+/// about e^(π√D / a), so the precision is the bits of Π (|j| + 1) plus a
+/// margin for the rounding.
+pub fn compute_hilbert_class_polynomial(d: u64) -> Result<Vec<BigInt>, &'static str> {
+    if d < 3 || !(d.is_multiple_of(4) || d % 4 == 3) {
+        return Err("-D must be a discriminant, D ≡ 0 or 3 (mod 4)");
+    }
+    let forms = reduced_forms(d);
+    let size: f64 = forms
+        .iter()
+        .map(|&(a, _, _)| {
+            std::f64::consts::PI * (d as f64).sqrt() / a as f64 / std::f64::consts::LN_2 + 2.0
+        })
+        .sum();
+    let fixed = FixedPoint::new(size as u64 + 32 * forms.len() as u64 + 128);
+
+    let zero = Complex {
+        re: BigInt::zero(),
+        im: BigInt::zero(),
+    };
+    let mut polynomial = vec![Complex {
+        re: fixed.one(),
+        im: BigInt::zero(),
+    }];
+    for &(a, b, _) in &forms {
+        let j = fixed.j_invariant(d, a, b);
+        // multiply by (X - j)
+        let mut next = vec![zero.clone(); polynomial.len() + 1];
+        for (i, coefficient) in polynomial.iter().enumerate() {
+            next[i + 1].re += &coefficient.re;
+            next[i + 1].im += &coefficient.im;
+            let shifted = fixed.complex_mul(coefficient, &j);
+            next[i].re -= shifted.re;
+            next[i].im -= shifted.im;
+        }
+        polynomial = next;
+    }
+
+    let half = BigInt::one() << (fixed.precision - 1);
+    let tolerance = BigInt::one() << (fixed.precision - 8);
+    let mut coefficients = Vec::new();
+    for coefficient in polynomial {
+        let (rounded, error) = (&coefficient.re + &half).div_mod_floor(&fixed.one());
+        if (error - &half).abs() > tolerance || coefficient.im.abs() > tolerance {
+            return Err("precision too low to round H_D to integers");
+        }
+        coefficients.push(rounded);
+    }
+    Ok(coefficients)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn table_matches_computed_polynomials() {
+        for (d, _) in TABLE {
+            let table = hilbert_class_polynomial(d).unwrap();
+            assert_eq!(
+                compute_hilbert_class_polynomial(d).unwrap(),
+                table,
+                "D = {}",
+                d
+            );
+            assert_eq!(table.len() - 1, reduced_forms(d).len(), "D = {}", d);
+            assert_eq!(table.last(), Some(&BigInt::one()), "D = {}", d);
+        }
+    }
+
+    #[test]
+    fn rejects_non_discriminants() {
+        for d in [1, 2, 5, 6, 10] {
+            assert!(compute_hilbert_class_polynomial(d).is_err(), "D = {}", d);
+        }
+    }
+}
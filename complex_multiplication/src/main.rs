@@ -0,0 +1,159 @@
+mod cm;
+mod hilbert;
+mod polynomial;
+
+use cm::construct;
+use hilbert::compute_hilbert_class_polynomial;
+use integer_factorization::is_probable_prime;
+use num_bigint::BigUint;
+use std::time::Instant;
+
+/// (D, p) pairs small enough to count #E(F_p) point by point.
+const SMALL_FIELDS: [(u64, u32); 5] = [(23, 59), (71, 107), (7, 43), (4, 109), (3, 103)];
+
+/// The first prime p = (t² + D) / 4 with t ≥ start, so that y = 1, for
+/// which the CM curve has prime order p + 1 - t. Needs D ≡ 3 (mod 8) and
+/// D ≢ 2 (mod 3): otherwise 2 or 3 divides one of p and p + 1 - t.
+fn prime_order_field(d: u64, start: &BigUint) -> BigUint {
+    let mut t = start.clone();
+    if t.bit(0) != (d % 2 == 1) {
+        t += 1u32;
+    }
+    loop {
+        let p = (&t * &t + d) >> 2;
+        if is_probable_prime(&p) && is_probable_prime(&(&p + 1u32 - &t)) {
+            return p;
+        }
+        t += 2u32;
+    }
+}
+
+fn main() {
+    // Class polynomials beyond the table
+    for d in [47, 71] {
+        let start = Instant::now();
+        match compute_hilbert_class_polynomial(d) {
+            Ok(coefficients) => {
+                println!("\nH_{}(X), constant term first ({:?}):", d, start.elapsed());
+                for c in coefficients {
+                    println!("  {}", c);
+                }
+            }
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
+    // Small fields, where the tests check the orders by counting
+    for (d, p) in SMALL_FIELDS {
+        println!("\nD = {}, p = {}", d, p);
+        match construct(d, &BigUint::from(p)) {
+            Ok(construction) => {
+                println!("{}", construction);
+                println!("j(E) = {}", construction.curve.j_invariant());
+            }
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
+    // Prime-order curves over 128 and 256-bit fields, D = 139 and 427 beyond the table
+    for (d, bits) in [(19u64, 64u32), (139, 64), (427, 128)] {
+        let p = prime_order_field(d, &(BigUint::from(1u32) << bits));
+        let start = Instant::now();
+        println!("\nD = {}, {}-bit p = {}", d, p.bits(), p);
+        match construct(d, &p) {
+            Ok(construction) => {
+                let order = construction.order();
+                let generator = construction.curve.points().next().unwrap();
+                println!("{}", construction);
+                println!(
+                    "#E prime: {}, G on E: {}, [#E]G = O: {} ({:?})",
+                    is_probable_prime(&order),
+                    construction.curve.contains(&generator),
+                    construction.curve.multiply(&order, &generator).is_none(),
+                    start.elapsed()
+                );
+            }
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
+    println!("\nD = 23, p = 53");
+    if let Err(e) = construct(23, &BigUint::from(53u32)) {
+        println!("Error: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cm::Curve;
+    use crate::polynomial::Polynomial;
+
+    /// #E(F_p) by counting, one Legendre symbol per x.
+    fn count_points(curve: &Curve) -> BigUint {
+        let p = &curve.p;
+        let exponent = (p - 1u32) >> 1;
+        let mut count = BigUint::from(1u32);
+        let mut x = BigUint::from(0u32);
+        while x < *p {
+            let rhs = curve.rhs(&x);
+            if rhs == BigUint::from(0u32) {
+                count += 1u32;
+            } else if rhs.modpow(&exponent, p) == BigUint::from(1u32) {
+                count += 2u32;
+            }
+            x += 1u32;
+        }
+        count
+    }
+
+    #[test]
+    fn counted_orders_are_p_plus_1_minus_and_plus_t() {
+        for (d, p) in SMALL_FIELDS {
+            let construction = construct(d, &BigUint::from(p)).unwrap();
+            let p = BigUint::from(p);
+            let (t, y) = (&construction.t, &construction.y);
+            assert_eq!(t * t + y * y * d, &p * 4u32, "D = {}", d);
+            assert_eq!(
+                count_points(&construction.curve),
+                &p + 1u32 - t,
+                "D = {}",
+                d
+            );
+            assert_eq!(
+                count_points(&construction.twist),
+                &p + 1u32 + t,
+                "D = {}",
+                d
+            );
+            assert_eq!(construction.order(), &p + 1u32 - t);
+            assert_eq!(construction.twist_order(), &p + 1u32 + t);
+
+            let class_polynomial = Polynomial::reduce(&construction.class_polynomial, &p);
+            assert!(class_polynomial
+                .roots()
+                .contains(&construction.curve.j_invariant()));
+            assert_eq!(construction.curve.j_invariant(), construction.j);
+        }
+    }
+
+    #[test]
+    fn prime_order_curves() {
+        let p = prime_order_field(19, &(BigUint::from(1u32) << 64u32));
+        let construction = construct(19, &p).unwrap();
+        let order = construction.order();
+        assert!(is_probable_prime(&order));
+        let generator = construction.curve.points().next().unwrap();
+        assert!(construction.curve.contains(&generator));
+        assert!(construction.curve.multiply(&order, &generator).is_none());
+    }
+
+    #[test]
+    fn rejects_primes_without_a_norm_solution() {
+        // 4 · 53 = 212 is not t² + 23y²
+        assert_eq!(
+            construct(23, &BigUint::from(53u32)).err(),
+            Some("p is not of the form (t² + Dy²) / 4")
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+/// A polynomial over F_p, constant term first, without leading zeros.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polynomial {
+    pub coefficients: Vec<BigUint>,
+    pub p: BigUint,
+}
+
+impl Polynomial {
+    pub fn new(coefficients: Vec<BigUint>, p: &BigUint) -> Self {
+        let mut polynomial = Polynomial {
+            coefficients: coefficients.into_iter().map(|c| c % p).collect(),
+            p: p.clone(),
+        };
+        polynomial.trim();
+        polynomial
+    }
+
+    /// The reduction mod p of a polynomial with integer coefficients.
+    pub fn reduce(coefficients: &[BigInt], p: &BigUint) -> Self {
+        let modulus = BigInt::from(p.clone());
+        let reduced = coefficients
+            .iter()
+            .map(|c| c.mod_floor(&modulus).to_biguint().unwrap())
+            .collect();
+        Self::new(reduced, p)
+    }
+
+    fn trim(&mut self) {
+        while self.coefficients.last().is_some_and(Zero::is_zero) {
+            self.coefficients.pop();
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coefficients.is_empty()
+    }
+
+    /// The degree, with -1 standing for the zero polynomial.
+    pub fn degree(&self) -> isize {
+        self.coefficients.len() as isize - 1
+    }
+
+    fn inverse(&self, value: &BigUint) -> BigUint {
+        value.modpow(&(&self.p - 2u32), &self.p)
+    }
+
+    fn sub(&self, other: &Polynomial) -> Polynomial {
+        let length = self.coefficients.len().max(other.coefficients.len());
+        let zero = BigUint::zero();
+        let coefficients = (0..length)
+            .map(|i| {
+                let x = self.coefficients.get(i).unwrap_or(&zero);
+                let y = other.coefficients.get(i).unwrap_or(&zero);
+                (x + &self.p - y) % &self.p
+            })
+            .collect();
+        Polynomial::new(coefficients, &self.p)
+    }
+
+    fn mul(&self, other: &Polynomial) -> Polynomial {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::new(Vec::new(), &self.p);
+        }
+        let mut coefficients =
+            vec![BigUint::zero(); self.coefficients.len() + other.coefficients.len() - 1];
+        for (i, x) in self.coefficients.iter().enumerate() {
+            for (j, y) in other.coefficients.iter().enumerate() {
+                coefficients[i + j] = (&coefficients[i + j] + x * y) % &self.p;
+            }
+        }
+        Polynomial::new(coefficients, &self.p)
+    }
+
+    /// Quotient and remainder of the division by a non-zero divisor.
+    pub fn div_rem(&self, divisor: &Polynomial) -> (Polynomial, Polynomial) {
+        let p = &self.p;
+        let lead_inverse = self.inverse(divisor.coefficients.last().unwrap());
+        let mut remainder = self.coefficients.clone();
+        let shift = self.coefficients.len() as isize - divisor.coefficients.len() as isize;
+        let mut quotient = vec![BigUint::zero(); (shift + 1).max(0) as usize];
+        for i in (0..=shift).rev() {
+            let i = i as usize;
+            let top = &remainder[i + divisor.coefficients.len() - 1];
+            let factor = top * &lead_inverse % p;
+            for (j, c) in divisor.coefficients.iter().enumerate() {
+                remainder[i + j] = (&remainder[i + j] + p - &factor * c % p) % p;
+            }
+            quotient[i] = factor;
+        }
+        (Polynomial::new(quotient, p), Polynomial::new(remainder, p))
+    }
+
+    fn monic(&self) -> Polynomial {
+        let inverse = self.inverse(self.coefficients.last().unwrap());
+        let coefficients = self.coefficients.iter().map(|c| c * &inverse).collect();
+        Polynomial::new(coefficients, &self.p)
+    }
+
+    pub fn gcd(&self, other: &Polynomial) -> Polynomial {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let (_, remainder) = a.div_rem(&b);
+            a = b;
+            b = remainder;
+        }
+        if a.is_zero() {
+            a
+        } else {
+            a.monic()
+        }
+    }
+
+    /// self^e mod modulus by square and multiply.
+    fn pow_mod(&self, e: &BigUint, modulus: &Polynomial) -> Polynomial {
+        let mut result = Polynomial::new(vec![BigUint::one()], &self.p);
+        let base = self.div_rem(modulus).1;
+        for i in (0..e.bits()).rev() {
+            result = result.mul(&result).div_rem(modulus).1;
+            if e.bit(i) {
+                result = result.mul(&base).div_rem(modulus).1;
+            }
+        }
+        result
+    }
+
+    /// All roots in F_p of a polynomial over F_p, p an odd prime: the split
+    /// part gcd(f, X^p - X), broken up by Cantor–Zassenhaus with
+    /// gcd(g, (X + δ)^((p-1)/2) - 1) for δ = 0, 1, 2, ...
+    pub fn roots(&self) -> Vec<BigUint> {
+        let x = Polynomial::new(vec![BigUint::zero(), BigUint::one()], &self.p);
+        if self.degree() < 1 {
+            return Vec::new();
+        }
+        let split = self.gcd(&x.pow_mod(&self.p, self).sub(&x));
+        let mut roots = Vec::new();
+        split.split_roots(&mut roots);
+        roots.sort();
+        roots
+    }
+
+    fn split_roots(&self, roots: &mut Vec<BigUint>) {
+        let p = &self.p;
+        match self.degree() {
+            d if d < 1 => return,
+            1 => {
+                let root = (p - &self.coefficients[0]) % p * self.inverse(&self.coefficients[1]);
+                roots.push(root % p);
+                return;
+            }
+            _ => {}
+        }
+        let one = Polynomial::new(vec![BigUint::one()], p);
+        let exponent = (p - 1u32) >> 1;
+        let mut delta = BigUint::zero();
+        loop {
+            let shifted = Polynomial::new(vec![delta.clone(), BigUint::one()], p);
+            let factor = self.gcd(&shifted.pow_mod(&exponent, self).sub(&one));
+            if factor.degree() > 0 && factor.degree() < self.degree() {
+                factor.split_roots(roots);
+                self.div_rem(&factor).0.split_roots(roots);
+                return;
+            }
+            delta += 1u32;
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::fmt;
+use std::rc::Rc;
+
+use crate::field::{ExtensionField, FieldElement};
+
+/// An affine point over F_q, or the point at infinity.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub enum CurvePoint {
+    Infinity,
+    Affine(FieldElement, FieldElement),
+}
+
+impl CurvePoint {
+    pub fn is_infinity(&self) -> bool {
+        matches!(self, CurvePoint::Infinity)
+    }
+
+    pub fn x(&self) -> Option<&FieldElement> {
+        match self {
+            CurvePoint::Infinity => None,
+            CurvePoint::Affine(x, _) => Some(x),
+        }
+    }
+
+    /// Applies the p-power Frobenius to both coordinates.
+    pub fn frobenius(&self) -> CurvePoint {
+        match self {
+            CurvePoint::Infinity => CurvePoint::Infinity,
+            CurvePoint::Affine(x, y) => CurvePoint::Affine(x.frobenius(), y.frobenius()),
+        }
+    }
+}
+
+impl fmt::Debug for CurvePoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurvePoint::Infinity => write!(f, "O"),
+            CurvePoint::Affine(x, y) => write!(f, "({}, {})", x, y),
+        }
+    }
+}
+
+/// y² = x³ + ax + b with a, b in F_p, considered over an extension F_q.
+#[derive(Clone)]
+pub struct Curve {
+    pub a: FieldElement,
+    pub b: FieldElement,
+    pub field: Rc<ExtensionField>,
+}
+
+impl Curve {
+    pub fn new(a: i64, b: i64, field: &Rc<ExtensionField>) -> Self {
+        Curve {
+            a: field.from_int(a),
+            b: field.from_int(b),
+            field: Rc::clone(field),
+        }
+    }
+
+    /// The same curve with its coefficients lifted into a larger field.
+    pub fn over(&self, field: &Rc<ExtensionField>) -> Self {
+        Curve::new(self.a.coeffs[0], self.b.coeffs[0], field)
+    }
+
+    /// x³ + ax + b
+    pub fn rhs(&self, x: &FieldElement) -> FieldElement {
+        x.square() * x.clone() + self.a.clone() * x.clone() + self.b.clone()
+    }
+
+    pub fn contains(&self, point: &CurvePoint) -> bool {
+        match point {
+            CurvePoint::Infinity => true,
+            CurvePoint::Affine(x, y) => y.square() == self.rhs(x),
+        }
+    }
+
+    pub fn neg(&self, point: &CurvePoint) -> CurvePoint {
+        match point {
+            CurvePoint::Infinity => CurvePoint::Infinity,
+            CurvePoint::Affine(x, y) => CurvePoint::Affine(x.clone(), -y.clone()),
+        }
+    }
+
+    pub fn add(&self, p: &CurvePoint, q: &CurvePoint) -> CurvePoint {
+        let (x1, y1, x2, y2) = match (p, q) {
+            (CurvePoint::Infinity, _) => return q.clone(),
+            (_, CurvePoint::Infinity) => return p.clone(),
+            (CurvePoint::Affine(x1, y1), CurvePoint::Affine(x2, y2)) => (x1, y1, x2, y2),
+        };
+        if x1 == x2 && (y1.clone() + y2.clone()).is_zero() {
+            return CurvePoint::Infinity;
+        }
+
+        let lambda = if x1 == x2 {
+            let numerator = x1.square().scale(3) + self.a.clone();
+            numerator * y1.scale(2).inverse().unwrap()
+        } else {
+            (y2.clone() - y1.clone()) * (x2.clone() - x1.clone()).inverse().unwrap()
+        };
+        let x3 = lambda.square() - x1.clone() - x2.clone();
+        let y3 = lambda * (x1.clone() - x3.clone()) - y1.clone();
+        CurvePoint::Affine(x3, y3)
+    }
+
+    pub fn double(&self, p: &CurvePoint) -> CurvePoint {
+        self.add(p, p)
+    }
+
+    pub fn multiply(&self, k: u128, p: &CurvePoint) -> CurvePoint {
+        let mut result = CurvePoint::Infinity;
+        let mut addend = p.clone();
+        let mut k = k;
+        while k > 0 {
+            if k & 1 == 1 {
+                result = self.add(&result, &addend);
+            }
+            addend = self.double(&addend);
+            k >>= 1;
+        }
+        result
+    }
+
+    /// The points with the given x-coordinate, empty if x³ + ax + b is not a square.
+    pub fn points_with_x(&self, x: &FieldElement) -> Vec<CurvePoint> {
+        match self.rhs(x).sqrt() {
+            None => Vec::new(),
+            Some(y) if y.is_zero() => vec![CurvePoint::Affine(x.clone(), y)],
+            Some(y) => vec![
+                CurvePoint::Affine(x.clone(), y.clone()),
+                CurvePoint::Affine(x.clone(), -y),
+            ],
+        }
+    }
+}
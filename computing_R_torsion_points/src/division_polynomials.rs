@@ -0,0 +1,320 @@
+use std::rc::Rc;
+
+use crate::curve::{Curve, CurvePoint};
+use crate::field::{mul_mod, ExtensionField};
+use crate::polynomial::{factor_degrees, Polynomial};
+
+/// The division polynomials of y² = x³ + ax + b over F_p, p odd.
+///
+/// Entry n holds f_n = ψ_n for odd n and f_n = ψ_n / y for even n, so that
+/// every f_n is a polynomial in x alone; y² is replaced by x³ + ax + b.
+pub struct DivisionPolynomials {
+    pub a: i64,
+    pub b: i64,
+    pub field: Rc<ExtensionField>,
+    polynomials: Vec<Polynomial>,
+}
+
+impl DivisionPolynomials {
+    /// Computes f_0 through f_n with the standard recurrences
+    ///   ψ_{2m+1} = ψ_{m+2} ψ_m³ - ψ_{m-1} ψ_{m+1}³
+    ///   ψ_{2m}   = ψ_m (ψ_{m+2} ψ_{m-1}² - ψ_{m-2} ψ_{m+1}²) / 2y
+    pub fn new(a: i64, b: i64, p: i64, n: usize) -> Self {
+        let field = ExtensionField::prime(p);
+        let poly = |coeffs: &[i64]| Polynomial::from_ints(coeffs, &field);
+        // Every coefficient is built from products reduced mod p, so nothing
+        // overflows for p up to 2^62
+        let c = |k: i64, x: i64| mul_mod(k, x, p);
+        let a2 = mul_mod(a, a, p);
+        let a3 = mul_mod(a2, a, p);
+
+        // f = x³ + ax + b, the value of y² and the factor the recurrences trade in for it
+        let f = poly(&[b, a, 0, 1]);
+        let f_squared = f.mul(&f);
+        let half = field.from_int(2).inverse().unwrap();
+
+        let mut polynomials = vec![
+            poly(&[0]),
+            poly(&[1]),
+            poly(&[2]),
+            poly(&[-a2, c(12, b), c(6, a), 0, 3]),
+            poly(&[
+                -c(8, mul_mod(b, b, p)) - a3,
+                -c(4, mul_mod(a, b, p)),
+                -c(5, a2),
+                c(20, b),
+                c(5, a),
+                0,
+                1,
+            ])
+            .scale(&field.from_int(4)),
+        ];
+
+        for i in 5..=n {
+            let m = i / 2;
+            let fm = |j: usize| &polynomials[j];
+            let next = if i % 2 == 1 {
+                let mut first = fm(m + 2).mul(&cube(fm(m)));
+                let mut second = fm(m - 1).mul(&cube(fm(m + 1)));
+                // The even-indexed factors each carried a y, contributing y⁴ = f²
+                if m % 2 == 0 {
+                    first = first.mul(&f_squared);
+                } else {
+                    second = second.mul(&f_squared);
+                }
+                first.sub(&second)
+            } else {
+                let first = fm(m + 2).mul(&fm(m - 1).mul(fm(m - 1)));
+                let second = fm(m - 2).mul(&fm(m + 1).mul(fm(m + 1)));
+                fm(m).mul(&first.sub(&second)).scale(&half)
+            };
+            polynomials.push(next);
+        }
+        polynomials.truncate(n + 1);
+
+        DivisionPolynomials {
+            a,
+            b,
+            field,
+            polynomials,
+        }
+    }
+
+    /// f_n, i.e. ψ_n with the factor y dropped when n is even.
+    pub fn get(&self, n: usize) -> &Polynomial {
+        &self.polynomials[n]
+    }
+
+    /// The polynomial whose roots are the x-coordinates of E[n] \ {O}: f_n
+    /// for odd n, and f_n (x³ + ax + b) for even n to include the 2-torsion.
+    pub fn torsion_x_polynomial(&self, n: usize) -> Polynomial {
+        let f_n = self.get(n).clone();
+        if n % 2 == 1 {
+            f_n
+        } else {
+            f_n.mul(&Polynomial::from_ints(&[self.b, self.a, 0, 1], &self.field))
+        }
+    }
+}
+
+fn cube(f: &Polynomial) -> Polynomial {
+    f.mul(f).mul(f)
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The r-torsion points of E defined over F_{p^k}: the point at infinity
+/// plus the lifts of every root of the torsion x-polynomial lying in F_{p^k}.
+pub fn r_torsion_over(
+    a: i64,
+    b: i64,
+    p: i64,
+    r: usize,
+    k: usize,
+) -> (Rc<ExtensionField>, Vec<CurvePoint>) {
+    let division = DivisionPolynomials::new(a, b, p, r);
+    let field = ExtensionField::new(p, k);
+    let curve = Curve::new(a, b, &field);
+    let points = torsion_points_in(&division, r, &curve);
+    (field, points)
+}
+
+fn torsion_points_in(division: &DivisionPolynomials, r: usize, curve: &Curve) -> Vec<CurvePoint> {
+    let base = division.torsion_x_polynomial(r);
+    let lifted = Polynomial::new(
+        base.coeffs
+            .iter()
+            .map(|c| curve.field.from_int(c.coeffs[0]))
+            .collect(),
+        &curve.field,
+    );
+
+    let mut points = vec![CurvePoint::Infinity];
+    for x in lifted.roots() {
+        points.extend(curve.points_with_x(&x));
+    }
+    points
+}
+
+/// The degree L of the field generated by the x-coordinates of E[r]: the
+/// lcm of the degrees of the irreducible factors of the torsion x-polynomial.
+fn x_coordinate_degree(division: &DivisionPolynomials, r: usize) -> usize {
+    let degrees = factor_degrees(&division.torsion_x_polynomial(r));
+    degrees.iter().fold(1, |l, &(d, _)| l / gcd(l, d) * d)
+}
+
+/// The smallest k with E[r] ⊆ E(F_{p^k}), for r coprime to p. The
+/// y-coordinates need at most one quadratic extension beyond F_{p^L}.
+pub fn torsion_field_degree(a: i64, b: i64, p: i64, r: usize) -> usize {
+    let division = DivisionPolynomials::new(a, b, p, r);
+    let l = x_coordinate_degree(&division, r);
+    let field = ExtensionField::new(p, l);
+    let curve = Curve::new(a, b, &field);
+    if torsion_points_in(&division, r, &curve).len() == r * r {
+        l
+    } else {
+        2 * l
+    }
+}
+
+/// The full r-torsion subgroup E[r] ≅ Z/r × Z/r, computed in the smallest
+/// extension field containing it. Fails if that field is larger than
+/// F_{p^max_degree}.
+pub fn full_r_torsion(
+    a: i64,
+    b: i64,
+    p: i64,
+    r: usize,
+    max_degree: usize,
+) -> Result<(Rc<ExtensionField>, Vec<CurvePoint>), &'static str> {
+    if r as i64 % p == 0 {
+        return Err("r must be coprime to the characteristic");
+    }
+    let division = DivisionPolynomials::new(a, b, p, r);
+    let l = x_coordinate_degree(&division, r);
+
+    for k in [l, 2 * l] {
+        if k > max_degree {
+            return Err("the r-torsion lives in an extension above the degree limit");
+        }
+        let field = ExtensionField::new(p, k);
+        let curve = Curve::new(a, b, &field);
+        let points = torsion_points_in(&division, r, &curve);
+        if points.len() == r * r {
+            return Ok((field, points));
+        }
+    }
+    Err("did not find r² torsion points")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::FieldElement;
+    use std::collections::HashSet;
+
+    /// Coefficients of a polynomial over F_p as integers.
+    fn ints(f: &Polynomial) -> Vec<i64> {
+        f.coeffs.iter().map(|c| c.coeffs[0]).collect()
+    }
+
+    /// Reduces closed-form coefficients computed in i128.
+    fn reduce(coeffs: &[i128], p: i64) -> Vec<i64> {
+        let mut reduced: Vec<i64> = coeffs
+            .iter()
+            .map(|c| c.rem_euclid(p as i128) as i64)
+            .collect();
+        while reduced.last() == Some(&0) {
+            reduced.pop();
+        }
+        reduced
+    }
+
+    #[test]
+    fn psi_3_and_psi_4_match_their_closed_forms() {
+        // A prime past 2^32 with coefficients of both signs, where the
+        // unreduced products overflow an i64
+        let p = 4_294_967_311;
+        for (a, b) in [
+            (-3, p - 5),
+            (p - 1, p - 2),
+            (2, 3),
+            (123_456_789, -987_654_321),
+        ] {
+            let division = DivisionPolynomials::new(a, b, p, 4);
+            let (a, b) = (a as i128, b as i128);
+            let psi_3 = [-a * a, 12 * b, 6 * a, 0, 3];
+            assert_eq!(ints(division.get(3)), reduce(&psi_3, p));
+            let psi_4: Vec<i128> = [
+                -8 * b * b - a * a % p as i128 * a,
+                -4 * a * b,
+                -5 * a * a,
+                20 * b,
+                5 * a,
+                0,
+                1,
+            ]
+            .iter()
+            .map(|c| c % p as i128 * 4)
+            .collect();
+            assert_eq!(ints(division.get(4)), reduce(&psi_4, p));
+        }
+    }
+
+    /// E(F_{p^k})[r] by trying every x in the field.
+    fn brute_force_torsion(curve: &Curve, r: usize) -> HashSet<CurvePoint> {
+        curve
+            .field
+            .elements()
+            .iter()
+            .flat_map(|x| curve.points_with_x(x))
+            .chain([CurvePoint::Infinity])
+            .filter(|point| curve.multiply(r as u128, point).is_infinity())
+            .collect()
+    }
+
+    #[test]
+    fn roots_are_the_x_coordinates_of_the_torsion() {
+        for (a, b, p, r) in [
+            (1, 1, 5, 3),
+            (1, 0, 11, 3),
+            (2, 3, 7, 3),
+            (1, 1, 5, 2),
+            (1, 1, 7, 5),
+        ] {
+            let (field, points) = full_r_torsion(a, b, p, r, 4).unwrap();
+            let curve = Curve::new(a, b, &field);
+            let expected = brute_force_torsion(&curve, r);
+
+            let division = DivisionPolynomials::new(a, b, p, r);
+            let base = division.torsion_x_polynomial(r);
+            let lifted = Polynomial::new(
+                base.coeffs
+                    .iter()
+                    .map(|c| field.from_int(c.coeffs[0]))
+                    .collect(),
+                &field,
+            );
+            let roots: HashSet<FieldElement> = lifted.roots().into_iter().collect();
+            let xs: HashSet<FieldElement> =
+                expected.iter().filter_map(|q| q.x().cloned()).collect();
+            assert_eq!(
+                roots, xs,
+                "y² = x³ + {}x + {} over F_{}, r = {}",
+                a, b, p, r
+            );
+            assert_eq!(points.into_iter().collect::<HashSet<_>>(), expected);
+        }
+    }
+
+    #[test]
+    fn full_r_torsion_has_r_squared_points() {
+        for (a, b, p, r) in [
+            (1, 1, 5, 3),
+            (1, 1, 7, 2),
+            (1, 1, 7, 5),
+            (8, 8, 13, 5),
+            (1, 6, 17, 5),
+            (3, 5, 19, 3),
+        ] {
+            let (field, points) = full_r_torsion(a, b, p, r, 12).unwrap();
+            assert_eq!(points.len(), r * r, "y² = x³ + {}x + {} over F_{}", a, b, p);
+            assert_eq!(
+                points.iter().collect::<HashSet<_>>().len(),
+                r * r,
+                "duplicate points"
+            );
+            assert_eq!(field.k, torsion_field_degree(a, b, p, r));
+        }
+        assert_eq!(
+            full_r_torsion(1, 1, 5, 5, 12).err(),
+            Some("r must be coprime to the characteristic")
+        );
+    }
+}
@@ -0,0 +1,353 @@
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::rc::Rc;
+
+/// The finite field F_{p^k} = F_p[z] / (g(z)) for a monic irreducible g of degree k.
+/// With k = 1 and g(z) = z this is the prime field itself.
+#[derive(Debug, PartialEq, Eq)]
+pub struct ExtensionField {
+    pub p: i64,
+    pub k: usize,
+    /// Coefficients of g, lowest degree first, with the leading 1 included
+    pub modulus: Vec<i64>,
+}
+
+/// An element of an extension field, stored as a polynomial in z of degree < k.
+#[derive(Clone)]
+pub struct FieldElement {
+    pub coeffs: Vec<i64>,
+    pub field: Rc<ExtensionField>,
+}
+
+pub fn mul_mod(a: i64, b: i64, p: i64) -> i64 {
+    (a as i128 * b as i128).rem_euclid(p as i128) as i64
+}
+
+pub fn pow_mod(base: i64, exponent: u128, p: i64) -> i64 {
+    let mut result = 1;
+    let mut base = base.rem_euclid(p);
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = mul_mod(result, base, p);
+        }
+        base = mul_mod(base, base, p);
+        exponent >>= 1;
+    }
+    result
+}
+
+pub fn inv_mod(a: i64, p: i64) -> Option<i64> {
+    let a = a.rem_euclid(p);
+    if a == 0 {
+        return None;
+    }
+    Some(pow_mod(a, (p - 2) as u128, p))
+}
+
+impl ExtensionField {
+    pub fn prime(p: i64) -> Rc<Self> {
+        Rc::new(ExtensionField {
+            p,
+            k: 1,
+            modulus: vec![0, 1],
+        })
+    }
+
+    /// F_{p^k} built from the first irreducible polynomial of degree k found by
+    /// enumerating monic polynomials in lexicographic order.
+    pub fn new(p: i64, k: usize) -> Rc<Self> {
+        if k == 1 {
+            return Self::prime(p);
+        }
+        let modulus = crate::polynomial::find_irreducible(p, k);
+        Rc::new(ExtensionField { p, k, modulus })
+    }
+
+    /// The number of elements p^k, as long as it fits in a u128.
+    pub fn order(&self) -> u128 {
+        (self.p as u128).pow(self.k as u32)
+    }
+
+    pub fn zero(self: &Rc<Self>) -> FieldElement {
+        FieldElement {
+            coeffs: vec![0; self.k],
+            field: Rc::clone(self),
+        }
+    }
+
+    pub fn one(self: &Rc<Self>) -> FieldElement {
+        self.from_int(1)
+    }
+
+    pub fn from_int(self: &Rc<Self>, value: i64) -> FieldElement {
+        let mut element = self.zero();
+        element.coeffs[0] = value.rem_euclid(self.p);
+        element
+    }
+
+    /// The generator z of the extension, i.e. the class of z mod g(z).
+    pub fn z(self: &Rc<Self>) -> FieldElement {
+        if self.k == 1 {
+            return self.from_int(-self.modulus[0]);
+        }
+        let mut element = self.zero();
+        element.coeffs[1] = 1;
+        element
+    }
+
+    pub fn from_coeffs(self: &Rc<Self>, coeffs: &[i64]) -> FieldElement {
+        let mut power = self.one();
+        let z = self.z();
+        let mut element = self.zero();
+        for &c in coeffs {
+            element = element + power.scale(c);
+            power = power * z.clone();
+        }
+        element
+    }
+
+    /// Every element of the field, for brute-force checks on tiny fields.
+    pub fn elements(self: &Rc<Self>) -> Vec<FieldElement> {
+        let total = self.order() as usize;
+        (0..total)
+            .map(|mut index| {
+                let mut element = self.zero();
+                for c in element.coeffs.iter_mut() {
+                    *c = (index % self.p as usize) as i64;
+                    index /= self.p as usize;
+                }
+                element
+            })
+            .collect()
+    }
+
+    /// A pseudo-random element drawn from a simple xorshift state.
+    pub fn random(self: &Rc<Self>, state: &mut u64) -> FieldElement {
+        let mut element = self.zero();
+        for c in element.coeffs.iter_mut() {
+            *state ^= *state << 13;
+            *state ^= *state >> 7;
+            *state ^= *state << 17;
+            *c = (*state % self.p as u64) as i64;
+        }
+        element
+    }
+}
+
+impl FieldElement {
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.iter().all(|&c| c == 0)
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.coeffs[0] == 1 && self.coeffs[1..].iter().all(|&c| c == 0)
+    }
+
+    /// True when the element lies in the prime field F_p.
+    pub fn in_prime_field(&self) -> bool {
+        self.coeffs[1..].iter().all(|&c| c == 0)
+    }
+
+    pub fn scale(&self, c: i64) -> FieldElement {
+        let p = self.field.p;
+        FieldElement {
+            coeffs: self.coeffs.iter().map(|&x| mul_mod(x, c, p)).collect(),
+            field: Rc::clone(&self.field),
+        }
+    }
+
+    pub fn square(&self) -> FieldElement {
+        self.clone() * self.clone()
+    }
+
+    pub fn pow(&self, exponent: u128) -> FieldElement {
+        let mut result = self.field.one();
+        let mut base = self.clone();
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result * base.clone();
+            }
+            base = base.square();
+            exponent >>= 1;
+        }
+        result
+    }
+
+    /// The inverse by Fermat's little theorem, `None` for zero.
+    pub fn inverse(&self) -> Option<FieldElement> {
+        if self.is_zero() {
+            return None;
+        }
+        Some(self.pow(self.field.order() - 2))
+    }
+
+    /// The p-power Frobenius map x -> x^p.
+    pub fn frobenius(&self) -> FieldElement {
+        self.pow(self.field.p as u128)
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.is_zero() || self.pow((self.field.order() - 1) / 2).is_one()
+    }
+
+    /// A square root by Tonelli–Shanks over F_q, `None` for non-squares.
+    pub fn sqrt(&self) -> Option<FieldElement> {
+        if self.is_zero() {
+            return Some(self.clone());
+        }
+        if !self.is_square() {
+            return None;
+        }
+        let q = self.field.order();
+        let mut odd = q - 1;
+        let mut s = 0;
+        while odd.is_multiple_of(2) {
+            odd /= 2;
+            s += 1;
+        }
+
+        // Deterministic search for a non-square
+        let mut state = 0x9e3779b97f4a7c15;
+        let non_square = loop {
+            let candidate = self.field.random(&mut state);
+            if !candidate.is_square() {
+                break candidate;
+            }
+        };
+
+        let mut m = s;
+        let mut c = non_square.pow(odd);
+        let mut t = self.pow(odd);
+        let mut r = self.pow(odd.div_ceil(2));
+        while !t.is_one() {
+            let mut i = 0;
+            let mut t2i = t.clone();
+            while !t2i.is_one() {
+                t2i = t2i.square();
+                i += 1;
+            }
+            let mut b = c.clone();
+            for _ in 0..m - i - 1 {
+                b = b.square();
+            }
+            m = i;
+            c = b.square();
+            t = t * c.clone();
+            r = r * b;
+        }
+        Some(r)
+    }
+}
+
+impl PartialEq for FieldElement {
+    fn eq(&self, other: &Self) -> bool {
+        self.coeffs == other.coeffs
+    }
+}
+
+impl Eq for FieldElement {}
+
+impl std::hash::Hash for FieldElement {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.coeffs.hash(state);
+    }
+}
+
+impl Add for FieldElement {
+    type Output = FieldElement;
+    fn add(self, other: FieldElement) -> FieldElement {
+        let p = self.field.p;
+        let coeffs = self
+            .coeffs
+            .iter()
+            .zip(&other.coeffs)
+            .map(|(&a, &b)| (a + b) % p)
+            .collect();
+        FieldElement {
+            coeffs,
+            field: self.field,
+        }
+    }
+}
+
+impl Sub for FieldElement {
+    type Output = FieldElement;
+    fn sub(self, other: FieldElement) -> FieldElement {
+        self + (-other)
+    }
+}
+
+impl Neg for FieldElement {
+    type Output = FieldElement;
+    fn neg(self) -> FieldElement {
+        let p = self.field.p;
+        let coeffs = self.coeffs.iter().map(|&a| (p - a) % p).collect();
+        FieldElement {
+            coeffs,
+            field: self.field,
+        }
+    }
+}
+
+impl Mul for FieldElement {
+    type Output = FieldElement;
+    fn mul(self, other: FieldElement) -> FieldElement {
+        let field = &self.field;
+        let (p, k) = (field.p, field.k);
+        let mut product = vec![0i64; 2 * k - 1];
+        for (i, &a) in self.coeffs.iter().enumerate() {
+            if a == 0 {
+                continue;
+            }
+            for (j, &b) in other.coeffs.iter().enumerate() {
+                product[i + j] = (product[i + j] + mul_mod(a, b, p)) % p;
+            }
+        }
+        // Reduce with z^k = -(g_0 + g_1 z + ... + g_{k-1} z^{k-1})
+        for i in (k..product.len()).rev() {
+            let c = product[i];
+            if c == 0 {
+                continue;
+            }
+            product[i] = 0;
+            for j in 0..k {
+                let reduced = mul_mod(c, field.modulus[j], p);
+                product[i - k + j] = (product[i - k + j] - reduced).rem_euclid(p);
+            }
+        }
+        product.truncate(k);
+        FieldElement {
+            coeffs: product,
+            field: Rc::clone(field),
+        }
+    }
+}
+
+impl fmt::Display for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let terms: Vec<String> = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .filter(|(_, &c)| c != 0)
+            .map(|(i, &c)| match i {
+                0 => format!("{}", c),
+                1 => format!("{}z", c),
+                _ => format!("{}z^{}", c, i),
+            })
+            .collect();
+        if terms.is_empty() {
+            write!(f, "0")
+        } else {
+            write!(f, "{}", terms.join(" + "))
+        }
+    }
+}
+
+impl fmt::Debug for FieldElement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
@@ -0,0 +1,10 @@
+//! Torsion points of elliptic curves over prime fields and their extensions,
+//! computed from division polynomials.
+
+pub mod curve;
+pub mod division_polynomials;
+pub mod field;
+pub mod mov_attack;
+pub mod pairing;
+pub mod polynomial;
+pub mod torsion_basis;
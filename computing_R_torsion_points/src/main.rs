@@ -1,10 +1,16 @@
+use r_torsion::curve::{Curve, CurvePoint};
+use r_torsion::division_polynomials::{full_r_torsion, r_torsion_over, DivisionPolynomials};
+use r_torsion::field::ExtensionField;
+use r_torsion::mov_attack::{mov_attack, mov_transfer};
+use r_torsion::pairing::{tate_pairing, verify_tate_pairing, weil_pairing};
+use r_torsion::torsion_basis::{embedding_degree, TorsionBasis};
 use std::fmt;
 
 #[derive(Clone, Copy)]
-struct Point{
+struct Point {
     x: i64,
     y: i64,
-    infinity: bool
+    infinity: bool,
 }
 
 impl fmt::Debug for Point {
@@ -22,7 +28,11 @@ impl Point {
         self.infinity
     }
     fn at_inifinity() -> Self {
-        Point { x: 0, y: 0, infinity: true }
+        Point {
+            x: 0,
+            y: 0,
+            infinity: true,
+        }
     }
 }
 
@@ -60,7 +70,11 @@ fn elliptic_add(p: &Point, q: &Point, a: i64, m: i64) -> Point {
     };
     let x = ((s * s - p.x - q.x) % m + m) % m;
     let y = ((s * (p.x - x) - p.y) % m + m) % m;
-    Point { x, y, infinity: false }
+    Point {
+        x,
+        y,
+        infinity: false,
+    }
 }
 
 fn scalar_mult(n: i64, p: &Point, a: i64, m: i64) -> Point {
@@ -90,33 +104,165 @@ fn find_r_torsion_points(r: i64, a: i64, b: i64, m: i64) -> Vec<Point> {
     let mut torsion_points = vec![Point::at_inifinity()];
     for x in 0..m {
         for y in 0..m {
-            let p = Point { x, y, infinity: false };
-            if is_on_curve(&p, a, b, m) {
-                let mut q = p;
-                for i in 1..r {
-                    q = elliptic_add(&q, &p, a, m);
-                    if q.is_at_infinity() {
-                        break;
-                    }
-                }
-                if q.is_at_infinity() {
-                    torsion_points.push(p);
-                }
+            let p = Point {
+                x,
+                y,
+                infinity: false,
+            };
+            if is_on_curve(&p, a, b, m) && scalar_mult(r, &p, a, m).is_at_infinity() {
+                torsion_points.push(p);
             }
         }
     }
     torsion_points
 }
 
-
-
 fn main() {
     let a = 8;
     let b = 8;
     let m = 13;
     let r = 5;
     let torsion_points = find_r_torsion_points(r, a, b, m);
-    for p in torsion_points {
+    println!("{}-torsion over F_{} by brute force:", r, m);
+    for p in &torsion_points {
         println!("{:?}", p);
     }
-}
\ No newline at end of file
+
+    let division = DivisionPolynomials::new(a, b, m, r as usize);
+    println!("\nψ_{} = {}", r, division.get(r as usize));
+
+    // The roots of ψ_r in F_p give the same points without the p² search
+    let (_, rational) = r_torsion_over(a, b, m, r as usize, 1);
+    println!(
+        "{}-torsion over F_{} from ψ_{}: {} points",
+        r,
+        m,
+        r,
+        rational.len()
+    );
+    for point in &rational {
+        if let CurvePoint::Affine(x, y) = point {
+            println!("({}, {})", x, y);
+        }
+    }
+
+    match full_r_torsion(a, b, m, r as usize, 24) {
+        Ok((field, points)) => {
+            println!(
+                "\nE[{}] has {} points over F_{}^{} = F_{}[z]/({:?})",
+                r,
+                points.len(),
+                m,
+                field.k,
+                m,
+                field.modulus
+            );
+            for point in points.iter().take(6) {
+                println!("{:?}", point);
+            }
+            println!("...");
+        }
+        Err(e) => println!("Could not compute E[{}]: {}", r, e),
+    }
+
+    // E[r] is defined over F_{p^k} for the embedding degree k when r ∤ p - 1
+    let k = embedding_degree(m, r as u64).unwrap();
+    println!("\nEmbedding degree of r = {} for p = {}: k = {}", r, m, k);
+    match TorsionBasis::new(a, b, m, r as u64, k) {
+        Ok(basis) => {
+            println!(
+                "Basis of E[{}]: P1 = {:?}, P2 = {:?}",
+                r, basis.p1, basis.p2
+            );
+            println!(
+                "Frobenius matrix in this basis: {:?}",
+                basis.frobenius_matrix()
+            );
+            println!("G1 (π = 1) generated by {:?}", basis.g1);
+            println!("G2 (π = {}) generated by {:?}", m % r, basis.g2);
+            for point in basis.g2_points().iter().skip(1) {
+                println!(
+                    "  {:?} = {:?} in the basis",
+                    point,
+                    basis.coordinates(point).unwrap()
+                );
+            }
+        }
+        Err(e) => println!("Could not build a basis of E[{}]: {}", r, e),
+    }
+
+    // The Weil pairing on E[r] for a few small curves, with r | #E(F_p)
+    println!();
+    let curves = [
+        (8, 8, 13, 5),
+        (1, 1, 5, 3),
+        (1, 1, 7, 5),
+        (1, 6, 17, 5),
+        (3, 5, 19, 3),
+    ];
+    for (a, b, p, r) in curves {
+        let basis = match embedding_degree(p, r).and_then(|k| TorsionBasis::new(a, b, p, r, k)) {
+            Ok(basis) => basis,
+            Err(e) => {
+                println!("y² = x³ + {}x + {} over F_{}: {}", a, b, p, e);
+                continue;
+            }
+        };
+        let e = weil_pairing(&basis.curve, r as u128, &basis.p1, &basis.p2);
+        println!(
+            "y² = x³ + {}x + {} over F_{}^{}: e_{}(P1, P2) = {}",
+            a, b, p, basis.k, r, e
+        );
+        let t = tate_pairing(&basis.curve, r as u128, &basis.g1, &basis.g2);
+        let check = match verify_tate_pairing(&basis) {
+            Ok(()) => "bilinear, non-degenerate, consistent with Weil".to_string(),
+            Err(e) => format!("FAILED: {}", e),
+        };
+        println!("    reduced Tate t_{}(G1, G2) = {} ({})", r, t, check);
+    }
+
+    // The MOV / Frey–Rück attack: supersingular y² = x³ + x with p ≡ 3 (mod 4)
+    // has p + 1 points and embedding degree 2, so its ECDLP falls to a
+    // discrete logarithm in F_{p^2}
+    println!();
+    for p in [1_000_171i64, 1_000_000_411] {
+        let field = ExtensionField::prime(p);
+        let curve = Curve::new(1, 0, &field);
+        let r = (p as u64 + 1) / 4;
+        let base = (1..)
+            .flat_map(|x| curve.points_with_x(&field.from_int(x)))
+            .map(|point| curve.multiply(4, &point))
+            .find(|point| !point.is_infinity())
+            .unwrap();
+        let secret = 123_456_789 % r;
+        let target = curve.multiply(secret as u128, &base);
+
+        println!("y² = x³ + x over F_{}, r = {}", p, r);
+        println!("P = {:?}, Q = [n]P = {:?}", base, target);
+        match mov_transfer(&curve, r, &base, &target, 6) {
+            Ok(transfer) => println!(
+                "k = {}: t(P, S) = {}, t(Q, S) = {} in F_{}^{}",
+                transfer.k, transfer.alpha, transfer.beta, p, transfer.k
+            ),
+            Err(e) => println!("Transfer failed: {}", e),
+        }
+        match mov_attack(&curve, r, &base, &target, 6) {
+            Ok(n) => println!("recovered n = {} (secret was {})", n, secret),
+            Err(e) => println!("MOV attack failed: {}", e),
+        }
+    }
+
+    // An ordinary curve with huge embedding degree resists the transfer
+    let field = ExtensionField::prime(1_000_003);
+    let curve = Curve::new(2, 5, &field);
+    let base = (1..)
+        .flat_map(|x| curve.points_with_x(&field.from_int(x)))
+        .map(|point| curve.multiply(250, &point))
+        .find(|point| !point.is_infinity())
+        .unwrap();
+    let target = curve.multiply(1000, &base);
+    match mov_attack(&curve, 4003, &base, &target, 6) {
+        Ok(n) => println!("\ny² = x³ + 2x + 5 over F_1000003: n = {}", n),
+        Err(e) => println!("\ny² = x³ + 2x + 5 over F_1000003: {}", e),
+    }
+}
@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use integer_factorization::Factorization;
+use num_bigint::BigUint;
+
+use crate::curve::{Curve, CurvePoint};
+use crate::field::{ExtensionField, FieldElement};
+use crate::pairing::tate_pairing;
+use crate::torsion_basis::embedding_degree;
+
+/// An ECDLP instance Q = [n]P moved into μ_r ⊂ F_{p^k}^* by the pairing:
+/// with S a fixed auxiliary point, alpha = t(P, S) and beta = t(Q, S) = alpha^n.
+pub struct MovTransfer {
+    pub k: usize,
+    pub field: Rc<ExtensionField>,
+    pub auxiliary: CurvePoint,
+    pub alpha: FieldElement,
+    pub beta: FieldElement,
+}
+
+/// The same point with its coordinates viewed in the larger field.
+fn lift(point: &CurvePoint, field: &Rc<ExtensionField>) -> CurvePoint {
+    match point {
+        CurvePoint::Infinity => CurvePoint::Infinity,
+        CurvePoint::Affine(x, y) => {
+            CurvePoint::Affine(field.from_int(x.coeffs[0]), field.from_int(y.coeffs[0]))
+        }
+    }
+}
+
+/// Moves the discrete logarithm of `target` to the base `point`, both of
+/// prime order r on y² = x³ + ax + b over F_p, into F_{p^k} with k the
+/// embedding degree. Fails if k exceeds `max_degree`, which is exactly
+/// when the attack stops being a threat.
+pub fn mov_transfer(
+    curve: &Curve,
+    r: u64,
+    point: &CurvePoint,
+    target: &CurvePoint,
+    max_degree: usize,
+) -> Result<MovTransfer, &'static str> {
+    let p = curve.field.p;
+    if curve.field.k != 1 {
+        return Err("the curve must be given over its prime field");
+    }
+    if !curve.contains(point) || !curve.contains(target) {
+        return Err("point is not on the curve");
+    }
+    if point.is_infinity() || !curve.multiply(r as u128, point).is_infinity() {
+        return Err("the base point must have order r");
+    }
+    let k = embedding_degree(p, r)?;
+    if k > max_degree {
+        return Err("the embedding degree is too large for the transfer");
+    }
+
+    let field = ExtensionField::new(p, k);
+    let extended = curve.over(&field);
+    let (point, target) = (lift(point, &field), lift(target, &field));
+
+    // t(P, ·) is non-trivial on most points of E(F_{p^k}), so a few random
+    // choices of S give a primitive r-th root of unity alpha
+    let mut state = 0x5851f42d4c957f2d;
+    for _ in 0..64 {
+        let x = field.random(&mut state);
+        let Some(auxiliary) = extended.points_with_x(&x).pop() else {
+            continue;
+        };
+        let alpha = tate_pairing(&extended, r as u128, &point, &auxiliary);
+        if alpha.is_one() {
+            continue;
+        }
+        let beta = tate_pairing(&extended, r as u128, &target, &auxiliary);
+        return Ok(MovTransfer {
+            k,
+            field,
+            auxiliary,
+            alpha,
+            beta,
+        });
+    }
+    Err("could not find an auxiliary point with a non-trivial pairing")
+}
+
+/// Solves Q = [n]P by the MOV / Frey–Rück reduction: transfer with the
+/// reduced Tate pairing, then take the discrete logarithm in μ_r ⊂ F_{p^k}^*.
+pub fn mov_attack(
+    curve: &Curve,
+    r: u64,
+    point: &CurvePoint,
+    target: &CurvePoint,
+    max_degree: usize,
+) -> Result<u64, &'static str> {
+    let transfer = mov_transfer(curve, r, point, target, max_degree)?;
+    let n = discrete_log(&transfer.alpha, &transfer.beta, r as u128)
+        .ok_or("target is not in the subgroup generated by the base point")?;
+    if curve.multiply(n, point) != *target {
+        return Err("the finite field logarithm does not solve the ECDLP");
+    }
+    Ok(n as u64)
+}
+
+/// x with g^x = h, where g has order n, by Pohlig–Hellman: the logarithm
+/// is found modulo each prime power q^e of n, one base-q digit at a time
+/// with baby-step giant-step, and recombined by the CRT.
+pub fn discrete_log(g: &FieldElement, h: &FieldElement, n: u128) -> Option<u128> {
+    let mut x = 0u128;
+    let mut modulus = 1u128;
+    let factorization = Factorization::of(&BigUint::from(n)).ok()?;
+    for (q, e) in factorization.factors() {
+        let (q, e) = (u128::try_from(q).ok()?, *e);
+        let q_e = q.pow(e);
+        let cofactor = n / q_e;
+        let (g_i, h_i) = (g.pow(cofactor), h.pow(cofactor));
+
+        // g_i has order q^e, and gamma = g_i^(q^(e-1)) has order q
+        let gamma = g_i.pow(q_e / q);
+        let g_i_inverse = g_i.inverse()?;
+        let mut x_i = 0u128;
+        for j in 0..e {
+            let shifted = h_i.clone() * g_i_inverse.pow(x_i);
+            let digit = bsgs(&gamma, &shifted.pow(q_e / q.pow(j + 1)), q)?;
+            x_i += digit * q.pow(j);
+        }
+        x = crt(x, modulus, x_i, q_e);
+        modulus *= q_e;
+    }
+    Some(x)
+}
+
+/// Baby-step giant-step for g^x = h with x < n, in O(√n) time and memory.
+pub fn bsgs(g: &FieldElement, h: &FieldElement, n: u128) -> Option<u128> {
+    let m = (n as f64).sqrt().ceil() as u128 + 1;
+    let mut baby_steps = HashMap::new();
+    let mut power = g.field.one();
+    for j in 0..m {
+        baby_steps.entry(power.clone()).or_insert(j);
+        power = power * g.clone();
+    }
+    let giant = g.inverse()?.pow(m);
+    let mut current = h.clone();
+    for i in 0..m {
+        if let Some(j) = baby_steps.get(&current) {
+            return Some((i * m + j) % n);
+        }
+        current = current * giant.clone();
+    }
+    None
+}
+
+/// The x mod m1 m2 with x ≡ a1 (mod m1) and x ≡ a2 (mod m2), m1 and m2 coprime.
+fn crt(a1: u128, m1: u128, a2: u128, m2: u128) -> u128 {
+    // m1^(-1) mod m2 by the extended Euclidean algorithm
+    let (mut old_r, mut r) = ((m1 % m2) as i128, m2 as i128);
+    let (mut old_s, mut s) = (1i128, 0i128);
+    while r != 0 {
+        let quotient = old_r / r;
+        (old_r, r) = (r, old_r - quotient * r);
+        (old_s, s) = (s, old_s - quotient * s);
+    }
+    let inverse = old_s.rem_euclid(m2 as i128) as u128;
+    let difference = (a2 + m2 - a1 % m2) % m2;
+    a1 + m1 * (difference * inverse % m2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A point of order r = (p + 1) / 4 on the supersingular y² = x³ + x
+    /// over F_p, p ≡ 3 (mod 4).
+    fn supersingular(p: i64) -> (Curve, u64, CurvePoint) {
+        let field = ExtensionField::prime(p);
+        let curve = Curve::new(1, 0, &field);
+        let r = (p as u64 + 1) / 4;
+        let base = (1..)
+            .flat_map(|x| curve.points_with_x(&field.from_int(x)))
+            .map(|point| curve.multiply(4, &point))
+            .find(|point| !point.is_infinity())
+            .unwrap();
+        (curve, r, base)
+    }
+
+    #[test]
+    fn recovers_a_known_logarithm() {
+        let (curve, r, base) = supersingular(1_000_171);
+        for n in [1, 2, 123_456_789 % r, r - 1] {
+            let target = curve.multiply(n as u128, &base);
+            assert_eq!(mov_attack(&curve, r, &base, &target, 6), Ok(n));
+        }
+        let transfer = mov_transfer(&curve, r, &base, &base, 6).unwrap();
+        assert_eq!(transfer.k, 2);
+        assert!(transfer.alpha.pow(r as u128).is_one());
+    }
+
+    #[test]
+    fn refuses_a_large_embedding_degree() {
+        // #E = 2 · 5³ · 4003 over F_1000003, and 1000003 has order 4002 mod 4003
+        let field = ExtensionField::prime(1_000_003);
+        let curve = Curve::new(2, 5, &field);
+        let base = (1..)
+            .flat_map(|x| curve.points_with_x(&field.from_int(x)))
+            .map(|point| curve.multiply(250, &point))
+            .find(|point| !point.is_infinity())
+            .unwrap();
+        let target = curve.multiply(1000, &base);
+        assert_eq!(
+            mov_attack(&curve, 4003, &base, &target, 6),
+            Err("the embedding degree is too large for the transfer")
+        );
+    }
+
+    #[test]
+    fn rejects_bad_inputs() {
+        let (curve, r, base) = supersingular(1_000_171);
+        let field = &curve.field;
+        let off_curve = CurvePoint::Affine(field.from_int(1), field.from_int(1));
+        assert_eq!(
+            mov_attack(&curve, r, &base, &off_curve, 6).err(),
+            Some("point is not on the curve")
+        );
+        assert_eq!(
+            mov_attack(&curve, r - 2, &base, &base, 6).err(),
+            Some("the base point must have order r")
+        );
+    }
+
+    #[test]
+    fn discrete_log_in_a_finite_field() {
+        // 11 generates F_1009^*, of order 1008 = 2⁴ 3² 7
+        let field = ExtensionField::prime(1_009);
+        let g = field.from_int(11);
+        for x in [0, 1, 500, 1_007] {
+            let h = g.pow(x);
+            assert_eq!(discrete_log(&g, &h, 1_008), Some(x % 1_008));
+            assert_eq!(bsgs(&g, &h, 1_008), Some(x));
+        }
+        assert_eq!(crt(2, 3, 3, 5), 8);
+    }
+}
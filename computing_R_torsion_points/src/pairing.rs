@@ -0,0 +1,209 @@
+use crate::curve::{Curve, CurvePoint};
+use crate::field::FieldElement;
+use crate::torsion_basis::TorsionBasis;
+
+/// The line through T and S (the tangent when T = S, the vertical when
+/// T = -S) evaluated at X, together with T + S.
+fn line(
+    curve: &Curve,
+    t: &CurvePoint,
+    s: &CurvePoint,
+    x: &FieldElement,
+    y: &FieldElement,
+) -> (FieldElement, CurvePoint) {
+    let sum = curve.add(t, s);
+    let (CurvePoint::Affine(xt, yt), CurvePoint::Affine(xs, ys)) = (t, s) else {
+        unreachable!("Miller's loop never lines up the point at infinity")
+    };
+    if sum.is_infinity() {
+        return (x.clone() - xt.clone(), sum);
+    }
+    let lambda = if xt == xs {
+        (xt.square().scale(3) + curve.a.clone()) * yt.scale(2).inverse().unwrap()
+    } else {
+        (ys.clone() - yt.clone()) * (xs.clone() - xt.clone()).inverse().unwrap()
+    };
+    let value = y.clone() - yt.clone() - lambda * (x.clone() - xt.clone());
+    (value, sum)
+}
+
+/// The vertical line through R evaluated at X, or 1 when R = O.
+fn vertical(r: &CurvePoint, x: &FieldElement) -> FieldElement {
+    match r {
+        CurvePoint::Infinity => x.field.one(),
+        CurvePoint::Affine(xr, _) => x.clone() - xr.clone(),
+    }
+}
+
+/// Miller's algorithm: f_{n,P}(Q) for the function with divisor
+/// n(P) - ([n]P) - (n - 1)(O), normalized at infinity.
+///
+/// Every line and vertical in the loop vanishes only at multiples of P, so
+/// this returns `None` exactly when Q lands on one of them.
+pub fn miller_loop(curve: &Curve, n: u128, p: &CurvePoint, q: &CurvePoint) -> Option<FieldElement> {
+    let (CurvePoint::Affine(_, _), CurvePoint::Affine(xq, yq)) = (p, q) else {
+        return None;
+    };
+    let mut f = curve.field.one();
+    let mut t = p.clone();
+    for i in (0..(127 - n.leading_zeros())).rev() {
+        let (l, doubled) = line(curve, &t, &t, xq, yq);
+        let v = vertical(&doubled, xq);
+        f = f.square() * l * v.inverse()?;
+        t = doubled;
+
+        if (n >> i) & 1 == 1 {
+            let (l, sum) = line(curve, &t, p, xq, yq);
+            let v = vertical(&sum, xq);
+            f = f * l * v.inverse()?;
+            t = sum;
+        }
+    }
+    if f.is_zero() {
+        None
+    } else {
+        Some(f)
+    }
+}
+
+/// The Weil pairing e_r(P, Q) = (-1)^r f_{r,P}(Q) / f_{r,Q}(P) for P, Q in E[r].
+///
+/// The Miller loops only fail when P and Q generate the same cyclic
+/// subgroup, and on such pairs the alternating pairing is 1.
+pub fn weil_pairing(curve: &Curve, r: u128, p: &CurvePoint, q: &CurvePoint) -> FieldElement {
+    let one = curve.field.one();
+    if p.is_infinity() || q.is_infinity() {
+        return one;
+    }
+    let (Some(f_p), Some(f_q)) = (miller_loop(curve, r, p, q), miller_loop(curve, r, q, p)) else {
+        return one;
+    };
+    let value = f_p * f_q.inverse().unwrap();
+    if r % 2 == 1 {
+        -value
+    } else {
+        value
+    }
+}
+
+/// The reduced Tate pairing t_r(P, Q) = f_{r,P}(Q)^((q - 1) / r) over F_q,
+/// for P in E[r] and Q any point of E(F_q), with r | q - 1. It is trivial on
+/// points of rE(F_q), so on G1 × G2 it can degenerate when r² | #E(F_q).
+///
+/// When Q sits on a zero of the Miller functions the argument is moved to
+/// the divisor (Q + S) - (S) for pseudo-random points S, which leaves the
+/// reduced pairing unchanged.
+pub fn tate_pairing(curve: &Curve, r: u128, p: &CurvePoint, q: &CurvePoint) -> FieldElement {
+    let one = curve.field.one();
+    if p.is_infinity() || q.is_infinity() {
+        return one;
+    }
+    let exponent = (curve.field.order() - 1) / r;
+    if let Some(f) = miller_loop(curve, r, p, q) {
+        return f.pow(exponent);
+    }
+
+    let mut state = 0x853c49e6748fea9b;
+    loop {
+        let x = curve.field.random(&mut state);
+        let Some(s) = curve.points_with_x(&x).pop() else {
+            continue;
+        };
+        let shifted = curve.add(q, &s);
+        if let (Some(numerator), Some(denominator)) = (
+            miller_loop(curve, r, p, &shifted),
+            miller_loop(curve, r, p, &s),
+        ) {
+            return (numerator * denominator.inverse().unwrap()).pow(exponent);
+        }
+    }
+}
+
+/// Checks the reduced Tate pairing on G1 × G2: bilinear, non-degenerate,
+/// with values in μ_r, and e_r(P, Q)^((q - 1) / r) = t_r(P, Q) / t_r(Q, P).
+pub fn verify_tate_pairing(basis: &TorsionBasis) -> Result<(), &'static str> {
+    let curve = &basis.curve;
+    let r = basis.r as u128;
+    let t = |p: &CurvePoint, q: &CurvePoint| tate_pairing(curve, r, p, q);
+
+    if basis.g1.is_infinity() || basis.g2.is_infinity() {
+        return Err("G1 or G2 is trivial");
+    }
+    let base = t(&basis.g1, &basis.g2);
+    if base.is_one() || !base.pow(r).is_one() {
+        return Err("t(G1, G2) is not a primitive r-th root of unity");
+    }
+    for i in 1..r.min(5) {
+        for j in 1..r.min(5) {
+            let p = curve.multiply(i, &basis.g1);
+            let q = curve.multiply(j, &basis.g2);
+            if t(&p, &q) != base.pow(i * j) {
+                return Err("t([i]P, [j]Q) differs from t(P, Q)^(ij)");
+            }
+        }
+    }
+
+    let exponent = (curve.field.order() - 1) / r;
+    let weil = weil_pairing(curve, r, &basis.p1, &basis.p2).pow(exponent);
+    let ratio = t(&basis.p1, &basis.p2) * t(&basis.p2, &basis.p1).inverse().unwrap();
+    if weil != ratio {
+        return Err("the Weil pairing does not match the ratio of Tate pairings");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::torsion_basis::embedding_degree;
+
+    /// Bases of E[r] for small curves with r | #E(F_p) and k > 1.
+    fn bases() -> Vec<TorsionBasis> {
+        [(8, 8, 13, 5), (1, 1, 5, 3), (1, 1, 7, 5), (1, 6, 17, 5)]
+            .into_iter()
+            .map(|(a, b, p, r)| {
+                let k = embedding_degree(p, r).unwrap();
+                TorsionBasis::new(a, b, p, r, k).unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn weil_pairing_is_a_primitive_root_of_unity() {
+        for basis in bases() {
+            let e = weil_pairing(&basis.curve, basis.r as u128, &basis.p1, &basis.p2);
+            assert!(e.pow(basis.r as u128).is_one());
+            assert!(!e.is_one(), "degenerate on a basis of E[{}]", basis.r);
+        }
+    }
+
+    #[test]
+    fn weil_pairing_is_bilinear() {
+        for basis in bases() {
+            let (curve, r) = (&basis.curve, basis.r as u128);
+            let e = |p: &CurvePoint, q: &CurvePoint| weil_pairing(curve, r, p, q);
+            let base = e(&basis.p1, &basis.p2);
+            for i in 1..r.min(5) {
+                for j in 1..r.min(5) {
+                    let p = curve.multiply(i, &basis.p1);
+                    let q = curve.multiply(j, &basis.p2);
+                    assert_eq!(e(&p, &q), base.pow(i * j));
+                    let sum = curve.add(&p, &basis.p1);
+                    assert_eq!(e(&sum, &q), e(&p, &q) * e(&basis.p1, &q));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn weil_pairing_is_alternating() {
+        for basis in bases() {
+            let (curve, r) = (&basis.curve, basis.r as u128);
+            let e = |p: &CurvePoint, q: &CurvePoint| weil_pairing(curve, r, p, q);
+            for point in basis.points.iter().take(12) {
+                assert!(e(point, point).is_one());
+                assert!((e(point, &basis.p2) * e(&basis.p2, point)).is_one());
+            }
+        }
+    }
+}
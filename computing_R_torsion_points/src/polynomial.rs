@@ -0,0 +1,315 @@
+use std::fmt;
+use std::rc::Rc;
+
+use crate::field::{ExtensionField, FieldElement};
+
+/// A univariate polynomial over F_q, lowest degree coefficient first and
+/// with no trailing zeros (the zero polynomial has no coefficients).
+#[derive(Clone, PartialEq, Eq)]
+pub struct Polynomial {
+    pub coeffs: Vec<FieldElement>,
+    pub field: Rc<ExtensionField>,
+}
+
+impl Polynomial {
+    pub fn new(coeffs: Vec<FieldElement>, field: &Rc<ExtensionField>) -> Self {
+        let mut poly = Polynomial {
+            coeffs,
+            field: Rc::clone(field),
+        };
+        poly.trim();
+        poly
+    }
+
+    /// A polynomial with integer coefficients reduced into F_q.
+    pub fn from_ints(coeffs: &[i64], field: &Rc<ExtensionField>) -> Self {
+        Self::new(coeffs.iter().map(|&c| field.from_int(c)).collect(), field)
+    }
+
+    pub fn zero(field: &Rc<ExtensionField>) -> Self {
+        Self::new(Vec::new(), field)
+    }
+
+    pub fn constant(c: FieldElement) -> Self {
+        let field = Rc::clone(&c.field);
+        Self::new(vec![c], &field)
+    }
+
+    /// The polynomial X.
+    pub fn x(field: &Rc<ExtensionField>) -> Self {
+        Self::new(vec![field.zero(), field.one()], field)
+    }
+
+    fn trim(&mut self) {
+        while self.coeffs.last().is_some_and(|c| c.is_zero()) {
+            self.coeffs.pop();
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    /// The degree, with -1 standing in for the zero polynomial.
+    pub fn degree(&self) -> isize {
+        self.coeffs.len() as isize - 1
+    }
+
+    pub fn leading(&self) -> FieldElement {
+        self.coeffs
+            .last()
+            .cloned()
+            .unwrap_or_else(|| self.field.zero())
+    }
+
+    pub fn add(&self, other: &Polynomial) -> Polynomial {
+        let length = self.coeffs.len().max(other.coeffs.len());
+        let coeffs = (0..length)
+            .map(|i| self.coeff(i) + other.coeff(i))
+            .collect();
+        Polynomial::new(coeffs, &self.field)
+    }
+
+    pub fn sub(&self, other: &Polynomial) -> Polynomial {
+        let length = self.coeffs.len().max(other.coeffs.len());
+        let coeffs = (0..length)
+            .map(|i| self.coeff(i) - other.coeff(i))
+            .collect();
+        Polynomial::new(coeffs, &self.field)
+    }
+
+    pub fn mul(&self, other: &Polynomial) -> Polynomial {
+        if self.is_zero() || other.is_zero() {
+            return Polynomial::zero(&self.field);
+        }
+        let mut coeffs = vec![self.field.zero(); self.coeffs.len() + other.coeffs.len() - 1];
+        for (i, a) in self.coeffs.iter().enumerate() {
+            if a.is_zero() {
+                continue;
+            }
+            for (j, b) in other.coeffs.iter().enumerate() {
+                coeffs[i + j] = coeffs[i + j].clone() + a.clone() * b.clone();
+            }
+        }
+        Polynomial::new(coeffs, &self.field)
+    }
+
+    pub fn scale(&self, c: &FieldElement) -> Polynomial {
+        let coeffs = self.coeffs.iter().map(|a| a.clone() * c.clone()).collect();
+        Polynomial::new(coeffs, &self.field)
+    }
+
+    pub fn coeff(&self, i: usize) -> FieldElement {
+        self.coeffs
+            .get(i)
+            .cloned()
+            .unwrap_or_else(|| self.field.zero())
+    }
+
+    /// Quotient and remainder of division by a non-zero polynomial.
+    pub fn div_rem(&self, divisor: &Polynomial) -> (Polynomial, Polynomial) {
+        assert!(!divisor.is_zero(), "division by the zero polynomial");
+        let mut remainder = self.coeffs.clone();
+        let d = divisor.coeffs.len();
+        if remainder.len() < d {
+            return (Polynomial::zero(&self.field), self.clone());
+        }
+        let lead_inv = divisor.leading().inverse().unwrap();
+        let mut quotient = vec![self.field.zero(); remainder.len() - d + 1];
+        for i in (0..quotient.len()).rev() {
+            let c = remainder[i + d - 1].clone() * lead_inv.clone();
+            if c.is_zero() {
+                continue;
+            }
+            for (j, b) in divisor.coeffs.iter().enumerate() {
+                remainder[i + j] = remainder[i + j].clone() - c.clone() * b.clone();
+            }
+            quotient[i] = c;
+        }
+        remainder.truncate(d - 1);
+        (
+            Polynomial::new(quotient, &self.field),
+            Polynomial::new(remainder, &self.field),
+        )
+    }
+
+    pub fn rem(&self, divisor: &Polynomial) -> Polynomial {
+        self.div_rem(divisor).1
+    }
+
+    pub fn monic(&self) -> Polynomial {
+        if self.is_zero() {
+            return self.clone();
+        }
+        self.scale(&self.leading().inverse().unwrap())
+    }
+
+    /// The monic greatest common divisor.
+    pub fn gcd(&self, other: &Polynomial) -> Polynomial {
+        let (mut a, mut b) = (self.clone(), other.clone());
+        while !b.is_zero() {
+            let r = a.rem(&b);
+            a = b;
+            b = r;
+        }
+        a.monic()
+    }
+
+    /// self^exponent mod modulus by square-and-multiply.
+    pub fn pow_mod(&self, exponent: u128, modulus: &Polynomial) -> Polynomial {
+        let mut result = Polynomial::constant(self.field.one()).rem(modulus);
+        let mut base = self.rem(modulus);
+        let mut exponent = exponent;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result = result.mul(&base).rem(modulus);
+            }
+            base = base.mul(&base).rem(modulus);
+            exponent >>= 1;
+        }
+        result
+    }
+
+    pub fn evaluate(&self, x: &FieldElement) -> FieldElement {
+        self.coeffs
+            .iter()
+            .rev()
+            .fold(self.field.zero(), |acc, c| acc * x.clone() + c.clone())
+    }
+
+    /// Every root in F_q, found as the roots of gcd(f, X^q - X) and split by
+    /// Cantor–Zassenhaus equal-degree factorization.
+    pub fn roots(&self) -> Vec<FieldElement> {
+        if self.degree() < 1 {
+            return Vec::new();
+        }
+        let f = self.monic();
+        let x = Polynomial::x(&self.field);
+        let x_q = x.pow_mod(self.field.order(), &f);
+        let linear_part = f.gcd(&x_q.sub(&x));
+
+        let mut roots = Vec::new();
+        let mut state = 0x2545f4914f6cdd1d;
+        split_linear_factors(&linear_part, &mut roots, &mut state);
+        roots
+    }
+}
+
+/// Splits a product of distinct linear factors into its roots. The field
+/// must have odd characteristic.
+fn split_linear_factors(f: &Polynomial, roots: &mut Vec<FieldElement>, state: &mut u64) {
+    match f.degree() {
+        d if d < 1 => return,
+        1 => {
+            roots.push(-f.coeff(0) * f.coeff(1).inverse().unwrap());
+            return;
+        }
+        _ => {}
+    }
+
+    let field = &f.field;
+    let q = field.order();
+    loop {
+        let delta = field.random(state);
+        let shifted = Polynomial::new(vec![delta, field.one()], field);
+        let power = shifted.pow_mod((q - 1) / 2, f);
+        let g = f.gcd(&power.sub(&Polynomial::constant(field.one())));
+        if g.degree() > 0 && g.degree() < f.degree() {
+            split_linear_factors(&g, roots, state);
+            split_linear_factors(&f.div_rem(&g).0, roots, state);
+            return;
+        }
+    }
+}
+
+/// The first monic irreducible polynomial of degree k over F_p, as integer
+/// coefficients lowest degree first. Irreducibility is Ben-Or's test:
+/// gcd(g, X^(p^i) - X) = 1 for every i <= k / 2.
+pub fn find_irreducible(p: i64, k: usize) -> Vec<i64> {
+    let base = ExtensionField::prime(p);
+    let x = Polynomial::x(&base);
+    let total = (p as u128).pow(k as u32);
+    for index in 0..total {
+        let mut coeffs = Vec::with_capacity(k + 1);
+        let mut rest = index;
+        for _ in 0..k {
+            coeffs.push((rest % p as u128) as i64);
+            rest /= p as u128;
+        }
+        coeffs.push(1);
+        if coeffs[0] == 0 {
+            continue;
+        }
+
+        let g = Polynomial::from_ints(&coeffs, &base);
+        let mut x_power = x.clone();
+        let irreducible = (1..=k / 2).all(|_| {
+            x_power = x_power.pow_mod(p as u128, &g);
+            g.gcd(&x_power.sub(&x)).degree() == 0
+        });
+        if irreducible {
+            return coeffs;
+        }
+    }
+    unreachable!("an irreducible polynomial of every degree exists")
+}
+
+/// Degrees of the irreducible factors of a squarefree polynomial over F_p,
+/// with their multiplicity, by distinct-degree factorization.
+pub fn factor_degrees(f: &Polynomial) -> Vec<(usize, usize)> {
+    let p = f.field.p as u128;
+    let x = Polynomial::x(&f.field);
+    let mut remaining = f.monic();
+    let mut x_power = x.clone();
+    let mut degrees = Vec::new();
+    let mut d = 1;
+
+    while remaining.degree() >= 2 * d as isize {
+        x_power = x_power.pow_mod(p, &remaining);
+        let g = remaining.gcd(&x_power.sub(&x));
+        if g.degree() > 0 {
+            degrees.push((d, g.degree() as usize / d));
+            remaining = remaining.div_rem(&g).0;
+            x_power = x_power.rem(&remaining);
+        }
+        d += 1;
+    }
+    if remaining.degree() > 0 {
+        degrees.push((remaining.degree() as usize, 1));
+    }
+    degrees
+}
+
+impl fmt::Display for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_zero() {
+            return write!(f, "0");
+        }
+        let terms: Vec<String> = self
+            .coeffs
+            .iter()
+            .enumerate()
+            .rev()
+            .filter(|(_, c)| !c.is_zero())
+            .map(|(i, c)| {
+                let c = if c.in_prime_field() {
+                    c.to_string()
+                } else {
+                    format!("({})", c)
+                };
+                match i {
+                    0 => c,
+                    1 => format!("{}x", c),
+                    _ => format!("{}x^{}", c, i),
+                }
+            })
+            .collect();
+        write!(f, "{}", terms.join(" + "))
+    }
+}
+
+impl fmt::Debug for Polynomial {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
@@ -0,0 +1,228 @@
+use std::rc::Rc;
+
+use integer_factorization::{is_prime_u64, Factorization};
+use num_traits::ToPrimitive;
+
+use crate::curve::{Curve, CurvePoint};
+use crate::division_polynomials::r_torsion_over;
+use crate::field::{pow_mod, ExtensionField};
+
+/// A basis of E[r] ≅ Z/r × Z/r over F_{p^k}, together with generators of the
+/// two Frobenius eigenspaces used by pairings:
+///   G1 = E[r] ∩ ker(π - 1), the r-torsion rational over F_p
+///   G2 = E[r] ∩ ker(π - p), the trace-zero subgroup
+pub struct TorsionBasis {
+    pub curve: Curve,
+    pub field: Rc<ExtensionField>,
+    pub r: u64,
+    pub k: usize,
+    pub p1: CurvePoint,
+    pub p2: CurvePoint,
+    pub g1: CurvePoint,
+    pub g2: CurvePoint,
+    pub points: Vec<CurvePoint>,
+}
+
+/// Multiplicative order of p modulo the prime r, the embedding degree of a
+/// subgroup of order r not dividing p. It divides r - 1, so starting from
+/// r - 1 every prime q of r - 1 is divided out for as long as p^(k/q) = 1.
+pub fn embedding_degree(p: i64, r: u64) -> Result<usize, &'static str> {
+    if !is_prime_u64(r) {
+        return Err("r must be prime");
+    }
+    let modulus = i64::try_from(r).map_err(|_| "r does not fit in an i64")?;
+    let p = p.rem_euclid(modulus);
+    if p == 0 {
+        return Err("r divides p");
+    }
+    let mut k = r - 1;
+    for (q, e) in Factorization::of_u64(r - 1)?.factors() {
+        let q = q.to_u64().ok_or("factor of r - 1 does not fit in a u64")?;
+        for _ in 0..*e {
+            if pow_mod(p, (k / q) as u128, modulus) != 1 {
+                break;
+            }
+            k /= q;
+        }
+    }
+    usize::try_from(k).map_err(|_| "embedding degree does not fit in a usize")
+}
+
+impl TorsionBasis {
+    /// Builds F_{p^k}, computes E[r] there from the division polynomial ψ_r
+    /// and picks a basis and the eigenspace generators.
+    pub fn new(a: i64, b: i64, p: i64, r: u64, k: usize) -> Result<Self, &'static str> {
+        if !is_prime_u64(r) || r as i64 == p {
+            return Err("r must be a prime different from p");
+        }
+        // With k = 1 the Frobenius fixes all of E[r], so the trace map that
+        // cuts out G2 is multiplication by 1 and has no trace-zero points
+        if k < 2 {
+            return Err("G2 needs an embedding degree k ≥ 2");
+        }
+        let (field, points) = r_torsion_over(a, b, p, r as usize, k);
+        if points.len() as u64 != r * r {
+            return Err("E[r] is not defined over F_{p^k}; k is too small");
+        }
+        let curve = Curve::new(a, b, &field);
+
+        // Any non-zero point starts the basis, any point outside its span completes it
+        let p1 = points
+            .iter()
+            .find(|point| !point.is_infinity())
+            .cloned()
+            .ok_or("no non-trivial r-torsion point")?;
+        let span = multiples(&curve, &p1, r);
+        let p2 = points
+            .iter()
+            .find(|point| !span.contains(point))
+            .cloned()
+            .ok_or("E[r] is cyclic")?;
+
+        let p_mod_r = p.rem_euclid(r as i64) as u128;
+        let g1 = points
+            .iter()
+            .find(|point| !point.is_infinity() && point.frobenius() == **point)
+            .cloned()
+            .unwrap_or(CurvePoint::Infinity);
+
+        // [k]R - Tr(R) is killed by the trace for every R, so it lands in G2
+        let g2 = points
+            .iter()
+            .map(|point| {
+                let trace = trace(&curve, point, k);
+                curve.add(&curve.multiply(k as u128, point), &curve.neg(&trace))
+            })
+            .find(|candidate| {
+                !candidate.is_infinity()
+                    && candidate.frobenius() == curve.multiply(p_mod_r, candidate)
+            })
+            .ok_or("no point of E[r] lies in the trace-zero subgroup")?;
+
+        Ok(TorsionBasis {
+            curve,
+            field,
+            r,
+            k,
+            p1,
+            p2,
+            g1,
+            g2,
+            points,
+        })
+    }
+
+    /// The eigenspace G1 = <g1>, empty apart from O when r ∤ #E(F_p).
+    pub fn g1_points(&self) -> Vec<CurvePoint> {
+        multiples(&self.curve, &self.g1, self.r)
+    }
+
+    /// The trace-zero eigenspace G2 = <g2>.
+    pub fn g2_points(&self) -> Vec<CurvePoint> {
+        multiples(&self.curve, &self.g2, self.r)
+    }
+
+    /// Coordinates (i, j) with point = [i]P1 + [j]P2, by exhaustive search.
+    pub fn coordinates(&self, point: &CurvePoint) -> Option<(u64, u64)> {
+        let first = multiples(&self.curve, &self.p1, self.r);
+        let mut shifted = CurvePoint::Infinity;
+        for j in 0..self.r {
+            for (i, multiple) in first.iter().enumerate() {
+                if self.curve.add(multiple, &shifted) == *point {
+                    return Some((i as u64, j));
+                }
+            }
+            shifted = self.curve.add(&shifted, &self.p2);
+        }
+        None
+    }
+
+    /// The matrix of the Frobenius π acting on E[r] in the basis (P1, P2),
+    /// as columns π(P1) and π(P2). Its eigenvalues are 1 and p mod r.
+    pub fn frobenius_matrix(&self) -> [[u64; 2]; 2] {
+        let (a, c) = self.coordinates(&self.p1.frobenius()).unwrap();
+        let (b, d) = self.coordinates(&self.p2.frobenius()).unwrap();
+        [[a, b], [c, d]]
+    }
+}
+
+/// O, P, 2P, ..., (r - 1)P
+fn multiples(curve: &Curve, point: &CurvePoint, r: u64) -> Vec<CurvePoint> {
+    let mut result = Vec::with_capacity(r as usize);
+    let mut current = CurvePoint::Infinity;
+    for _ in 0..r {
+        result.push(current.clone());
+        current = curve.add(&current, point);
+    }
+    result
+}
+
+/// The trace P + π(P) + ... + π^{k-1}(P), always a point over F_p.
+pub fn trace(curve: &Curve, point: &CurvePoint, k: usize) -> CurvePoint {
+    let mut sum = CurvePoint::Infinity;
+    let mut conjugate = point.clone();
+    for _ in 0..k {
+        sum = curve.add(&sum, &conjugate);
+        conjugate = conjugate.frobenius();
+    }
+    sum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force_order(p: i64, r: u64) -> usize {
+        let r = r as i64;
+        (1..r as usize)
+            .find(|&k| pow_mod(p, k as u128, r) == 1)
+            .unwrap()
+    }
+
+    #[test]
+    fn embedding_degree_is_the_order_of_p() {
+        for r in [2u64, 3, 5, 7, 13, 31, 97, 1_009] {
+            for p in [2i64, 3, 5, 7, 11, 13, 17, 19, 23, 101, 1_000_003] {
+                if (p as u64).is_multiple_of(r) {
+                    continue;
+                }
+                assert_eq!(
+                    embedding_degree(p, r),
+                    Ok(brute_force_order(p, r)),
+                    "p = {}, r = {}",
+                    p,
+                    r
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn embedding_degree_of_large_subgroups() {
+        // r | p + 1 on the supersingular demo curves, so k = 2
+        assert_eq!(embedding_degree(1_000_171, 250_043), Ok(2));
+        // 2^61 - 1 would take about 2^61 steps to walk
+        let r = (1u64 << 61) - 1;
+        assert_eq!(embedding_degree(r as i64 - 1, r), Ok(2));
+        assert_eq!(embedding_degree(2, r), Ok(61));
+        assert_eq!(embedding_degree(1, r), Ok(1));
+    }
+
+    #[test]
+    fn embedding_degree_rejects_bad_r() {
+        assert_eq!(embedding_degree(5, 1), Err("r must be prime"));
+        assert_eq!(embedding_degree(5, 91), Err("r must be prime"));
+        assert_eq!(embedding_degree(14, 7), Err("r divides p"));
+        // 2^64 - 59, the largest prime below 2^64
+        assert_eq!(
+            embedding_degree(5, u64::MAX - 58),
+            Err("r does not fit in an i64")
+        );
+    }
+
+    #[test]
+    fn rejects_composite_and_huge_r() {
+        assert!(TorsionBasis::new(1, 1, 7, 91, 2).is_err());
+        assert!(TorsionBasis::new(1, 1, 7, u64::MAX, 2).is_err());
+    }
+}
@@ -0,0 +1,384 @@
+use integer_factorization::{is_probable_prime, primes_between, primes_up_to, trial_division};
+use num_bigint::{BigInt, BigUint, RandBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+//giant step size of stage 2, a product of small primes so few residues are coprime to it
+const STAGE2_D: u64 = 210;
+
+//bounds and curve count for one ECM run
+#[derive(Debug, Clone, Copy)]
+pub struct EcmParams {
+    pub b1: u64,
+    pub b2: u64,
+    pub curves: usize,
+    pub threads: usize,
+}
+
+impl EcmParams {
+    //the usual B1 and expected curve counts for a factor of the given number of digits
+    pub fn for_factor_digits(digits: u32) -> Self {
+        let (b1, curves) = match digits {
+            0..=10 => (500, 20),
+            11..=15 => (2_000, 30),
+            16..=20 => (11_000, 90),
+            21..=25 => (50_000, 240),
+            26..=30 => (250_000, 500),
+            _ => (1_000_000, 900),
+        };
+        EcmParams {
+            b1,
+            b2: 100 * b1,
+            curves,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        }
+    }
+}
+
+//a point in x-only projective coordinates (X : Z) on a montgomery curve
+#[derive(Debug, Clone)]
+struct XzPoint {
+    x: BigUint,
+    z: BigUint,
+}
+
+//montgomery curve By^2 = x^3 + Ax^2 + x mod n, stored as a24 = (A + 2) / 4
+struct MontgomeryCurve<'a> {
+    n: &'a BigUint,
+    a24: BigUint,
+}
+
+impl MontgomeryCurve<'_> {
+    fn sub(&self, a: &BigUint, b: &BigUint) -> BigUint {
+        (a + self.n - b) % self.n
+    }
+
+    fn double(&self, p: &XzPoint) -> XzPoint {
+        let n = self.n;
+        let sum = (&p.x + &p.z) % n;
+        let diff = self.sub(&p.x, &p.z);
+        let t1 = &sum * &sum % n;
+        let t2 = &diff * &diff % n;
+        let t3 = self.sub(&t1, &t2);
+        XzPoint {
+            x: &t1 * &t2 % n,
+            z: &t3 * ((&t2 + &self.a24 * &t3) % n) % n,
+        }
+    }
+
+    //p + q given their difference p - q
+    fn add(&self, p: &XzPoint, q: &XzPoint, diff: &XzPoint) -> XzPoint {
+        let n = self.n;
+        let u = self.sub(&p.x, &p.z) * ((&q.x + &q.z) % n) % n;
+        let v = (&p.x + &p.z) * self.sub(&q.x, &q.z) % n;
+        let plus = (&u + &v) % n;
+        let minus = self.sub(&u, &v);
+        XzPoint {
+            x: &diff.z * (&plus * &plus % n) % n,
+            z: &diff.x * (&minus * &minus % n) % n,
+        }
+    }
+
+    //montgomery ladder for k * p
+    fn multiply(&self, k: u64, p: &XzPoint) -> XzPoint {
+        if k == 1 {
+            return p.clone();
+        }
+        let mut r0 = p.clone();
+        let mut r1 = self.double(p);
+        for i in (0..63 - k.leading_zeros()).rev() {
+            if (k >> i) & 1 == 1 {
+                r0 = self.add(&r1, &r0, p);
+                r1 = self.double(&r1);
+            } else {
+                r1 = self.add(&r1, &r0, p);
+                r0 = self.double(&r0);
+            }
+        }
+        r0
+    }
+}
+
+//result of running a single curve
+enum CurveOutcome {
+    Factor(BigUint),
+    //the gcd was n itself, the curve order was too smooth for this n
+    Degenerate,
+    NoFactor,
+}
+
+fn nontrivial_gcd(value: &BigUint, n: &BigUint) -> CurveOutcome {
+    let g = value.gcd(n);
+    if g.is_one() {
+        CurveOutcome::NoFactor
+    } else if g == *n {
+        CurveOutcome::Degenerate
+    } else {
+        CurveOutcome::Factor(g)
+    }
+}
+
+//suyama's parametrization: for sigma not in {0, ±1, 5} the curve has a point of
+//known x-coordinate and a group order divisible by 12, making smoothness likelier.
+//the a24 denominator may not be invertible, in which case the gcd is the factor.
+fn suyama_curve<'a>(
+    n: &'a BigUint,
+    sigma: &BigUint,
+) -> Result<(MontgomeryCurve<'a>, XzPoint), CurveOutcome> {
+    let u = (sigma * sigma % n + n - BigUint::from(5u32)) % n;
+    let v = (sigma * 4u32) % n;
+    let u3 = &u * &u % n * &u % n;
+    let v3 = &v * &v % n * &v % n;
+
+    let v_minus_u = (&v + n - &u) % n;
+    let numerator = &v_minus_u * &v_minus_u % n * &v_minus_u % n * ((&u * 3u32 + &v) % n) % n;
+    let denominator = &u3 * &v * 16u32 % n;
+
+    let inverse = match mod_inverse(&denominator, n) {
+        Ok(inverse) => inverse,
+        Err(g) if g == *n => return Err(CurveOutcome::Degenerate),
+        Err(g) => return Err(CurveOutcome::Factor(g)),
+    };
+    let curve = MontgomeryCurve {
+        n,
+        a24: numerator * inverse % n,
+    };
+    Ok((curve, XzPoint { x: u3, z: v3 }))
+}
+
+//inverse of a mod n, or the gcd that prevented it
+pub fn mod_inverse(a: &BigUint, n: &BigUint) -> Result<BigUint, BigUint> {
+    let a = BigInt::from(a % n);
+    let modulus = BigInt::from(n.clone());
+    let extended = a.extended_gcd(&modulus);
+    if extended.gcd.is_one() {
+        Ok(extended.x.mod_floor(&modulus).to_biguint().unwrap())
+    } else {
+        Err(extended.gcd.to_biguint().unwrap())
+    }
+}
+
+//stage 1 multiplies by every prime power up to b1, stage 2 looks for a single
+//extra prime b1 < q <= b2 with baby-step giant-step differences of x-coordinates,
+//its primes coming from a segmented sieve rather than a table up to b2
+fn run_curve(n: &BigUint, sigma: &BigUint, params: &EcmParams, primes: &[u64]) -> CurveOutcome {
+    let (curve, mut q) = match suyama_curve(n, sigma) {
+        Ok(setup) => setup,
+        Err(outcome) => return outcome,
+    };
+
+    // Stage 1
+    for &p in primes.iter().take_while(|&&p| p <= params.b1) {
+        let mut power = p;
+        while power <= params.b1 / p {
+            power *= p;
+        }
+        q = curve.multiply(power, &q);
+    }
+    match nontrivial_gcd(&q.z, n) {
+        CurveOutcome::NoFactor => {}
+        outcome => return outcome,
+    }
+
+    // Stage 2: baby steps [j]Q for odd j < D/2 coprime to D
+    let d = STAGE2_D;
+    let q2 = curve.double(&q);
+    let mut baby = vec![None; (d / 2) as usize];
+    let mut previous = q.clone();
+    let mut current = curve.add(&q2, &q, &q);
+    baby[1] = Some(q.clone());
+    for j in (3..d / 2).step_by(2) {
+        if j.gcd(&d) == 1 {
+            baby[j as usize] = Some(current.clone());
+        }
+        let next = curve.add(&current, &q2, &previous);
+        previous = current;
+        current = next;
+    }
+
+    // Giant steps R_m = [mD]Q, pairing each prime mD ± j with the baby step j
+    let step = curve.multiply(d, &q);
+    let mut m = (params.b1 / d).max(1);
+    let mut giant = curve.multiply(m * d, &q);
+    let mut next_giant = curve.multiply((m + 1) * d, &q);
+
+    let mut accumulator = BigUint::one();
+    let mut primes_in_range = primes_between(params.b1, params.b2).peekable();
+    while primes_in_range.peek().is_some() {
+        let low = m * d - d / 2;
+        let high = m * d + d / 2;
+        while let Some(&p) = primes_in_range.peek() {
+            if p > high {
+                break;
+            }
+            primes_in_range.next();
+            if p < low {
+                continue;
+            }
+            let j = p.abs_diff(m * d) as usize;
+            if let Some(Some(b)) = baby.get(j) {
+                let cross = curve.sub(&(&giant.x * &b.z % n), &(&b.x * &giant.z % n));
+                accumulator = accumulator * cross % n;
+            }
+        }
+        let after = curve.add(&next_giant, &step, &giant);
+        giant = next_giant;
+        next_giant = after;
+        m += 1;
+    }
+    nontrivial_gcd(&accumulator, n)
+}
+
+//runs up to params.curves random curves, spread over params.threads threads,
+//and returns the first non-trivial factor of n that any of them finds
+pub fn ecm_find_factor(n: &BigUint, params: &EcmParams) -> Option<BigUint> {
+    if n.is_even() {
+        return Some(BigUint::from(2u32));
+    }
+    let primes = primes_up_to(params.b1);
+    let found = AtomicBool::new(false);
+    let factor = Mutex::new(None);
+    let threads = params.threads.max(1);
+
+    thread::scope(|scope| {
+        for t in 0..threads {
+            let (primes, found, factor) = (&primes, &found, &factor);
+            scope.spawn(move || {
+                let mut rng = rand::thread_rng();
+                let low = BigUint::from(6u32);
+                for _ in (t..params.curves).step_by(threads) {
+                    if found.load(Ordering::Relaxed) {
+                        return;
+                    }
+                    let sigma = rng.gen_biguint_range(&low, n);
+                    if let CurveOutcome::Factor(g) = run_curve(n, &sigma, params, primes) {
+                        found.store(true, Ordering::Relaxed);
+                        factor.lock().unwrap().get_or_insert(g);
+                        return;
+                    }
+                }
+            });
+        }
+    });
+    factor.into_inner().unwrap()
+}
+
+//the digit counts whose parameters ecm_factor tries in turn, starting from
+//start_digits and going up by 5 until a factor turns up
+pub fn ecm_schedule(start_digits: u32) -> impl Iterator<Item = (u32, EcmParams)> {
+    (start_digits..)
+        .step_by(5)
+        .map(|digits| (digits, EcmParams::for_factor_digits(digits)))
+}
+
+//fully factors n into primes, raising B1 each time the current level runs out of
+//curves, starting from the parameters for factors of start_digits digits
+pub fn ecm_factor(n: &BigUint, start_digits: u32) -> Vec<BigUint> {
+    let (small, cofactor) = trial_division(n, 1_000);
+    let mut primes: Vec<BigUint> = small
+        .into_iter()
+        .flat_map(|(p, e)| std::iter::repeat_n(p, e as usize))
+        .collect();
+
+    let mut pending = vec![cofactor];
+    while let Some(m) = pending.pop() {
+        if m.is_one() {
+            continue;
+        }
+        if is_probable_prime(&m) {
+            primes.push(m);
+            continue;
+        }
+        let factor = ecm_schedule(start_digits)
+            .find_map(|(_, params)| ecm_find_factor(&m, &params))
+            .unwrap();
+        pending.push(&m / &factor);
+        pending.push(factor);
+    }
+    primes.sort();
+    primes
+}
+
+//lenstra's original method on short weierstrass curves y^2 = x^3 + ax + b mod n with
+//affine coordinates: every addition needs an inverse mod n, and the first one
+//that does not exist exposes gcd(denominator, n) as a factor
+pub fn lenstra_factor(n: &BigUint, bound: u64, curves: usize) -> Option<BigUint> {
+    let mut rng = rand::thread_rng();
+    let primes = primes_up_to(bound);
+
+    for _ in 0..curves {
+        // Pick the point first and let it determine b
+        let a = rng.gen_biguint_below(n);
+        let mut point = Some((rng.gen_biguint_below(n), rng.gen_biguint_below(n)));
+
+        for &p in &primes {
+            let mut power = p;
+            while power <= bound / p {
+                power *= p;
+            }
+            match weierstrass_multiply(power, point, &a, n) {
+                Ok(result) => point = result,
+                Err(g) if g != *n => return Some(g),
+                Err(_) => break,
+            }
+            if point.is_none() {
+                break;
+            }
+        }
+    }
+    None
+}
+
+type AffinePoint = Option<(BigUint, BigUint)>;
+
+fn weierstrass_add(
+    p: &AffinePoint,
+    q: &AffinePoint,
+    a: &BigUint,
+    n: &BigUint,
+) -> Result<AffinePoint, BigUint> {
+    let (x1, y1) = match p {
+        Some(point) => point,
+        None => return Ok(q.clone()),
+    };
+    let (x2, y2) = match q {
+        Some(point) => point,
+        None => return Ok(p.clone()),
+    };
+    if x1 == x2 && (y1 + y2) % n == BigUint::zero() {
+        return Ok(None);
+    }
+
+    let lambda = if x1 == x2 {
+        let numerator = (x1 * x1 * 3u32 + a) % n;
+        numerator * mod_inverse(&(y1 * 2u32), n)? % n
+    } else {
+        let numerator = (y2 + n - y1) % n;
+        numerator * mod_inverse(&((x2 + n - x1) % n), n)? % n
+    };
+    let x3 = (&lambda * &lambda + n * 2u32 - x1 - x2) % n;
+    let y3 = (lambda * ((x1 + n - &x3) % n) + n - y1) % n;
+    Ok(Some((x3, y3)))
+}
+
+fn weierstrass_multiply(
+    k: u64,
+    point: AffinePoint,
+    a: &BigUint,
+    n: &BigUint,
+) -> Result<AffinePoint, BigUint> {
+    let mut result = None;
+    let mut addend = point;
+    let mut k = k;
+    while k > 0 {
+        if k & 1 == 1 {
+            result = weierstrass_add(&result, &addend, a, n)?;
+        }
+        addend = weierstrass_add(&addend, &addend, a, n)?;
+        k >>= 1;
+    }
+    Ok(result)
+}
@@ -1,3 +1,9 @@
+mod ecm;
+
+use ecm::{ecm_factor, ecm_schedule, lenstra_factor};
+use num_bigint::BigUint;
+use std::time::Instant;
+
 #[derive(Debug, Clone, Copy)]
 struct Point {
     x: Option<i64>,
@@ -18,19 +24,16 @@ impl EllipticCurve {
         if p2.x.is_none() && p2.y.is_none() {
             return Some(*p1);
         }
-        if p1.x == p2.x && p1.y == p2.y {
-            return self.double(*p1);
-        }
         if p1.x == p2.x && p1.y == Some(mod_neg(-p2.y.unwrap(), self.p)) {
             return Some(Point { x: None, y: None });
         }
 
         let lambda = if p1.x == p2.x {
             // Double point
-            (3 * p1.x.unwrap().pow(2) + self.a) * self.mod_inv(2 * p1.y.unwrap(), self.p).unwrap()
+            (3 * p1.x.unwrap().pow(2) + self.a) * self.mod_inv(2 * p1.y.unwrap(), self.p)?
         } else {
             // Point addition
-            (p2.y.unwrap() - p1.y.unwrap()) * self.mod_inv(p2.x.unwrap() - p1.x.unwrap(), self.p).unwrap()
+            (p2.y.unwrap() - p1.y.unwrap()) * self.mod_inv(p2.x.unwrap() - p1.x.unwrap(), self.p)?
         };
         let lambda = mod_neg(lambda, self.p);
 
@@ -44,7 +47,7 @@ impl EllipticCurve {
     }
 
     fn is_on_curve(&self, p: &Point) -> bool {
-        p.y.map_or(false, |y| (y.pow(2) - (p.x.unwrap().pow(3) + self.a * p.x.unwrap() + self.b)) % self.p == 0)
+        p.y.is_some_and(|y| (y.pow(2) - (p.x.unwrap().pow(3) + self.a * p.x.unwrap() + self.b)) % self.p == 0)
     }
 
     fn mod_inv(&self, a: i64, m: i64) -> Option<i64> {
@@ -89,4 +92,26 @@ fn main() {
 
     println!("p1_on_curve: {}", curve.is_on_curve(&p1));
     println!("p2_on_curve: {}", curve.is_on_curve(&p2));
-}
\ No newline at end of file
+
+    // 713 = 23 * 31 is not a field, so some inversions fail and reveal a factor
+    let n = BigUint::from(713u32);
+    match lenstra_factor(&n, 50, 20) {
+        Some(factor) => println!("\nLenstra found {} = {} * {}", n, factor, &n / &factor),
+        None => println!("\nLenstra found no factor of {}", n),
+    }
+
+    // Montgomery-curve ECM with both stages on a 36-digit semiprime
+    let n: BigUint = "853973422267358518389151330698073613".parse().unwrap();
+    println!("ECM on {}, escalating through", n);
+    for (digits, params) in ecm_schedule(20).take(3) {
+        println!(
+            "  {}-digit factors: B1 = {}, B2 = {}, {} curves",
+            digits, params.b1, params.b2, params.curves
+        );
+    }
+    println!("  ... until a factor is found");
+    let start = Instant::now();
+    let factors = ecm_factor(&n, 20);
+    let factors: Vec<String> = factors.iter().map(|f| f.to_string()).collect();
+    println!("{} = {} ({:?})", n, factors.join(" * "), start.elapsed());
+}
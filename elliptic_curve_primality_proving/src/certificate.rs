@@ -0,0 +1,190 @@
+use integer_factorization::is_prime_miller_rabin;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+use std::fmt;
+
+use crate::curve::{Curve, Point};
+
+/// Below this bound Miller–Rabin with the first 13 prime bases is a proof
+/// of primality (Sorenson and Webster), so the certificate chain can stop.
+pub fn small_prime_bound() -> BigUint {
+    "3317044064679887385961981".parse().unwrap()
+}
+
+/// One link of a Goldwasser–Kilian certificate: a curve mod n and a point P
+/// with [m]P = O and [m/q]P ≠ O, where q > (n^(1/4) + 1)² is certified next.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CertificateStep {
+    pub n: BigUint,
+    pub a: BigUint,
+    pub b: BigUint,
+    pub m: BigUint,
+    pub q: BigUint,
+    pub point: (BigUint, BigUint),
+}
+
+/// A chain of steps n > q₁ > q₂ > ... ending in a prime small enough to be
+/// proven directly.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Certificate {
+    pub n: BigUint,
+    pub steps: Vec<CertificateStep>,
+}
+
+impl Certificate {
+    /// The prime whose primality the whole chain reduces to.
+    pub fn leaf(&self) -> &BigUint {
+        self.steps.last().map_or(&self.n, |step| &step.q)
+    }
+}
+
+impl fmt::Display for Certificate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Primality certificate for {}", self.n)?;
+        for (i, step) in self.steps.iter().enumerate() {
+            writeln!(f, "  step {}: n = {}", i + 1, step.n)?;
+            writeln!(f, "    E: y² = x³ + {}x + {}", step.a, step.b)?;
+            writeln!(f, "    m = {} = {} * q", step.m, &step.m / &step.q)?;
+            writeln!(f, "    q = {}", step.q)?;
+            writeln!(f, "    P = ({}, {})", step.point.0, step.point.1)?;
+        }
+        write!(
+            f,
+            "  leaf {} proven by deterministic Miller-Rabin",
+            self.leaf()
+        )
+    }
+}
+
+/// Checks a single step without trusting anything about q beyond its size.
+fn verify_step(step: &CertificateStep) -> Result<(), &'static str> {
+    let n = &step.n;
+    if n.gcd(&BigUint::from(6u32)) != BigUint::one() {
+        return Err("n must be coprime to 6");
+    }
+    if step.q.is_zero() || !(&step.m % &step.q).is_zero() {
+        return Err("q does not divide m");
+    }
+
+    // q > (n^(1/4) + 1)², checked against the integer fourth root rounded up
+    let root = n.nth_root(4) + 2u32;
+    if step.q <= &root * &root {
+        return Err("q is too small to imply primality of n");
+    }
+
+    if step.point.0 >= *n || step.point.1 >= *n {
+        return Err("point coordinates must be reduced modulo n");
+    }
+    let curve = Curve::new(step.a.clone(), step.b.clone(), n.clone());
+    if curve.discriminant().gcd(n) != BigUint::one() {
+        return Err("curve is singular modulo n");
+    }
+    let point: Point = Some(step.point.clone());
+    if !curve.contains(&point) {
+        return Err("point is not on the curve");
+    }
+
+    let cofactor = &step.m / &step.q;
+    let multiple = curve
+        .multiply(&cofactor, &point)
+        .map_err(|_| "[m/q]P hit a non-invertible element")?;
+    if multiple.is_none() {
+        return Err("[m/q]P is the point at infinity");
+    }
+    let identity = curve
+        .multiply(&step.q, &multiple)
+        .map_err(|_| "[m]P hit a non-invertible element")?;
+    if identity.is_some() {
+        return Err("[m]P is not the point at infinity");
+    }
+    Ok(())
+}
+
+/// Verifies a certificate independently of how it was produced: every step
+/// must check, each q must be the next step's n, and the chain must end in a
+/// prime below the deterministic Miller–Rabin bound.
+pub fn verify_certificate(certificate: &Certificate) -> Result<(), &'static str> {
+    let mut expected = &certificate.n;
+    for step in &certificate.steps {
+        if &step.n != expected {
+            return Err("certificate chain is broken");
+        }
+        verify_step(step)?;
+        expected = &step.q;
+    }
+
+    let leaf = certificate.leaf();
+    if *leaf >= small_prime_bound() {
+        return Err("leaf is too large to prove directly");
+    }
+    if !is_prime_miller_rabin(leaf) {
+        return Err("leaf is not prime");
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prover::prove_prime;
+
+    fn certificate() -> Certificate {
+        let p: BigUint = "1000000000000000000000000000057".parse().unwrap();
+        let certificate = prove_prime(&p).unwrap();
+        assert!(!certificate.steps.is_empty());
+        certificate
+    }
+
+    #[test]
+    fn accepts_a_proven_prime() {
+        assert_eq!(verify_certificate(&certificate()), Ok(()));
+    }
+
+    #[test]
+    fn rejects_a_wrong_order() {
+        let mut forged = certificate();
+        forged.steps[0].m += 1u32;
+        assert_eq!(verify_certificate(&forged), Err("q does not divide m"));
+
+        // Still a multiple of q, but [m]P = [q]P ≠ O
+        let mut forged = certificate();
+        let q = forged.steps[0].q.clone();
+        forged.steps[0].m += q;
+        assert_eq!(
+            verify_certificate(&forged),
+            Err("[m]P is not the point at infinity")
+        );
+    }
+
+    #[test]
+    fn rejects_a_point_off_the_curve() {
+        let mut forged = certificate();
+        let step = &mut forged.steps[0];
+        step.point.1 = (&step.point.1 + 1u32) % &step.n;
+        assert_eq!(
+            verify_certificate(&forged),
+            Err("point is not on the curve")
+        );
+    }
+
+    #[test]
+    fn rejects_a_small_q() {
+        let mut forged = certificate();
+        forged.steps[0].q = BigUint::one();
+        assert_eq!(
+            verify_certificate(&forged),
+            Err("q is too small to imply primality of n")
+        );
+    }
+
+    #[test]
+    fn rejects_a_broken_chain() {
+        let mut forged = certificate();
+        forged.n += 2u32;
+        assert_eq!(
+            verify_certificate(&forged),
+            Err("certificate chain is broken")
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+/// An affine point on y² = x³ + ax + b mod n, `None` being the point at infinity.
+pub type Point = Option<(BigUint, BigUint)>;
+
+/// A short Weierstrass curve over Z/nZ, where n is only believed to be prime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Curve {
+    pub a: BigUint,
+    pub b: BigUint,
+    pub n: BigUint,
+}
+
+/// Raised when a denominator has no inverse mod n; the gcd proves n composite.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NotInvertible(pub BigUint);
+
+impl Curve {
+    pub fn new(a: BigUint, b: BigUint, n: BigUint) -> Self {
+        Curve {
+            a: a % &n,
+            b: b % &n,
+            n,
+        }
+    }
+
+    /// 4a³ + 27b² mod n, which must be a unit for the curve to be usable.
+    pub fn discriminant(&self) -> BigUint {
+        let n = &self.n;
+        (&self.a * &self.a % n * &self.a * 4u32 + &self.b * &self.b * 27u32) % n
+    }
+
+    pub fn rhs(&self, x: &BigUint) -> BigUint {
+        let n = &self.n;
+        (x * x % n * x + &self.a * x + &self.b) % n
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        match point {
+            None => true,
+            Some((x, y)) => y * y % &self.n == self.rhs(x),
+        }
+    }
+
+    pub fn add(&self, p: &Point, q: &Point) -> Result<Point, NotInvertible> {
+        let n = &self.n;
+        let (x1, y1) = match p {
+            Some(point) => point,
+            None => return Ok(q.clone()),
+        };
+        let (x2, y2) = match q {
+            Some(point) => point,
+            None => return Ok(p.clone()),
+        };
+        if x1 == x2 && (y1 + y2) % n == BigUint::zero() {
+            return Ok(None);
+        }
+
+        let lambda = if x1 == x2 {
+            (x1 * x1 * 3u32 + &self.a) % n * mod_inverse(&(y1 * 2u32), n)? % n
+        } else {
+            (y2 + n - y1) % n * mod_inverse(&((x2 + n - x1) % n), n)? % n
+        };
+        let x3 = (&lambda * &lambda + n * 2u32 - x1 - x2) % n;
+        let y3 = (lambda * ((x1 + n - &x3) % n) + n - y1) % n;
+        Ok(Some((x3, y3)))
+    }
+
+    /// k * point by double-and-add, failing as soon as an inversion does.
+    pub fn multiply(&self, k: &BigUint, point: &Point) -> Result<Point, NotInvertible> {
+        let mut result = None;
+        for i in (0..k.bits()).rev() {
+            result = self.add(&result, &result)?;
+            if k.bit(i) {
+                result = self.add(&result, point)?;
+            }
+        }
+        Ok(result)
+    }
+}
+
+pub fn mod_inverse(a: &BigUint, n: &BigUint) -> Result<BigUint, NotInvertible> {
+    let a = BigInt::from(a % n);
+    let modulus = BigInt::from(n.clone());
+    let extended = a.extended_gcd(&modulus);
+    if extended.gcd.is_one() {
+        Ok(extended.x.mod_floor(&modulus).to_biguint().unwrap())
+    } else {
+        Err(NotInvertible(extended.gcd.to_biguint().unwrap()))
+    }
+}
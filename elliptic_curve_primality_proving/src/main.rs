@@ -0,0 +1,55 @@
+mod certificate;
+mod curve;
+mod prover;
+
+use certificate::verify_certificate;
+use num_bigint::BigUint;
+use prover::prove_prime;
+use std::time::Instant;
+
+fn main() {
+    let primes = [
+        ("2^127 - 1", (BigUint::from(1u32) << 127u32) - 1u32),
+        (
+            "secp256k1 field prime",
+            BigUint::parse_bytes(
+                b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+                16,
+            )
+            .unwrap(),
+        ),
+        ("2^255 - 19", (BigUint::from(1u32) << 255u32) - 19u32),
+    ];
+
+    for (name, p) in &primes {
+        let start = Instant::now();
+        match prove_prime(p) {
+            Some(certificate) => {
+                println!(
+                    "{} ({} steps, {:?})",
+                    name,
+                    certificate.steps.len(),
+                    start.elapsed()
+                );
+                match verify_certificate(&certificate) {
+                    Ok(()) => println!("  certificate verified"),
+                    Err(e) => println!("  certificate rejected: {}", e),
+                }
+            }
+            None => println!("{}: no certificate found", name),
+        }
+    }
+
+    // Print a full chain for a smaller prime and show that tampering is caught
+    let p: BigUint = "1000000000000000000000000000057".parse().unwrap();
+    if let Some(certificate) = prove_prime(&p) {
+        println!("\n{}", certificate);
+        println!("verified: {:?}", verify_certificate(&certificate));
+
+        let mut forged = certificate.clone();
+        if let Some(step) = forged.steps.first_mut() {
+            step.point.1 += 1u32;
+        }
+        println!("tampered point: {:?}", verify_certificate(&forged));
+    }
+}
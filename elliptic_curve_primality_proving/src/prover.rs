@@ -0,0 +1,151 @@
+use cm_curves::{cornacchia, curves_with_j, reduce_j, sqrt_mod, CLASS_NUMBER_ONE};
+use integer_factorization::{is_probable_prime, primes_up_to};
+use num_bigint::{BigUint, RandBigInt};
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+use crate::certificate::{small_prime_bound, Certificate, CertificateStep};
+use crate::curve::{Curve, Point};
+
+/// Small primes stripped from a candidate order before testing the cofactor.
+const ORDER_TRIAL_BOUND: u64 = 100_000;
+/// Random points tried on a curve before giving up on it.
+const POINT_ATTEMPTS: usize = 20;
+
+/// A possible step: the curve order m = n + 1 - t from a CM discriminant,
+/// with m = k * q and q a probable prime.
+struct Candidate {
+    j: i64,
+    m: BigUint,
+    q: BigUint,
+}
+
+/// Removes small primes from m and keeps it if what is left is a probable
+/// prime large enough for the Goldwasser–Kilian condition.
+fn large_prime_part(m: &BigUint, n: &BigUint, small_primes: &[u64]) -> Option<BigUint> {
+    let mut q = m.clone();
+    for &p in small_primes {
+        let p = BigUint::from(p);
+        while (&q % &p).is_zero() && q > p {
+            q /= &p;
+        }
+    }
+
+    let root = n.nth_root(4) + 2u32;
+    if q >= *n || q <= &root * &root || !is_probable_prime(&q) {
+        return None;
+    }
+    Some(q)
+}
+
+/// Every candidate order for n from the class number one discriminants,
+/// smallest q first so the chain shrinks as fast as possible.
+fn candidates(n: &BigUint, small_primes: &[u64]) -> Vec<Candidate> {
+    let mut found = Vec::new();
+    for &(d, j) in CLASS_NUMBER_ONE.iter() {
+        let t = match cornacchia(d, n) {
+            Some((t, _)) => t,
+            None => continue,
+        };
+        let plus = n + 1u32 + &t;
+        let minus = n + 1u32 - &t;
+        for m in [minus, plus] {
+            if let Some(q) = large_prime_part(&m, n, small_primes) {
+                found.push(Candidate { j, m, q });
+            }
+        }
+    }
+    found.sort_by(|x, y| x.q.cmp(&y.q));
+    found
+}
+
+fn random_point(curve: &Curve) -> Option<(BigUint, BigUint)> {
+    let mut rng = rand::thread_rng();
+    for _ in 0..100 {
+        let x = rng.gen_biguint_below(&curve.n);
+        if let Some(y) = sqrt_mod(&curve.rhs(&x), &curve.n) {
+            return Some((x, y));
+        }
+    }
+    None
+}
+
+/// Looks for a curve of order m among those with the candidate's j-invariant
+/// and a point whose [m/q] multiple is not the identity.
+fn find_step(n: &BigUint, candidate: &Candidate) -> Option<CertificateStep> {
+    let cofactor = &candidate.m / &candidate.q;
+    let curves = curves_with_j(&reduce_j(candidate.j, n), n);
+    for curve in curves.into_iter().map(|(a, b)| Curve::new(a, b, n.clone())) {
+        if !curve.discriminant().gcd(n).is_one() {
+            continue;
+        }
+        for _ in 0..POINT_ATTEMPTS {
+            let point = random_point(&curve)?;
+            let p: Point = Some(point.clone());
+            let multiple = curve.multiply(&cofactor, &p).ok()?;
+            if multiple.is_none() {
+                // Unlucky point in the small subgroup, try another
+                continue;
+            }
+            match curve.multiply(&candidate.q, &multiple) {
+                Ok(None) => {
+                    return Some(CertificateStep {
+                        n: n.clone(),
+                        a: curve.a.clone(),
+                        b: curve.b.clone(),
+                        m: candidate.m.clone(),
+                        q: candidate.q.clone(),
+                        point,
+                    })
+                }
+                // Wrong twist, this curve does not have order m
+                Ok(Some(_)) => break,
+                Err(_) => return None,
+            }
+        }
+    }
+    None
+}
+
+/// Depth-first search for a chain down to the directly provable range,
+/// backing up to the next candidate whenever a q admits no further step.
+fn prove_recursive(
+    n: &BigUint,
+    small_primes: &[u64],
+    bound: &BigUint,
+) -> Option<Vec<CertificateStep>> {
+    if n < bound {
+        return Some(Vec::new());
+    }
+    for candidate in candidates(n, small_primes) {
+        let step = match find_step(n, &candidate) {
+            Some(step) => step,
+            None => continue,
+        };
+        if let Some(mut rest) = prove_recursive(&candidate.q, small_primes, bound) {
+            rest.insert(0, step);
+            return Some(rest);
+        }
+    }
+    None
+}
+
+/// Atkin–Morain style ECPP restricted to class number one discriminants.
+///
+/// Returns `None` when n is composite, divisible by 2 or 3, or when no chain
+/// could be found with the available discriminants.
+pub fn prove_prime(n: &BigUint) -> Option<Certificate> {
+    if !is_probable_prime(n) {
+        return None;
+    }
+    let bound = small_prime_bound();
+    if *n >= bound && !n.gcd(&BigUint::from(6u32)).is_one() {
+        return None;
+    }
+    let small_primes = primes_up_to(ORDER_TRIAL_BOUND);
+    let steps = prove_recursive(n, &small_primes, &bound)?;
+    Some(Certificate {
+        n: n.clone(),
+        steps,
+    })
+}
@@ -0,0 +1,432 @@
+use cm_curves::{curves_with_j, reduce_j, sqrt_mod, CLASS_NUMBER_ONE};
+use integer_factorization::{is_probable_prime, trial_division};
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use std::fmt;
+
+use crate::{compute_embedding_degree, log2};
+
+/// Points that must be killed by #E before a curve is accepted.
+const ORDER_CHECKS: usize = 3;
+/// Small primes allowed in the cofactor of an MNT curve.
+const MNT_COFACTOR_BOUND: u64 = 1000;
+/// Multiples of r tried on t and y when lifting a Cocks–Pinch solution.
+const LIFT_ATTEMPTS: u32 = 64;
+
+type Point = Option<(BigUint, BigUint)>;
+
+/// y² = x³ + ax + b over F_p in affine coordinates, `None` standing for
+/// the point at infinity.
+struct ShortCurve {
+    a: BigUint,
+    b: BigUint,
+    p: BigUint,
+}
+
+impl ShortCurve {
+    fn rhs(&self, x: &BigUint) -> BigUint {
+        (x * x % &self.p * x + &self.a * x + &self.b) % &self.p
+    }
+
+    fn contains(&self, point: &Point) -> bool {
+        match point {
+            None => true,
+            Some((x, y)) => y * y % &self.p == self.rhs(x),
+        }
+    }
+
+    fn inverse(&self, value: &BigUint) -> BigUint {
+        value.modpow(&(&self.p - 2u32), &self.p)
+    }
+
+    fn add(&self, p1: &Point, p2: &Point) -> Point {
+        let (Some((x1, y1)), Some((x2, y2))) = (p1, p2) else {
+            return if p1.is_none() { p2.clone() } else { p1.clone() };
+        };
+        let p = &self.p;
+        let lambda = if x1 != x2 {
+            (y2 + p - y1) * self.inverse(&((x2 + p - x1) % p)) % p
+        } else if y1 == y2 && !y1.is_zero() {
+            (x1 * x1 * 3u32 + &self.a) * self.inverse(&(y1 * 2u32 % p)) % p
+        } else {
+            return None;
+        };
+        let x3 = (&lambda * &lambda + p * 2u32 - x1 - x2) % p;
+        let y3 = (lambda * ((x1 + p - &x3) % p) + p - y1) % p;
+        Some((x3, y3))
+    }
+
+    fn multiply(&self, k: &BigUint, point: &Point) -> Point {
+        let mut result = None;
+        for i in (0..k.bits()).rev() {
+            result = self.add(&result, &result);
+            if k.bit(i) {
+                result = self.add(&result, point);
+            }
+        }
+        result
+    }
+
+    /// The points with x = 0, 1, 2, ..., one y for each.
+    fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0u64..)
+            .map(BigUint::from)
+            .take_while(|x| x < &self.p)
+            .filter_map(|x| sqrt_mod(&self.rhs(&x), &self.p).map(|y| Some((x, y))))
+    }
+}
+
+/// A curve from one of the families together with everything a pairing
+/// needs: #E(F_p) = p + 1 - t = h r and r | p^k - 1.
+pub struct PairingFriendlyCurve {
+    pub family: String,
+    pub p: BigUint,
+    pub r: BigUint,
+    pub k: u32,
+    pub trace: BigInt,
+    pub cofactor: BigUint,
+    pub a: BigUint,
+    pub b: BigUint,
+    pub generator: (BigUint, BigUint),
+}
+
+impl PairingFriendlyCurve {
+    /// Finds the curve with j-invariant j and trace t over F_p among the
+    /// twists, and a generator of its subgroup of order r.
+    fn from_parameters(
+        family: String,
+        p: BigUint,
+        trace: BigInt,
+        r: BigUint,
+        k: u32,
+        j: i64,
+    ) -> Result<Self, &'static str> {
+        let order = (BigInt::from(p.clone()) + 1u32 - &trace)
+            .to_biguint()
+            .ok_or("trace is larger than p + 1")?;
+        if !(&order % &r).is_zero() {
+            return Err("r does not divide the curve order");
+        }
+        let cofactor = &order / &r;
+
+        let curves = curves_with_j(&reduce_j(j, &p), &p);
+        for (a, b) in curves {
+            let curve = ShortCurve { a, b, p: p.clone() };
+            let right_twist = curve
+                .points()
+                .take(ORDER_CHECKS)
+                .all(|point| curve.multiply(&order, &point).is_none());
+            if !right_twist {
+                continue;
+            }
+            let Some((x, y)) = curve
+                .points()
+                .map(|point| curve.multiply(&cofactor, &point))
+                .find(Option::is_some)
+                .flatten()
+            else {
+                continue;
+            };
+            let found = PairingFriendlyCurve {
+                family,
+                p,
+                r,
+                k,
+                trace,
+                cofactor,
+                a: curve.a,
+                b: curve.b,
+                generator: (x, y),
+            };
+            found.validate()?;
+            return Ok(found);
+        }
+        Err("no twist has the expected order")
+    }
+
+    fn curve(&self) -> ShortCurve {
+        ShortCurve {
+            a: self.a.clone(),
+            b: self.b.clone(),
+            p: self.p.clone(),
+        }
+    }
+
+    /// Checks the parameters independently of the family formulas: p prime,
+    /// the Hasse bound, #E = h r, k from the embedding degree computation
+    /// and a generator of order exactly r.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        if !is_probable_prime(&self.p) {
+            return Err("p is not prime");
+        }
+        let four_p = BigInt::from(self.p.clone()) * 4u32;
+        if &self.trace * &self.trace > four_p {
+            return Err("trace violates the Hasse bound");
+        }
+        let order = BigInt::from(self.p.clone()) + 1u32 - &self.trace;
+        if order != BigInt::from(&self.cofactor * &self.r) {
+            return Err("#E is not the cofactor times r");
+        }
+        if compute_embedding_degree(&self.p, &self.r)? != BigUint::from(self.k) {
+            return Err("embedding degree differs from the family's");
+        }
+        let curve = self.curve();
+        let generator = Some(self.generator.clone());
+        if !curve.contains(&generator) {
+            return Err("generator is not on the curve");
+        }
+        if curve.multiply(&self.r, &generator).is_some() {
+            return Err("generator does not have order r");
+        }
+        Ok(())
+    }
+
+    /// ρ = log p / log r, 1 for an ideal family.
+    pub fn rho(&self) -> f64 {
+        log2(&self.p) / log2(&self.r)
+    }
+}
+
+impl fmt::Display for PairingFriendlyCurve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}", self.family)?;
+        writeln!(f, "y² = x³ + {}x + {}", self.a, self.b)?;
+        writeln!(f, "p = {} ({} bits)", self.p, self.p.bits())?;
+        writeln!(f, "r = {} ({} bits)", self.r, self.r.bits())?;
+        writeln!(f, "embedding degree k = {}", self.k)?;
+        writeln!(f, "trace t = {}", self.trace)?;
+        writeln!(f, "cofactor h = {}", self.cofactor)?;
+        writeln!(f, "ρ = {:.3}", self.rho())?;
+        write!(f, "G = ({}, {})", self.generator.0, self.generator.1)
+    }
+}
+
+/// Barreto–Naehrig curves, k = 12 and prime order, from the first u ≥ seed
+/// with p(u) = 36u⁴ + 36u³ + 24u² + 6u + 1 and n(u) = p(u) + 1 - t(u)
+/// both prime, where t(u) = 6u² + 1. They always have j = 0.
+pub fn bn(seed: i128, attempts: u32) -> Result<PairingFriendlyCurve, &'static str> {
+    for u in (seed..).take(attempts as usize) {
+        let u = BigInt::from(u);
+        let u2 = &u * &u;
+        let p = (&u2 * &u2 * 36u32 + &u2 * &u * 36u32 + &u2 * 24u32 + &u * 6u32 + 1u32)
+            .to_biguint()
+            .ok_or("p(u) is negative")?;
+        let t = u2 * 6u32 + 1u32;
+        let n = (BigInt::from(p.clone()) + 1u32 - &t).to_biguint().unwrap();
+        if is_probable_prime(&p) && is_probable_prime(&n) {
+            let family = format!("BN, u = {}", u);
+            return PairingFriendlyCurve::from_parameters(family, p, t, n, 12, 0);
+        }
+    }
+    Err("no BN curve within the given number of attempts")
+}
+
+/// Barreto–Lynn–Scott curves with k = 12 or 24 from the first x ≥ seed,
+/// x ≡ 1 (mod 3), with r = Φ_k(x) and p = (x - 1)² r / 3 + x both prime.
+/// The trace is x + 1, the cofactor (x - 1)² / 3 and j = 0.
+fn bls(k: u32, seed: i128, attempts: u32) -> Result<PairingFriendlyCurve, &'static str> {
+    let start = seed + (1 - seed).rem_euclid(3);
+    for x in (start..).step_by(3).take(attempts as usize) {
+        let x = BigInt::from(x);
+        let half = x.pow(k / 6);
+        let r = (&half * &half - &half + 1u32).to_biguint().unwrap();
+        let p = ((&x - 1u32).pow(2) * BigInt::from(r.clone()) / 3u32 + &x)
+            .to_biguint()
+            .ok_or("p(x) is negative")?;
+        if is_probable_prime(&r) && is_probable_prime(&p) {
+            let family = format!("BLS{}, x = {}", k, x);
+            return PairingFriendlyCurve::from_parameters(family, p, x + 1u32, r, k, 0);
+        }
+    }
+    Err("no BLS curve within the given number of attempts")
+}
+
+pub fn bls12(seed: i128, attempts: u32) -> Result<PairingFriendlyCurve, &'static str> {
+    bls(12, seed, attempts)
+}
+
+pub fn bls24(seed: i128, attempts: u32) -> Result<PairingFriendlyCurve, &'static str> {
+    bls(24, seed, attempts)
+}
+
+/// The fundamental solution of x² - n y² = 1 from the continued fraction
+/// of √n, for n not a square.
+fn pell_unit(n: u64) -> (BigInt, BigInt) {
+    let a0 = n.isqrt();
+    let (mut m, mut d, mut a) = (0u64, 1u64, a0);
+    let (mut h_previous, mut h) = (BigInt::one(), BigInt::from(a0));
+    let (mut k_previous, mut k) = (BigInt::zero(), BigInt::one());
+    while &h * &h - &k * &k * n != BigInt::one() {
+        m = d * a - m;
+        d = (n - m * m) / d;
+        a = (a0 + m) / d;
+        (h_previous, h) = (h.clone(), &h * a + h_previous);
+        (k_previous, k) = (k.clone(), &k * a + k_previous);
+    }
+    (h, k)
+}
+
+/// All solutions of x² - n y² = c, with c ≠ 0, whose x has at most
+/// `max_bits` bits: each class has a fundamental solution below Nagell's
+/// bound on y, and the rest follow by multiplying with the unit u + v√n.
+fn pell_solutions(n: u64, c: i64, max_bits: u64) -> Vec<BigInt> {
+    let (u, v) = pell_unit(n);
+    let bound = if c > 0 {
+        (&v * &v * c / ((&u + 1u32) * 2u32)).sqrt() + 1u32
+    } else {
+        ((&u + 1u32) * -c / (2 * n)).sqrt() + 1u32
+    };
+    let mut solutions = Vec::new();
+    for y in 0..=bound.to_u64().unwrap() {
+        let square = BigInt::from(n) * y * y + c;
+        if square.is_negative() || square.sqrt().pow(2) != square {
+            continue;
+        }
+        for x in [square.sqrt(), -square.sqrt()] {
+            let (mut x, mut y) = (x, BigInt::from(y));
+            while x.bits() <= max_bits {
+                solutions.push(x.clone());
+                (x, y) = (&x * &u + &y * &v * n, &x * &v + &y * &u);
+            }
+        }
+    }
+    solutions
+}
+
+/// The (p, t) pairs of the MNT family with embedding degree k reached from
+/// one x of the Pell equation, up to the sign of x:
+///   k = 3: p = 12l² - 1, t = -1 ± 6l, x = 6l ∓ 3 with x² - 3DV² = 24
+///   k = 4: p = l² + l + 1, t = -l or l + 1, x = 3l + 2 or 3l + 1, x² - 3DV² = -8
+///   k = 6: p = 4l² + 1, t = 1 ± 2l, x = 6l ∓ 1 with x² - 3DV² = -8
+fn mnt_candidates(k: u32, x: &BigInt) -> Vec<(BigInt, BigInt)> {
+    let mut found = Vec::new();
+    let mut try_offset = |modulus: u32, offset: i32, build: &dyn Fn(BigInt) -> (BigInt, BigInt)| {
+        let shifted = x - offset;
+        if shifted.is_multiple_of(&BigInt::from(modulus)) {
+            found.push(build(shifted / modulus));
+        }
+    };
+    match k {
+        3 => {
+            let p = |l: &BigInt| l * l * 12u32 - 1u32;
+            try_offset(6, 3, &|l| (p(&l), l * 6u32 - 1u32));
+            try_offset(6, -3, &|l| (p(&l), -(l * 6u32) - 1u32));
+        }
+        4 => {
+            let p = |l: &BigInt| l * l + l + 1u32;
+            try_offset(3, 2, &|l| (p(&l), -l));
+            try_offset(3, 1, &|l| (p(&l), l + 1u32));
+        }
+        6 => {
+            let p = |l: &BigInt| l * l * 4u32 + 1u32;
+            try_offset(6, -1, &|l| (p(&l), l * 2u32 + 1u32));
+            try_offset(6, 1, &|l| (p(&l), 1u32 - l * 2u32));
+        }
+        _ => {}
+    }
+    found
+}
+
+/// Miyaji–Nakabayashi–Takano curves with k = 3, 4 or 6 and p of at most
+/// `max_bits` bits. The CM equation DV² = 4p - t² turns into a Pell
+/// equation, solved for every class number one discriminant, so only a
+/// handful of small curves exist; larger ones need larger D. The order is
+/// accepted when it is a prime times a small cofactor.
+pub fn mnt(k: u32, max_bits: u64) -> Result<Vec<PairingFriendlyCurve>, &'static str> {
+    let c = match k {
+        3 => 24,
+        4 | 6 => -8,
+        _ => return Err("MNT curves have embedding degree 3, 4 or 6"),
+    };
+    let mut curves: Vec<PairingFriendlyCurve> = Vec::new();
+    for &(d, j) in CLASS_NUMBER_ONE.iter() {
+        let n = 3 * d;
+        if n.isqrt().pow(2) == n {
+            continue;
+        }
+        for x in pell_solutions(n, c, max_bits / 2 + 3) {
+            for (p, t) in mnt_candidates(k, &x) {
+                let Some(p) = p.to_biguint().filter(|p| p.bits() <= max_bits) else {
+                    continue;
+                };
+                if p < BigUint::from(MNT_COFACTOR_BOUND)
+                    || !is_probable_prime(&p)
+                    || curves.iter().any(|curve| curve.p == p && curve.trace == t)
+                {
+                    continue;
+                }
+                let order = (BigInt::from(p.clone()) + 1u32 - &t).to_biguint().unwrap();
+                let (_, r) = trial_division(&order, MNT_COFACTOR_BOUND);
+                if r.is_one() || !is_probable_prime(&r) {
+                    continue;
+                }
+                let family = format!("MNT k = {}, D = {}", k, d);
+                if let Ok(curve) = PairingFriendlyCurve::from_parameters(family, p, t, r, k, j) {
+                    curves.push(curve);
+                }
+            }
+        }
+    }
+    curves.sort_by(|x, y| x.p.cmp(&y.p));
+    Ok(curves)
+}
+
+/// Cocks–Pinch curves for any k with D = 3 (so j = 0): a prime r with
+/// k | r - 1 and -3 a square mod r, a primitive k-th root of unity z,
+/// t = z + 1 and y = (t - 2) / √-3 mod r, lifted by multiples of r until
+/// p = (t² + 3y²) / 4 is a prime. Then p ≡ t - 1 (mod r), so r divides
+/// p + 1 - t and p^k ≡ z^k = 1. The price is ρ ≈ 2.
+pub fn cocks_pinch(k: u32, r_bits: u64) -> Result<PairingFriendlyCurve, &'static str> {
+    if k == 0 || r_bits < 8 {
+        return Err("k must be positive and r at least 8 bits");
+    }
+    let step = BigUint::from(k.lcm(&3));
+    let mut r = (BigUint::one() << (r_bits - 1)) / &step * &step + 1u32;
+    loop {
+        if r.bits() >= r_bits && is_probable_prime(&r) {
+            if let Ok(curve) = cocks_pinch_with(k, &r) {
+                return Ok(curve);
+            }
+        }
+        r += &step;
+        if r.bits() > r_bits {
+            return Err("no Cocks-Pinch curve with an r of this size");
+        }
+    }
+}
+
+fn cocks_pinch_with(k: u32, r: &BigUint) -> Result<PairingFriendlyCurve, &'static str> {
+    let r_minus_1 = r - 1u32;
+    let k_primes = (2..=k).filter(|&q| k.is_multiple_of(q) && (2..q).all(|d| !q.is_multiple_of(d)));
+    let k_primes: Vec<u32> = k_primes.collect();
+    let z = (2u32..)
+        .map(|g| BigUint::from(g).modpow(&(&r_minus_1 / k), r))
+        .find(|z| {
+            k_primes
+                .iter()
+                .all(|&q| !z.modpow(&BigUint::from(k / q), r).is_one())
+        })
+        .unwrap();
+    let root = sqrt_mod(&(r - 3u32), r).ok_or("-3 is not a square mod r")?;
+
+    let t = (&z + 1u32) % r;
+    let y = (&t + r - 2u32) % r * root.modpow(&(r - 2u32), r) % r;
+    for i in 0..LIFT_ATTEMPTS {
+        let t = &t + r * i;
+        for j in 0..LIFT_ATTEMPTS {
+            let y = &y + r * j;
+            let four_p = &t * &t + &y * &y * 3u32;
+            if !(&four_p % 4u32).is_zero() {
+                continue;
+            }
+            let p = four_p / 4u32;
+            if !is_probable_prime(&p) {
+                continue;
+            }
+            let family = format!("Cocks–Pinch k = {}, D = 3", k);
+            return PairingFriendlyCurve::from_parameters(family, p, t.into(), r.clone(), k, 0);
+        }
+    }
+    Err("no prime p among the lifts")
+}
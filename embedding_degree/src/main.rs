@@ -1,30 +1,310 @@
-use num_bigint::{BigUint};
-use num_traits::{Zero, One};
+mod families;
 
+use integer_factorization::{is_probable_prime, Factorization};
+use num_bigint::BigUint;
+use num_traits::{One, ToPrimitive, Zero};
+use std::fmt;
 
-fn compute_embedding_degree(p: BigUint, r: BigUint) -> Option<u32> {
-    let mut k = 1u32;
-    let mut power = BigUint::one();
+/// Embedding degrees up to this bound always make the MOV transfer
+/// practical, the supersingular curves having k ≤ 6.
+const MOV_DEGREE_BOUND: u32 = 6;
+/// Fields F_{p^k} with fewer bits than this fall to index calculus outright.
+const MOV_FIELD_BITS_FLOOR: f64 = 1024.0;
 
-    //Iterate untill we find the smallest k such that r divides (p^k - 1)
-    loop {
-        power = power * &p;
-        let power = power.clone() - BigUint::one();
-        if power % &r == BigUint::zero() {
-            return Some(k);
+/// Everything the embedding degree tells us about a prime-order subgroup
+/// of E(F_p) and the pairing-based attacks on it.
+struct EmbeddingReport {
+    p: BigUint,
+    r: BigUint,
+    cofactor: Option<BigUint>,
+    k: BigUint,
+    rho: f64,
+    field_bits: f64,
+    ecdlp_security: f64,
+    field_security: f64,
+}
+
+impl EmbeddingReport {
+    /// The report for a subgroup of prime order r, with r ∤ p.
+    fn new(p: &BigUint, r: &BigUint) -> Result<Self, &'static str> {
+        let k = compute_embedding_degree(p, r)?;
+        let field_bits = k.to_f64().unwrap_or(f64::INFINITY) * log2(p);
+        Ok(EmbeddingReport {
+            p: p.clone(),
+            r: r.clone(),
+            cofactor: None,
+            rho: log2(p) / log2(r),
+            field_bits,
+            ecdlp_security: log2(r) / 2.0,
+            field_security: finite_field_security(field_bits),
+            k,
+        })
+    }
+
+    /// The report for y² = x³ + ax + b over F_p, taking r to be the largest
+    /// prime factor of #E(F_p) found by counting points.
+    fn for_curve(a: u64, b: u64, p: u64) -> Result<Self, &'static str> {
+        let (a_wide, b_wide, p_wide) = (a as u128, b as u128, p as u128);
+        let discriminant = 4 * a_wide * a_wide % p_wide * a_wide + 27 * b_wide * b_wide;
+        if discriminant.is_multiple_of(p_wide) {
+            return Err("Curve is singular");
         }
-        k += 1;
+        let order = Factorization::of_u64(count_points(a, b, p))?;
+        let (h, r) = order.cofactor_split().ok_or("Curve has a single point")?;
+        let mut report = Self::new(&BigUint::from(p), &r)?;
+        report.cofactor = Some(h);
+        Ok(report)
+    }
+
+    /// Security in bits: the cheaper of Pollard rho on the curve and the
+    /// discrete logarithm in F_{p^k} reached through the pairing.
+    fn security_level(&self) -> f64 {
+        self.ecdlp_security.min(self.field_security)
+    }
+
+    /// The MOV / Frey–Rück reduction applies whenever the pairing can be
+    /// computed and lands in a field where index calculus is practical: a
+    /// small embedding degree, a small F_{p^k}, or a finite field discrete
+    /// logarithm easier than the curve one.
+    fn mov_vulnerable(&self) -> bool {
+        self.k <= BigUint::from(MOV_DEGREE_BOUND)
+            || self.field_bits < MOV_FIELD_BITS_FLOOR
+            || self.field_security < self.ecdlp_security
+    }
+}
 
-        if k > 100000 {
-            return None;
+impl fmt::Display for EmbeddingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "p = {}", self.p)?;
+        match &self.cofactor {
+            Some(h) => writeln!(f, "#E = {} * {}", h, self.r)?,
+            None => writeln!(f, "r = {}", self.r)?,
         }
+        writeln!(f, "embedding degree k = {}", self.k)?;
+        writeln!(f, "ρ = log p / log r = {:.3}", self.rho)?;
+        writeln!(
+            f,
+            "security: ECDLP {:.0} bits, DLP in F_p^k {:.0} bits, overall {:.0} bits",
+            self.ecdlp_security,
+            self.field_security,
+            self.security_level()
+        )?;
+        if self.mov_vulnerable() {
+            write!(
+                f,
+                "MOV/FR attack: VULNERABLE, transfer the DLP to F_p^{}",
+                self.k
+            )
+        } else {
+            write!(f, "MOV/FR attack: not profitable")
+        }
+    }
+}
+
+/// log2 of a big integer, exact enough for bit-size estimates.
+fn log2(n: &BigUint) -> f64 {
+    let bits = n.bits();
+    if bits <= 64 {
+        return n.to_f64().unwrap().log2();
     }
+    let shift = bits - 64;
+    (n >> shift).to_f64().unwrap().log2() + shift as f64
+}
+
+/// Bits of security of the discrete logarithm in a field of the given size,
+/// from the number field sieve running time
+///   L_Q[1/3, (64/9)^(1/3)] = exp((64/9)^(1/3) (ln Q)^(1/3) (ln ln Q)^(2/3))
+/// shifted so that a 3072-bit field gives the usual 128 bits. This ignores
+/// the faster tower NFS variants for extension fields, so it is optimistic.
+fn finite_field_security(bits: f64) -> f64 {
+    let nfs_bits = |bits: f64| {
+        let ln_q = bits * std::f64::consts::LN_2;
+        (64.0f64 / 9.0).cbrt() * ln_q.cbrt() * ln_q.ln().powf(2.0 / 3.0) / std::f64::consts::LN_2
+    };
+    if bits.is_infinite() {
+        return f64::INFINITY;
+    }
+    (nfs_bits(bits) - nfs_bits(3072.0) + 128.0).max(0.0)
+}
+
+fn pow_mod(base: u64, exponent: u64, m: u64) -> u64 {
+    let mut result = 1u128;
+    let mut base = base as u128 % m as u128;
+    let mut exponent = exponent;
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % m as u128;
+        }
+        base = base * base % m as u128;
+        exponent >>= 1;
+    }
+    result as u64
+}
+
+/// #E(F_p) = p + 1 + Σ_x (x³ + ax + b | p), one Legendre symbol per x.
+fn count_points(a: u64, b: u64, p: u64) -> u64 {
+    let mut count = 1;
+    for x in 0..p {
+        let rhs =
+            ((x as u128 * x as u128 % p as u128 * x as u128 + a as u128 * x as u128 + b as u128)
+                % p as u128) as u64;
+        if rhs == 0 {
+            count += 1;
+        } else if pow_mod(rhs, (p - 1) / 2, p) == 1 {
+            count += 2;
+        }
+    }
+    count
+}
+
+/// Embedding degrees up to this bound are found by stepping through p^k.
+const DIRECT_SEARCH_LIMIT: u32 = 1000;
+
+/// The smallest k with r | p^k - 1, i.e. the multiplicative order of p mod r.
+/// Beyond the direct search it divides r - 1, so we start there and strip each prime factor q of
+/// r - 1 for as long as p^(k/q) ≡ 1 (mod r).
+fn compute_embedding_degree(p: &BigUint, r: &BigUint) -> Result<BigUint, &'static str> {
+    if !is_probable_prime(r) {
+        return Err("r must be prime");
+    }
+    if (p % r).is_zero() {
+        return Err("r divides p: the subgroup is anomalous, not pairing-friendly");
+    }
+    // Pairing-friendly curves have tiny k, found before factoring r - 1
+    let p_mod_r = p % r;
+    let mut power = p_mod_r.clone();
+    for k in 1..=DIRECT_SEARCH_LIMIT {
+        if power.is_one() {
+            return Ok(BigUint::from(k));
+        }
+        power = power * &p_mod_r % r;
+    }
+
+    let r_minus_1 = r - 1u32;
+    let factorization = Factorization::of(&r_minus_1)?;
+
+    let mut k = r_minus_1;
+    for q in factorization.primes() {
+        while (&k % q).is_zero() && p.modpow(&(&k / q), r).is_one() {
+            k /= q;
+        }
+    }
+    Ok(k)
 }
 
 fn main() {
     let p = BigUint::from(13u32);
     let r = BigUint::from(2u32);
 
-    let embedding_degree = compute_embedding_degree(p, r).unwrap();
+    let embedding_degree = compute_embedding_degree(&p, &r).unwrap();
     println!("Embedding degree: {}", embedding_degree);
-}
\ No newline at end of file
+
+    // The curve of the torsion example, whose 5-torsion lives in F_13^4
+    println!("\ny² = x³ + 8x + 8 over F_13");
+    match EmbeddingReport::for_curve(8, 8, 13) {
+        Ok(report) => println!("{}", report),
+        Err(e) => println!("Error: {}", e),
+    }
+
+    // Supersingular y² = x³ + x with p ≡ 3 (mod 4) has p + 1 points and k = 2
+    println!("\ny² = x³ + x over F_1000003");
+    match EmbeddingReport::for_curve(1, 0, 1_000_003) {
+        Ok(report) => println!("{}", report),
+        Err(e) => println!("Error: {}", e),
+    }
+
+    // A random-looking curve, where k is as large as r itself
+    println!("\ny² = x³ + 2x + 5 over F_1000003");
+    match EmbeddingReport::for_curve(2, 5, 1_000_003) {
+        Ok(report) => println!("{}", report),
+        Err(e) => println!("Error: {}", e),
+    }
+
+    // BN254, where k = 12 is chosen on purpose
+    let p = BigUint::parse_bytes(
+        b"21888242871839275222246405745257275088696311157297823662689037894645226208583",
+        10,
+    )
+    .unwrap();
+    let r = BigUint::parse_bytes(
+        b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+        10,
+    )
+    .unwrap();
+    println!("\nBN254");
+    match EmbeddingReport::new(&p, &r) {
+        Ok(report) => println!("{}", report),
+        Err(e) => println!("Error: {}", e),
+    }
+
+    println!("\nr = p");
+    match compute_embedding_degree(&p, &p) {
+        Ok(k) => println!("k = {}", k),
+        Err(e) => println!("Error: {}", e),
+    }
+
+    // Generated families, each checked against compute_embedding_degree
+    for k in [3, 4, 6] {
+        match families::mnt(k, 64) {
+            Ok(curves) => {
+                println!("\n{} MNT curves with k = {}, the largest:", curves.len(), k);
+                if let Some(curve) = curves.last() {
+                    println!("{}", curve);
+                }
+            }
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
+    let generated = [
+        ("BN from 2^30", families::bn(1 << 30, 100)),
+        ("BN254 seed", families::bn(4965661367192848881, 1)),
+        ("BLS12-381 seed", families::bls12(-0xd201000000010000, 1)),
+        ("BLS24 from 2^20", families::bls24(1 << 20, 100)),
+        ("Cocks-Pinch, k = 5", families::cocks_pinch(5, 64)),
+        ("Cocks-Pinch, k = 7", families::cocks_pinch(7, 64)),
+    ];
+    for (name, result) in generated {
+        println!("\n{}", name);
+        match result {
+            Ok(curve) => println!("{}", curve),
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn supersingular_curve_is_mov_vulnerable() {
+        let report = EmbeddingReport::for_curve(1, 0, 1_000_003).unwrap();
+        assert_eq!(report.k, BigUint::from(2u32));
+        assert!(report.mov_vulnerable());
+    }
+
+    #[test]
+    fn large_embedding_degree_is_not_mov_vulnerable() {
+        let report = EmbeddingReport::for_curve(2, 5, 1_000_003).unwrap();
+        assert_eq!(report.k, BigUint::from(4002u32));
+        assert!(!report.mov_vulnerable());
+    }
+
+    #[test]
+    fn bn254_is_not_mov_vulnerable() {
+        let p = BigUint::parse_bytes(
+            b"21888242871839275222246405745257275088696311157297823662689037894645226208583",
+            10,
+        )
+        .unwrap();
+        let r = BigUint::parse_bytes(
+            b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
+            10,
+        )
+        .unwrap();
+        let report = EmbeddingReport::new(&p, &r).unwrap();
+        assert_eq!(report.k, BigUint::from(12u32));
+        assert!(!report.mov_vulnerable());
+    }
+}
@@ -0,0 +1,245 @@
+use rand::Rng;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::{elliptic_add, scalar_mult, Point};
+
+// The logarithm found in the interval and the group additions spent
+#[derive(Debug)]
+pub struct KangarooSolution {
+    pub k: i64,
+    pub steps: u64,
+}
+
+// Jumps of size 2^i, i < L, chosen by a hash of the current point, so that
+// two kangaroos landing on the same point follow the same path from then on
+struct Jumps {
+    sizes: Vec<i64>,
+    points: Vec<Point>,
+    seed: u64,
+    a: i64,
+    m: i64,
+}
+
+impl Jumps {
+    // Powers of two whose average is at least `mean`
+    fn new(g: &Point, mean: i64, seed: u64, a: i64, m: i64) -> Self {
+        let mut count = 1;
+        while ((1i64 << count) - 1) / (count as i64) < mean {
+            count += 1;
+        }
+        let sizes: Vec<i64> = (0..count).map(|i| 1i64 << i).collect();
+        let points = sizes.iter().map(|&s| scalar_mult(s, g, a, m)).collect();
+        Jumps {
+            sizes,
+            points,
+            seed,
+            a,
+            m,
+        }
+    }
+
+    fn index(&self, point: &Point) -> usize {
+        let hash = (point.x as u64 ^ self.seed).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        (hash >> 32) as usize % self.sizes.len()
+    }
+
+    // Moves the kangaroo one jump, returning the distance travelled
+    fn jump(&self, point: &mut Point) -> i64 {
+        let i = self.index(point);
+        *point = elliptic_add(point, &self.points[i], self.a, self.m);
+        self.sizes[i]
+    }
+}
+
+fn check_interval(g: &Point, interval: &RangeInclusive<i64>) -> Result<(i64, i64), &'static str> {
+    if g.is_at_infinity() {
+        return Err("Base point must not be the point at infinity");
+    }
+    let (lower, upper) = (*interval.start(), *interval.end());
+    if lower < 0 || upper < lower {
+        return Err("Interval must satisfy 0 <= lower <= upper");
+    }
+    Ok((lower, upper))
+}
+
+// Pollard's kangaroo (lambda) method for Q = [k]G with k in [lower, upper].
+// A tame kangaroo starts at [upper]G and sets a trap where it stops; a wild
+// one starts at Q and uses the same jumps, so once it lands on a footprint
+// of the tame one it ends in the trap and k follows from the distances.
+// The cost is about 2√w for an interval of width w, whatever the group
+// order. Gives up once `max_steps` additions have been spent.
+pub fn kangaroo(
+    g: &Point,
+    q: &Point,
+    interval: RangeInclusive<i64>,
+    a: i64,
+    m: i64,
+    max_steps: u64,
+) -> Result<KangarooSolution, &'static str> {
+    let (lower, upper) = check_interval(g, &interval)?;
+    let width = upper - lower;
+    let root = ((width as f64).sqrt() as i64).max(1);
+    let mut steps = 0u64;
+
+    // A failed round is retried with a different jump function
+    for seed in 0.. {
+        let jumps = Jumps::new(g, (root / 2).max(1), seed, a, m);
+
+        let mut tame = scalar_mult(upper, g, a, m);
+        let mut tame_distance = 0i64;
+        for _ in 0..root {
+            tame_distance += jumps.jump(&mut tame);
+        }
+        steps += root as u64;
+
+        let mut wild = q.clone();
+        let mut wild_distance = 0i64;
+        while wild_distance <= width + tame_distance {
+            if wild == tame {
+                let k = upper + tame_distance - wild_distance;
+                if interval.contains(&k) && scalar_mult(k, g, a, m) == *q {
+                    return Ok(KangarooSolution { k, steps });
+                }
+                break;
+            }
+            if steps >= max_steps {
+                return Err("Work bound exceeded before the kangaroos met");
+            }
+            wild_distance += jumps.jump(&mut wild);
+            steps += 1;
+        }
+        if steps >= max_steps {
+            break;
+        }
+    }
+    Err("Work bound exceeded before the kangaroos met")
+}
+
+// What the shared table remembers about a distinguished point: the kind of
+// kangaroo and its offset, [offset]G for a tame and Q + [offset]G for a wild
+#[derive(Clone, Copy)]
+enum Footprint {
+    Tame(i64),
+    Wild(i64),
+}
+
+impl Footprint {
+    fn advance(self, distance: i64) -> Self {
+        match self {
+            Footprint::Tame(offset) => Footprint::Tame(offset + distance),
+            Footprint::Wild(offset) => Footprint::Wild(offset + distance),
+        }
+    }
+}
+
+// The parallel version of van Oorschot and Wiener: every thread runs one
+// tame and one wild kangaroo, spaced apart inside the interval, and reports
+// distinguished points to a shared table. A tame and a wild kangaroo
+// meeting anywhere end at the same distinguished point and reveal k; two of
+// the same kind meeting follow each other forever, so the later one is
+// pushed forward by a random distance.
+pub fn kangaroo_parallel(
+    g: &Point,
+    q: &Point,
+    interval: RangeInclusive<i64>,
+    a: i64,
+    m: i64,
+    threads: usize,
+    max_steps: u64,
+) -> Result<KangarooSolution, &'static str> {
+    let (lower, upper) = check_interval(g, &interval)?;
+    let threads = threads.max(1);
+    let width = (upper - lower).max(1);
+    let root = (width as f64).sqrt();
+
+    // With n kangaroos the mean jump is n√w/4, and about one point in
+    // 2^bits is distinguished so each kangaroo reports every √w/(8n) or
+    // so jumps
+    let herd = 2 * threads as i64;
+    let mean = ((herd as f64 * root / 4.0) as i64).max(1);
+    let bits = (root / (8.0 * herd as f64)).max(1.0).log2() as u32;
+    let mask = (1i64 << bits) - 1;
+    let jumps = Jumps::new(g, mean, 0, a, m);
+
+    let done = AtomicBool::new(false);
+    let steps = AtomicU64::new(0);
+    let table: Mutex<HashMap<Point, Footprint>> = Mutex::new(HashMap::new());
+    let result = Mutex::new(None);
+
+    thread::scope(|scope| {
+        for id in 0..threads {
+            let (jumps, done, steps, table, result) = (&jumps, &done, &steps, &table, &result);
+            let interval = &interval;
+            scope.spawn(move || {
+                let mut rng = rand::thread_rng();
+                // Tame kangaroos spread around the middle of the interval,
+                // wild ones around Q, with spacing so they never coincide
+                let spacing = (mean / herd).max(1);
+                let tame_start = lower + width / 2 + id as i64 * spacing;
+                let wild_start = id as i64 * spacing;
+                let mut kangaroos = [
+                    (
+                        scalar_mult(tame_start, g, a, m),
+                        Footprint::Tame(tame_start),
+                    ),
+                    (
+                        elliptic_add(q, &scalar_mult(wild_start, g, a, m), a, m),
+                        Footprint::Wild(wild_start),
+                    ),
+                ];
+
+                while !done.load(Ordering::Relaxed) {
+                    if steps.fetch_add(2, Ordering::Relaxed) >= max_steps {
+                        done.store(true, Ordering::Relaxed);
+                        return;
+                    }
+                    for (point, footprint) in kangaroos.iter_mut() {
+                        *footprint = footprint.advance(jumps.jump(point));
+                        if point.is_at_infinity() || point.x & mask != 0 {
+                            continue;
+                        }
+
+                        let mut table = table.lock().unwrap();
+                        let k = match (table.get(point).copied(), *footprint) {
+                            (None, _) => {
+                                table.insert(point.clone(), *footprint);
+                                continue;
+                            }
+                            (Some(Footprint::Tame(t)), Footprint::Wild(w))
+                            | (Some(Footprint::Wild(w)), Footprint::Tame(t)) => Some(t - w),
+                            (Some(_), _) => None,
+                        };
+                        // A kangaroo that wrapped around the group gives k
+                        // plus a multiple of the order, which is no better
+                        // than two of the same kind meeting
+                        match k {
+                            Some(k) if interval.contains(&k) && scalar_mult(k, g, a, m) == *q => {
+                                *result.lock().unwrap() = Some(k);
+                                done.store(true, Ordering::Relaxed);
+                                return;
+                            }
+                            _ => {
+                                let shift = rng.gen_range(1..=mean);
+                                *point = elliptic_add(point, &scalar_mult(shift, g, a, m), a, m);
+                                *footprint = footprint.advance(shift);
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    let k = result
+        .into_inner()
+        .unwrap()
+        .ok_or("Work bound exceeded before a tame and a wild kangaroo met")?;
+    Ok(KangarooSolution {
+        k,
+        steps: steps.load(Ordering::Relaxed),
+    })
+}
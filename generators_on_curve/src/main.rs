@@ -1,12 +1,21 @@
+mod kangaroo;
+mod pollard_rho;
+
+use integer_factorization::Factorization;
+use kangaroo::{kangaroo, kangaroo_parallel};
+use num_traits::ToPrimitive;
+use pollard_rho::{pollard_rho, pollard_rho_parallel, CycleDetection};
+use rand::Rng;
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash)]
 struct Point {
-    x: i32,
-    y: i32,
+    x: i64,
+    y: i64,
     infinity: bool,
 }
 
 impl Point {
-    fn new(x: i32, y: i32) -> Self {
+    fn new(x: i64, y: i64) -> Self {
         Self {
             x,
             y,
@@ -27,8 +36,28 @@ impl Point {
     }
 }
 
-fn mod_inv(a: i32, m: i32) -> i32 {
-    let (mut t, mut new_t, mut r, mut new_r) = (0, 1, m, a.rem_euclid(m));
+// Products are taken in i128 so the modulus can use the full i64 range
+fn mul_mod(a: i64, b: i64, m: i64) -> i64 {
+    ((a as i128 * b as i128).rem_euclid(m as i128)) as i64
+}
+
+fn pow_mod(base: i64, exp: i64, m: i64) -> i64 {
+    let mut result = 1;
+    let mut base = base.rem_euclid(m);
+    let mut exp = exp;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+    result
+}
+
+fn mod_inv(a: i64, m: i64) -> i64 {
+    let (mut t, mut new_t, mut r, mut new_r) = (0i64, 1i64, m, a.rem_euclid(m));
     while new_r != 0 {
         let quotient = r / new_r;
         t -= quotient * new_t;
@@ -39,7 +68,53 @@ fn mod_inv(a: i32, m: i32) -> i32 {
     t.rem_euclid(m)
 }
 
-fn elliptic_add(p: &Point, q: &Point, a: i32, m: i32) -> Point {
+// Square root modulo an odd prime using Tonelli-Shanks
+fn mod_sqrt(n: i64, p: i64) -> Option<i64> {
+    let n = n.rem_euclid(p);
+    if n == 0 {
+        return Some(0);
+    }
+    if pow_mod(n, (p - 1) / 2, p) != 1 {
+        return None;
+    }
+
+    // Write p - 1 = q * 2^s with q odd
+    let mut q = p - 1;
+    let mut s = 0;
+    while q % 2 == 0 {
+        q /= 2;
+        s += 1;
+    }
+
+    // Any quadratic non-residue will do
+    let mut z = 2;
+    while pow_mod(z, (p - 1) / 2, p) != p - 1 {
+        z += 1;
+    }
+
+    let mut m = s;
+    let mut c = pow_mod(z, q, p);
+    let mut t = pow_mod(n, q, p);
+    let mut r = pow_mod(n, (q + 1) / 2, p);
+
+    while t != 1 {
+        let mut i = 0;
+        let mut t2i = t;
+        while t2i != 1 {
+            t2i = mul_mod(t2i, t2i, p);
+            i += 1;
+        }
+        let b = pow_mod(c, 1 << (m - i - 1), p);
+        m = i;
+        c = mul_mod(b, b, p);
+        t = mul_mod(t, c, p);
+        r = mul_mod(r, b, p);
+    }
+    Some(r)
+}
+
+// Every intermediate sum stays within (-2m, 2m), so any m < 2^62 is safe
+fn elliptic_add(p: &Point, q: &Point, a: i64, m: i64) -> Point {
     if p.is_at_infinity() {
         return q.clone();
     }
@@ -52,22 +127,28 @@ fn elliptic_add(p: &Point, q: &Point, a: i32, m: i32) -> Point {
 
     let (x1, y1, x2, y2) = (p.x, p.y, q.x, q.y);
     let lambda = if p == q {
-        ((3 * x1 * x1 + a) * mod_inv(2 * y1, m)).rem_euclid(m)
+        let numerator = (mul_mod(3, mul_mod(x1, x1, m), m) + a.rem_euclid(m)) % m;
+        mul_mod(numerator, mod_inv(mul_mod(2, y1, m), m), m)
     } else {
-        ((y2 - y1) * mod_inv(x2 - x1, m)).rem_euclid(m)
+        mul_mod((y2 - y1).rem_euclid(m), mod_inv(x2 - x1, m), m)
     };
 
-    let x3 = (lambda * lambda - x1 - x2).rem_euclid(m);
-    let y3 = (lambda * (x1 - x3) - y1).rem_euclid(m);
+    let x3 = (mul_mod(lambda, lambda, m) - x1 - x2).rem_euclid(m);
+    let y3 = (mul_mod(lambda, x1 - x3, m) - y1).rem_euclid(m);
 
     Point::new(x3, y3)
 }
 
-fn scalar_mult(n: i64, p: &Point, a: i32, m: i32) -> Point {
+// [n]P = [-n](-P) for negative n
+fn scalar_mult(n: i64, p: &Point, a: i64, m: i64) -> Point {
     let mut r = Point::at_infinity();
-    let mut q = p.clone();
-    let mut n = n;
-    
+    let mut q = if n < 0 && !p.is_at_infinity() {
+        Point::new(p.x, (-p.y).rem_euclid(m))
+    } else {
+        p.clone()
+    };
+    let mut n = n.unsigned_abs();
+
     while n > 0 {
         if n & 1 == 1 {
             r = elliptic_add(&r, &q, a, m);
@@ -78,18 +159,18 @@ fn scalar_mult(n: i64, p: &Point, a: i32, m: i32) -> Point {
     r
 }
 
-fn is_point_on_curve(x: i32, y: i32, a: i32, b: i32, m: i32) -> bool {
-    let left = y.pow(2).rem_euclid(m);
-    let right = (x.pow(3) + a * x + b).rem_euclid(m);
+fn is_point_on_curve(x: i64, y: i64, a: i64, b: i64, m: i64) -> bool {
+    let left = mul_mod(y, y, m);
+    let right = (mul_mod(mul_mod(x, x, m), x, m) + mul_mod(a, x, m) + b).rem_euclid(m);
     left == right
 }
 
-fn find_all_points(a: i32, b: i32, m: i32) -> Vec<Point> {
+fn find_all_points(a: i64, b: i64, m: i64) -> Vec<Point> {
     let mut points = Vec::new();
-    
+
     // Add point at infinity
     points.push(Point::at_infinity());
-    
+
     // Check all possible x and y coordinates
     for x in 0..m {
         for y in 0..m {
@@ -98,43 +179,113 @@ fn find_all_points(a: i32, b: i32, m: i32) -> Vec<Point> {
             }
         }
     }
-    
+
     points
 }
 
-fn find_generators(points: &[Point], a: i32, m: i32) -> Vec<Point> {
-    let total_points = points.len();
+// Counts #E(F_m) in O(m log m) by summing Legendre symbols instead of
+// testing every (x, y) pair
+fn count_points(a: i64, b: i64, m: i64) -> i64 {
+    let mut count = 1;
+    for x in 0..m {
+        let rhs = (mul_mod(mul_mod(x, x, m), x, m) + mul_mod(a, x, m) + b).rem_euclid(m);
+        if rhs == 0 {
+            count += 1;
+        } else if pow_mod(rhs, (m - 1) / 2, m) == 1 {
+            count += 2;
+        }
+    }
+    count
+}
+
+// Distinct prime factors of n, smallest first
+fn prime_factors(n: i64) -> Vec<i64> {
+    let factorization = Factorization::of_u64(n.unsigned_abs()).unwrap();
+    factorization
+        .primes()
+        .map(|q| q.to_i64().unwrap())
+        .collect()
+}
+
+// Euler's totient from the distinct prime factors of n
+fn euler_totient(n: i64, factors: &[i64]) -> i64 {
+    factors.iter().fold(n, |phi, &q| phi / q * (q - 1))
+}
+
+// A point P generates a cyclic group of order n exactly when [n]P = O and
+// [n/q]P != O for every prime q dividing n
+fn is_generator(point: &Point, n: i64, factors: &[i64], a: i64, m: i64) -> bool {
+    if point.is_at_infinity() {
+        return n == 1;
+    }
+    if !scalar_mult(n, point, a, m).is_at_infinity() {
+        return false;
+    }
+    factors
+        .iter()
+        .all(|&q| !scalar_mult(n / q, point, a, m).is_at_infinity())
+}
+
+// Number of generators of a cyclic group of order n
+fn count_generators(n: i64) -> i64 {
+    euler_totient(n, &prime_factors(n))
+}
+
+fn find_generators(points: &[Point], a: i64, m: i64) -> Vec<Point> {
+    let total_points = points.len() as i64;
+    let factors = prime_factors(total_points);
     let mut generators = Vec::new();
-    
+
     for point in points {
         if point.is_at_infinity() {
             continue;
         }
-        
-        let mut subgroup_size = 1;
-        let mut current = point.clone();
-        
-        while !current.is_at_infinity() && subgroup_size <= total_points {
-            current = elliptic_add(&current, point, a, m);
-            subgroup_size += 1;
-        }
-        
-        if subgroup_size == total_points {
+
+        if is_generator(point, total_points, &factors, a, m) {
             generators.push(point.clone());
-            println!("Found generator: {:?} with order {}", point, subgroup_size);
+            println!("Found generator: {:?} with order {}", point, total_points);
         }
     }
-    
+
     generators
 }
 
+// Picks a uniformly random x until x³ + ax + b is a square, then lifts it
+fn random_point<R: Rng>(a: i64, b: i64, m: i64, rng: &mut R) -> Point {
+    loop {
+        let x = rng.gen_range(0..m);
+        let rhs = (mul_mod(mul_mod(x, x, m), x, m) + mul_mod(a, x, m) + b).rem_euclid(m);
+        if let Some(y) = mod_sqrt(rhs, m) {
+            let y = if rng.gen::<bool>() { y } else { (m - y) % m };
+            return Point::new(x, y);
+        }
+    }
+}
+
+// Samples random points until one generates the whole group of order n.
+// A random point is a generator with probability φ(n)/n, so only a handful
+// of attempts are needed in practice.
+fn random_generator<R: Rng>(
+    a: i64,
+    b: i64,
+    m: i64,
+    n: i64,
+    max_attempts: usize,
+    rng: &mut R,
+) -> Option<Point> {
+    let factors = prime_factors(n);
+    (0..max_attempts)
+        .map(|_| random_point(a, b, m, rng))
+        .find(|point| is_generator(point, n, &factors, a, m))
+}
+
 fn main() {
     let a = 4;
     let b = 4;
     let m = 7;
-    
+
     println!("Finding points on curve y² = x³ + {}x + {} mod {}", a, b, m);
-    
+
     let points = find_all_points(a, b, m);
     println!("\nFound {} points on the curve:", points.len());
     for point in &points {
@@ -144,11 +295,209 @@ fn main() {
             println!("Point at infinity");
         }
     }
-    
+
     println!("\nFinding generators...");
     let generators = find_generators(&points, a, m);
     println!("\nFound {} generators:", generators.len());
     for generator in &generators {
         println!("({}, {})", generator.x, generator.y);
     }
-}
\ No newline at end of file
+    if generators.is_empty() {
+        println!(
+            "No generators, so the group is not cyclic (a cyclic group of order {} would have {})",
+            points.len(),
+            count_generators(points.len() as i64)
+        );
+    } else {
+        println!(
+            "φ({}) = {} generators, as expected for a cyclic group",
+            points.len(),
+            count_generators(points.len() as i64)
+        );
+    }
+
+    // A curve far too large to enumerate point by point
+    let a = 2;
+    let b = 5;
+    let m = 1_000_003;
+    let n = count_points(a, b, m);
+    println!(
+        "\nCurve y² = x³ + {}x + {} mod {} has {} points, factors {:?}",
+        a,
+        b,
+        m,
+        n,
+        prime_factors(n)
+    );
+    println!("Generators if cyclic: {}", count_generators(n));
+
+    let mut rng = rand::thread_rng();
+    match random_generator(a, b, m, n, 100, &mut rng) {
+        Some(g) => println!("Random generator: ({}, {})", g.x, g.y),
+        None => println!("No generator found, the group may not be cyclic"),
+    }
+
+    // Pollard rho on prime-order subgroups of growing size: the steps
+    // should grow like sqrt(r), a factor of about 3.2 per extra digit
+    println!("\nPollard rho, average steps over 5 instances:");
+    for m in [10_007, 100_003, 1_000_003] {
+        // The first b for which #E has a prime factor of at least #E / 8
+        let (b, n, r) = (1..)
+            .map(|b| {
+                let n = count_points(a, b, m);
+                (b, n, *prime_factors(n).last().unwrap())
+            })
+            .find(|&(_, n, r)| r >= n / 8)
+            .unwrap();
+        let g = loop {
+            let g = scalar_mult(n / r, &random_point(a, b, m, &mut rng), a, m);
+            if !g.is_at_infinity() {
+                break g;
+            }
+        };
+
+        let trials = 5;
+        let mut totals = [0u64; 3];
+        for trial in 0..trials {
+            let k = rng.gen_range(1..r);
+            let q = scalar_mult(k, &g, a, m);
+            let results = [
+                pollard_rho(&g, &q, r, a, m, CycleDetection::Floyd, &mut rng),
+                pollard_rho(&g, &q, r, a, m, CycleDetection::Brent, &mut rng),
+                pollard_rho_parallel(&g, &q, r, a, m, 4, trial),
+            ];
+            for (total, result) in totals.iter_mut().zip(results) {
+                match result {
+                    Ok(solution) if solution.k == k => *total += solution.steps,
+                    Ok(solution) => println!("wrong logarithm {} instead of {}", solution.k, k),
+                    Err(e) => println!("Error: {}", e),
+                }
+            }
+        }
+        let root = (r as f64).sqrt();
+        println!(
+            "y² = x³ + {}x + {} mod {}: r = {}, √r = {:.0}",
+            a, b, m, r, root
+        );
+        for (name, total) in ["Floyd", "Brent", "parallel, 4 threads"].iter().zip(totals) {
+            let average = total as f64 / trials as f64;
+            println!("  {:20} {:9.0} steps = {:.2} √r", name, average, average / root);
+        }
+    }
+
+    // Kangaroos only need k to lie in a known interval, so they cost about
+    // 2√w in the width w of the interval, however large the group is
+    let m = 1_000_003;
+    let (b, n, r) = (1..)
+        .map(|b| {
+            let n = count_points(a, b, m);
+            (b, n, *prime_factors(n).last().unwrap())
+        })
+        .find(|&(_, n, r)| r == n)
+        .unwrap();
+    let g = random_point(a, b, m, &mut rng);
+    println!(
+        "\nKangaroo on y² = x³ + {}x + {} mod {} of prime order {}, average over 5 instances:",
+        a, b, m, n
+    );
+    for width in [1_000, 10_000, 100_000] {
+        let lower = rng.gen_range(0..r - width);
+        let trials = 5;
+        let mut totals = [0u64; 2];
+        for _ in 0..trials {
+            let k = rng.gen_range(lower..=lower + width);
+            let q = scalar_mult(k, &g, a, m);
+            let results = [
+                kangaroo(&g, &q, lower..=lower + width, a, m, 1_000_000),
+                kangaroo_parallel(&g, &q, lower..=lower + width, a, m, 4, 1_000_000),
+            ];
+            for (total, result) in totals.iter_mut().zip(results) {
+                match result {
+                    Ok(solution) if solution.k == k => *total += solution.steps,
+                    Ok(solution) => println!("wrong logarithm {} instead of {}", solution.k, k),
+                    Err(e) => println!("Error: {}", e),
+                }
+            }
+        }
+        let root = (width as f64).sqrt();
+        println!("k in [{}, {}], √w = {:.0}", lower, lower + width, root);
+        for (name, total) in ["serial", "parallel, 4 threads"].iter().zip(totals) {
+            let average = total as f64 / trials as f64;
+            println!("  {:20} {:9.0} steps = {:.2} √w", name, average, average / root);
+        }
+    }
+
+    let q = scalar_mult(123_456, &g, a, m);
+    match kangaroo(&g, &q, 0..=r - 1, a, m, 1_000) {
+        Ok(solution) => println!("k = {} in {} steps", solution.k, solution.steps),
+        Err(e) => println!("Whole group with a bound of 1000 steps: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn cyclic_curve_has_phi_n_generators() {
+        let (a, b, m) = (4, 4, 7);
+        let points = find_all_points(a, b, m);
+        let n = points.len() as i64;
+        let generators = find_generators(&points, a, m);
+        assert_eq!(n, 10);
+        assert_eq!(generators.len() as i64, count_generators(n));
+
+        let factors = prime_factors(n);
+        for point in &points {
+            assert_eq!(
+                is_generator(point, n, &factors, a, m),
+                generators.contains(point)
+            );
+        }
+        assert!(!is_generator(&Point::at_infinity(), n, &factors, a, m));
+    }
+
+    #[test]
+    fn non_cyclic_curve_has_no_generator() {
+        // y² = x³ - x has all of E[2] rational, so E(F_7) ≅ Z/2 × Z/4
+        let (a, b, m) = (-1, 0, 7);
+        let points = find_all_points(a, b, m);
+        assert_eq!(points.len(), 8);
+        assert!(find_generators(&points, a, m).is_empty());
+
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(random_generator(a, b, m, 8, 50, &mut rng), None);
+    }
+
+    #[test]
+    fn random_generator_generates_the_group() {
+        let (a, b, m) = (2, 5, 1_000_003);
+        let n = count_points(a, b, m);
+        let mut rng = StdRng::seed_from_u64(7);
+        let g = random_generator(a, b, m, n, 100, &mut rng).unwrap();
+        assert!(is_point_on_curve(g.x, g.y, a, b, m));
+        assert!(is_generator(&g, n, &prime_factors(n), a, m));
+    }
+
+    #[test]
+    fn doubling_does_not_overflow_below_two_to_the_62() {
+        // 2^62 - 57 is the largest prime below 2^62 and is 3 mod 4. With
+        // x² mod m above 3m/4, 3x² no longer fits in an i64.
+        let (a, b, m) = (-3, 7, (1 << 62) - 57);
+        let mut rng = StdRng::seed_from_u64(3);
+        let p = loop {
+            let p = random_point(a, b, m, &mut rng);
+            if mul_mod(p.x, p.x, m) > m / 4 * 3 {
+                break p;
+            }
+        };
+
+        let double = elliptic_add(&p, &p, a, m);
+        assert!(is_point_on_curve(double.x, double.y, a, b, m));
+        let triple = elliptic_add(&double, &p, a, m);
+        assert_eq!(triple, scalar_mult(3, &p, a, m));
+        assert!(is_point_on_curve(triple.x, triple.y, a, b, m));
+    }
+}
@@ -0,0 +1,260 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+use crate::{elliptic_add, mod_inv, mul_mod, prime_factors, scalar_mult, Point};
+
+// Number of precomputed steps in the r-adding walk; Teske found r = 20
+// already behaves like a random mapping
+const WALK_PARTITIONS: usize = 32;
+
+// Random walks tried before giving up on an unlucky instance
+const MAX_RESTARTS: usize = 16;
+
+// Which cycle-finding method drives a single rho walk
+#[derive(Clone, Copy, Debug)]
+pub enum CycleDetection {
+    Floyd,
+    Brent,
+}
+
+// The discrete logarithm together with the number of group additions spent,
+// which is what grows as sqrt(n)
+#[derive(Debug)]
+pub struct RhoSolution {
+    pub k: i64,
+    pub steps: u64,
+}
+
+// A point of the walk with its representation X = [c]G + [d]Q
+#[derive(Clone)]
+struct State {
+    point: Point,
+    c: i64,
+    d: i64,
+}
+
+// Teske's r-adding walk: X -> X + R_j where j is a hash of X and each
+// R_j = [a_j]G + [b_j]Q is fixed in advance
+struct AddingWalk<'a> {
+    g: &'a Point,
+    q: &'a Point,
+    n: i64,
+    a: i64,
+    m: i64,
+    steps: Vec<State>,
+}
+
+impl<'a> AddingWalk<'a> {
+    fn new<R: Rng>(g: &'a Point, q: &'a Point, n: i64, a: i64, m: i64, rng: &mut R) -> Self {
+        let mut walk = AddingWalk {
+            g,
+            q,
+            n,
+            a,
+            m,
+            steps: Vec::with_capacity(WALK_PARTITIONS),
+        };
+        for _ in 0..WALK_PARTITIONS {
+            let state = walk.random_state(rng);
+            walk.steps.push(state);
+        }
+        walk
+    }
+
+    fn state(&self, c: i64, d: i64) -> State {
+        let point = elliptic_add(
+            &scalar_mult(c, self.g, self.a, self.m),
+            &scalar_mult(d, self.q, self.a, self.m),
+            self.a,
+            self.m,
+        );
+        State { point, c, d }
+    }
+
+    fn random_state<R: Rng>(&self, rng: &mut R) -> State {
+        self.state(rng.gen_range(0..self.n), rng.gen_range(0..self.n))
+    }
+
+    fn partition(point: &Point) -> usize {
+        if point.is_at_infinity() {
+            return 0;
+        }
+        let hash =
+            (point.x as u64 ^ (point.y as u64).rotate_left(32)).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+        (hash >> 32) as usize % WALK_PARTITIONS
+    }
+
+    fn step(&self, state: &State) -> State {
+        let add = &self.steps[Self::partition(&state.point)];
+        State {
+            point: elliptic_add(&state.point, &add.point, self.a, self.m),
+            c: (state.c + add.c) % self.n,
+            d: (state.d + add.d) % self.n,
+        }
+    }
+}
+
+// From [c1]G + [d1]Q = [c2]G + [d2]Q: k = (c1 - c2) / (d2 - d1) mod n,
+// unless the two representations agree in d
+fn solve_collision(first: &State, second: &State, n: i64) -> Option<i64> {
+    let denominator = (second.d - first.d).rem_euclid(n);
+    if denominator == 0 {
+        return None;
+    }
+    let numerator = (first.c - second.c).rem_euclid(n);
+    Some(mul_mod(numerator, mod_inv(denominator, n), n))
+}
+
+fn check_instance(g: &Point, n: i64, a: i64, m: i64) -> Result<(), &'static str> {
+    if g.is_at_infinity() {
+        return Err("Base point must not be the point at infinity");
+    }
+    if n < 2 || prime_factors(n) != [n] {
+        return Err("Order of the base point must be prime");
+    }
+    if !scalar_mult(n, g, a, m).is_at_infinity() {
+        return Err("Base point does not have order n");
+    }
+    Ok(())
+}
+
+// Finds a collision on one walk, returning both states and the steps taken
+fn find_cycle(walk: &AddingWalk, start: State, detection: CycleDetection) -> (State, State, u64) {
+    match detection {
+        // Tortoise moves one step, hare two, until they meet
+        CycleDetection::Floyd => {
+            let mut tortoise = walk.step(&start);
+            let mut hare = walk.step(&tortoise);
+            let mut steps = 3;
+            while tortoise.point != hare.point {
+                tortoise = walk.step(&tortoise);
+                hare = walk.step(&walk.step(&hare));
+                steps += 3;
+            }
+            (tortoise, hare, steps)
+        }
+        // The saved point jumps to the hare at every power of two, so each
+        // step costs a single group operation
+        CycleDetection::Brent => {
+            let mut saved = start.clone();
+            let mut hare = walk.step(&start);
+            let (mut power, mut length, mut steps) = (1u64, 1u64, 1u64);
+            while saved.point != hare.point {
+                if power == length {
+                    saved = hare.clone();
+                    power *= 2;
+                    length = 0;
+                }
+                hare = walk.step(&hare);
+                length += 1;
+                steps += 1;
+            }
+            (saved, hare, steps)
+        }
+    }
+}
+
+// Pollard's rho for Q = [k]G with G of prime order n on y² = x³ + ax + b
+// mod m, using a single r-adding walk and Floyd or Brent cycle detection.
+// Expected cost is about sqrt(πn/2) steps.
+pub fn pollard_rho<R: Rng>(
+    g: &Point,
+    q: &Point,
+    n: i64,
+    a: i64,
+    m: i64,
+    detection: CycleDetection,
+    rng: &mut R,
+) -> Result<RhoSolution, &'static str> {
+    check_instance(g, n, a, m)?;
+    let mut total = 0;
+    for _ in 0..MAX_RESTARTS {
+        let walk = AddingWalk::new(g, q, n, a, m, rng);
+        let start = walk.random_state(rng);
+        let (first, second, steps) = find_cycle(&walk, start, detection);
+        total += steps;
+        if let Some(k) = solve_collision(&first, &second, n) {
+            return Ok(RhoSolution { k, steps: total });
+        }
+    }
+    Err("Every walk ended in a useless collision")
+}
+
+// The parallel variant of van Oorschot and Wiener: each thread starts
+// fresh walks from random points and reports only distinguished points,
+// those whose x has its low bits zero. Two walks that ever meet continue
+// together to the same distinguished point, so a central table of them
+// detects collisions between threads.
+pub fn pollard_rho_parallel(
+    g: &Point,
+    q: &Point,
+    n: i64,
+    a: i64,
+    m: i64,
+    threads: usize,
+    seed: u64,
+) -> Result<RhoSolution, &'static str> {
+    check_instance(g, n, a, m)?;
+    // About one point in 2^w is distinguished, with w a quarter of the bits
+    // of n so that the table stays around n^(1/4) entries
+    let bits = 64 - n.leading_zeros() as i64;
+    let mask = (1i64 << (bits / 4)) - 1;
+    let max_length = 20 * (mask + 1) as u64;
+
+    let walk = AddingWalk::new(g, q, n, a, m, &mut StdRng::seed_from_u64(seed));
+    let done = AtomicBool::new(false);
+    let steps = AtomicU64::new(0);
+    let table: Mutex<HashMap<Point, State>> = Mutex::new(HashMap::new());
+    let result = Mutex::new(None);
+
+    thread::scope(|scope| {
+        for id in 0..threads.max(1) {
+            let (walk, done, steps, table, result) = (&walk, &done, &steps, &table, &result);
+            scope.spawn(move || {
+                let mut rng = StdRng::seed_from_u64(seed.wrapping_add(id as u64 + 1));
+                while !done.load(Ordering::Relaxed) {
+                    let mut state = walk.random_state(&mut rng);
+                    let mut length = 0;
+                    // Walks that cycle without meeting a distinguished
+                    // point are abandoned
+                    while length < max_length
+                        && (state.point.is_at_infinity() || state.point.x & mask != 0)
+                    {
+                        state = walk.step(&state);
+                        length += 1;
+                    }
+                    steps.fetch_add(length, Ordering::Relaxed);
+                    if length == max_length {
+                        continue;
+                    }
+
+                    let mut table = table.lock().unwrap();
+                    let solved = match table.get(&state.point) {
+                        Some(previous) => solve_collision(previous, &state, n),
+                        None => {
+                            table.insert(state.point.clone(), state);
+                            None
+                        }
+                    };
+                    if let Some(k) = solved {
+                        *result.lock().unwrap() = Some(k);
+                        done.store(true, Ordering::Relaxed);
+                    }
+                }
+            });
+        }
+    });
+
+    let k = result
+        .into_inner()
+        .unwrap()
+        .ok_or("All walkers stopped without a collision")?;
+    Ok(RhoSolution {
+        k,
+        steps: steps.load(Ordering::Relaxed),
+    })
+}
@@ -0,0 +1,274 @@
+//! Integer factorization and primality testing over `BigUint`, used to factor
+//! group orders, cofactors and p - 1 for the curve examples.
+
+mod pollard;
+mod primality;
+mod trial;
+
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+use std::fmt;
+
+pub use pollard::{pollard_brent, pollard_p_minus_1};
+pub use primality::{
+    is_prime_miller_rabin, is_prime_u64, is_probable_prime, jacobi, miller_rabin, strong_lucas,
+};
+pub use trial::{primes_between, primes_up_to, trial_division};
+
+/// Primes up to this bound are removed by trial division before anything else.
+const TRIAL_DIVISION_BOUND: u64 = 10_000;
+/// Stage 1 bound for Pollard's p - 1.
+const P_MINUS_1_BOUND: u64 = 100_000;
+/// Iterations of a single rho walk before switching to a new constant.
+const RHO_ITERATIONS: u64 = 1 << 24;
+
+/// The prime factorization of a positive integer, kept sorted by prime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Factorization {
+    factors: Vec<(BigUint, u32)>,
+}
+
+impl Factorization {
+    /// Fully factors `n` using trial division, Pollard p - 1 and Brent's rho,
+    /// with BPSW deciding when a cofactor is prime.
+    pub fn of(n: &BigUint) -> Result<Self, &'static str> {
+        if n.is_zero() {
+            return Err("zero has no prime factorization");
+        }
+
+        let (found, cofactor) = trial_division(n, TRIAL_DIVISION_BOUND);
+        let mut factorization = Factorization {
+            factors: Vec::new(),
+        };
+        for (p, e) in found {
+            factorization.insert(p, e);
+        }
+
+        let mut pending = vec![cofactor];
+        while let Some(m) = pending.pop() {
+            if m.is_one() {
+                continue;
+            }
+            if is_probable_prime(&m) {
+                factorization.insert(m, 1);
+                continue;
+            }
+            let d = split(&m);
+            pending.push(&m / &d);
+            pending.push(d);
+        }
+        Ok(factorization)
+    }
+
+    pub fn of_u64(n: u64) -> Result<Self, &'static str> {
+        Self::of(&BigUint::from(n))
+    }
+
+    /// Builds a factorization from known prime powers, checking each base is prime.
+    pub fn from_prime_powers(factors: Vec<(BigUint, u32)>) -> Result<Self, &'static str> {
+        let mut factorization = Factorization {
+            factors: Vec::new(),
+        };
+        for (p, e) in factors {
+            if !is_probable_prime(&p) {
+                return Err("factor is not prime");
+            }
+            factorization.insert(p, e);
+        }
+        Ok(factorization)
+    }
+
+    fn insert(&mut self, p: BigUint, e: u32) {
+        if e == 0 {
+            return;
+        }
+        match self.factors.binary_search_by(|(q, _)| q.cmp(&p)) {
+            Ok(i) => self.factors[i].1 += e,
+            Err(i) => self.factors.insert(i, (p, e)),
+        }
+    }
+
+    /// The prime powers, smallest prime first.
+    pub fn factors(&self) -> &[(BigUint, u32)] {
+        &self.factors
+    }
+
+    pub fn primes(&self) -> impl Iterator<Item = &BigUint> {
+        self.factors.iter().map(|(p, _)| p)
+    }
+
+    /// The integer this is a factorization of.
+    pub fn value(&self) -> BigUint {
+        self.factors
+            .iter()
+            .fold(BigUint::one(), |acc, (p, e)| acc * p.pow(*e))
+    }
+
+    pub fn is_prime(&self) -> bool {
+        self.factors.len() == 1 && self.factors[0].1 == 1
+    }
+
+    pub fn largest_prime(&self) -> Option<&BigUint> {
+        self.factors.last().map(|(p, _)| p)
+    }
+
+    /// Splits the value as h * r with r the largest prime factor, the usual
+    /// cofactor/subgroup-order decomposition of a group order.
+    pub fn cofactor_split(&self) -> Option<(BigUint, BigUint)> {
+        let r = self.largest_prime()?.clone();
+        Some((self.value() / &r, r))
+    }
+
+    /// Euler's totient of the value.
+    pub fn euler_phi(&self) -> BigUint {
+        self.factors.iter().fold(BigUint::one(), |acc, (p, e)| {
+            acc * p.pow(e - 1) * (p - BigUint::one())
+        })
+    }
+
+    /// Every positive divisor of the value, in increasing order.
+    pub fn divisors(&self) -> Vec<BigUint> {
+        let mut divisors = vec![BigUint::one()];
+        for (p, e) in &self.factors {
+            let mut next = Vec::with_capacity(divisors.len() * (*e as usize + 1));
+            for d in &divisors {
+                let mut power = d.clone();
+                next.push(power.clone());
+                for _ in 0..*e {
+                    power *= p;
+                    next.push(power.clone());
+                }
+            }
+            divisors = next;
+        }
+        divisors.sort();
+        divisors
+    }
+
+    /// True when every prime factor is at most `bound`.
+    pub fn is_smooth(&self, bound: &BigUint) -> bool {
+        self.largest_prime().is_none_or(|p| p <= bound)
+    }
+}
+
+impl fmt::Display for Factorization {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.factors.is_empty() {
+            return write!(f, "1");
+        }
+        let terms: Vec<String> = self
+            .factors
+            .iter()
+            .map(|(p, e)| {
+                if *e == 1 {
+                    p.to_string()
+                } else {
+                    format!("{}^{}", p, e)
+                }
+            })
+            .collect();
+        write!(f, "{}", terms.join(" * "))
+    }
+}
+
+/// Finds a non-trivial divisor of the composite `n`.
+fn split(n: &BigUint) -> BigUint {
+    if let Some(d) = pollard_p_minus_1(n, P_MINUS_1_BOUND) {
+        return d;
+    }
+    let mut c = BigUint::one();
+    loop {
+        if let Some(d) = pollard_brent(n, &c, RHO_ITERATIONS) {
+            return d;
+        }
+        c += 1u32;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(n: u64) -> BigUint {
+        BigUint::from(n)
+    }
+
+    fn factors_of(n: &BigUint) -> Vec<(BigUint, u32)> {
+        Factorization::of(n).unwrap().factors().to_vec()
+    }
+
+    #[test]
+    fn zero_and_one() {
+        assert_eq!(
+            Factorization::of(&BigUint::zero()),
+            Err("zero has no prime factorization")
+        );
+        let one = Factorization::of_u64(1).unwrap();
+        assert!(one.factors().is_empty());
+        assert_eq!(one.value(), BigUint::one());
+        assert!(!one.is_prime());
+        assert_eq!(one.to_string(), "1");
+        assert_eq!(one.divisors(), vec![BigUint::one()]);
+    }
+
+    #[test]
+    fn semiprimes() {
+        // Two primes past the trial division bound, split by rho
+        let n = big(1_000_000_007) * big(1_000_000_009);
+        assert_eq!(
+            factors_of(&n),
+            vec![(big(1_000_000_007), 1), (big(1_000_000_009), 1)]
+        );
+        // 31427115721 - 1 is smooth, so p - 1 splits this one
+        let n = big(31_427_115_721) * big(1_000_000_000_547);
+        assert_eq!(
+            factors_of(&n),
+            vec![(big(31_427_115_721), 1), (big(1_000_000_000_547), 1)]
+        );
+    }
+
+    #[test]
+    fn prime_powers() {
+        assert_eq!(factors_of(&(BigUint::one() << 64u32)), vec![(big(2), 64)]);
+        assert_eq!(factors_of(&big(10_007).pow(3)), vec![(big(10_007), 3)]);
+        assert_eq!(
+            factors_of(&big(1_000_000_007).pow(2)),
+            vec![(big(1_000_000_007), 2)]
+        );
+        let n = big(2).pow(5) * big(3).pow(4) * big(1_000_003).pow(2);
+        assert_eq!(
+            factors_of(&n),
+            vec![(big(2), 5), (big(3), 4), (big(1_000_003), 2)]
+        );
+    }
+
+    #[test]
+    fn primes_factor_as_themselves() {
+        let f = Factorization::of_u64(1_000_003).unwrap();
+        assert!(f.is_prime());
+        assert_eq!(f.cofactor_split(), Some((BigUint::one(), big(1_000_003))));
+    }
+
+    #[test]
+    fn derived_quantities() {
+        let f = Factorization::of_u64(360).unwrap();
+        assert_eq!(f.to_string(), "2^3 * 3^2 * 5");
+        assert_eq!(f.value(), big(360));
+        assert_eq!(f.euler_phi(), big(96));
+        assert_eq!(f.divisors().len(), 24);
+        assert!(f.divisors().iter().all(|d| (big(360) % d).is_zero()));
+        assert_eq!(f.cofactor_split(), Some((big(72), big(5))));
+        assert!(f.is_smooth(&big(5)));
+        assert!(!f.is_smooth(&big(3)));
+    }
+
+    #[test]
+    fn from_prime_powers_checks_primality() {
+        let f = Factorization::from_prime_powers(vec![(big(3), 2), (big(2), 1), (big(3), 1)]);
+        assert_eq!(f.unwrap().factors(), &[(big(2), 1), (big(3), 3)]);
+        assert_eq!(
+            Factorization::from_prime_powers(vec![(big(2_047), 1)]),
+            Err("factor is not prime")
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use integer_factorization::{is_prime_miller_rabin, is_probable_prime, Factorization};
+use num_bigint::BigUint;
+use std::time::Instant;
+
+fn main() {
+    // Group order of y² = x³ + 2x + 5 over F_1000003 from generators_on_curve
+    let order = Factorization::of_u64(1_000_750).unwrap();
+    println!("#E = {} = {}", order.value(), order);
+    let (h, r) = order.cofactor_split().unwrap();
+    println!("cofactor h = {}, subgroup order r = {}", h, r);
+    println!(
+        "generators of a cyclic group of that order: {}",
+        order.euler_phi()
+    );
+
+    // p - 1 for a field prime, as needed when computing multiplicative orders
+    let p = BigUint::from(1_000_003u32);
+    let p_minus_1 = Factorization::of(&(&p - 1u32)).unwrap();
+    println!("\np - 1 = {} = {}", &p - 1u32, p_minus_1);
+    println!(
+        "divisors: {:?}",
+        p_minus_1
+            .divisors()
+            .iter()
+            .map(|d| d.to_string())
+            .collect::<Vec<_>>()
+    );
+
+    // A product of two 11-digit primes needs Pollard rho
+    let n: BigUint = "1000000016000000063".parse().unwrap();
+    let start = Instant::now();
+    let factorization = Factorization::of(&n).unwrap();
+    println!("\n{} = {} ({:?})", n, factorization, start.elapsed());
+
+    // The secp256k1 field prime, checked by both primality tests
+    let secp256k1_p = BigUint::parse_bytes(
+        b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+        16,
+    )
+    .unwrap();
+    println!(
+        "\nsecp256k1 p is prime: BPSW {}, Miller-Rabin {}",
+        is_probable_prime(&secp256k1_p),
+        is_prime_miller_rabin(&secp256k1_p)
+    );
+
+    // A strong pseudoprime to several bases that BPSW rejects
+    let pseudoprime = BigUint::from(3_215_031_751u64);
+    println!(
+        "{} is prime: BPSW {}",
+        pseudoprime,
+        is_probable_prime(&pseudoprime)
+    );
+}
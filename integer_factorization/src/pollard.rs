@@ -0,0 +1,138 @@
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+use crate::trial::primes_up_to;
+
+/// Number of steps whose differences are multiplied together before taking a gcd.
+const BRENT_BATCH: u64 = 128;
+
+/// Pollard's rho with Brent's cycle detection on x -> x^2 + c mod n.
+///
+/// Returns a non-trivial factor of the composite `n`, or `None` if the walk
+/// collapsed to `n` itself and a different `c` should be tried.
+pub fn pollard_brent(n: &BigUint, c: &BigUint, max_iterations: u64) -> Option<BigUint> {
+    let one = BigUint::one();
+    if n.is_even() {
+        return Some(BigUint::from(2u32));
+    }
+    let f = |x: &BigUint| (x * x + c) % n;
+
+    let mut y = BigUint::from(2u32);
+    let mut x = y.clone();
+    let mut ys = y.clone();
+    let mut q = one.clone();
+    let mut g = one.clone();
+    let mut r = 1u64;
+    let mut iterations = 0u64;
+
+    while g.is_one() {
+        x = y.clone();
+        for _ in 0..r {
+            y = f(&y);
+        }
+        let mut k = 0;
+        while k < r && g.is_one() {
+            ys = y.clone();
+            for _ in 0..BRENT_BATCH.min(r - k) {
+                y = f(&y);
+                let diff = if x > y { &x - &y } else { &y - &x };
+                q = q * diff % n;
+            }
+            g = q.gcd(n);
+            k += BRENT_BATCH;
+        }
+        r *= 2;
+        iterations += r;
+        if iterations > max_iterations {
+            return None;
+        }
+    }
+
+    if g == *n {
+        // The batch overshot; redo it one step at a time
+        loop {
+            ys = f(&ys);
+            let diff = if x > ys { &x - &ys } else { &ys - &x };
+            g = diff.gcd(n);
+            if !g.is_one() {
+                break;
+            }
+        }
+    }
+
+    if g == *n {
+        None
+    } else {
+        Some(g)
+    }
+}
+
+/// Pollard's p - 1 method, stage 1 only.
+///
+/// Finds a prime factor q of `n` whenever q - 1 is `bound`-powersmooth.
+pub fn pollard_p_minus_1(n: &BigUint, bound: u64) -> Option<BigUint> {
+    let one = BigUint::one();
+    let mut a = BigUint::from(2u32);
+
+    for p in primes_up_to(bound) {
+        let mut power = p;
+        while power * p <= bound {
+            power *= p;
+        }
+        a = a.modpow(&BigUint::from(power), n);
+    }
+
+    if a.is_zero() {
+        return None;
+    }
+    let g = (&a + n - &one).gcd(n);
+    if g.is_one() || g == *n {
+        None
+    } else {
+        Some(g)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn big(n: u64) -> BigUint {
+        BigUint::from(n)
+    }
+
+    #[test]
+    fn brent_splits_a_semiprime() {
+        let n = big(1_000_000_007) * big(1_000_000_009);
+        let d = pollard_brent(&n, &BigUint::one(), 1 << 24).unwrap();
+        assert!(d == big(1_000_000_007) || d == big(1_000_000_009));
+        assert_eq!(
+            pollard_brent(&big(2 * 1_000_003), &BigUint::one(), 10),
+            Some(big(2))
+        );
+    }
+
+    #[test]
+    fn brent_splits_a_prime_square() {
+        let n = big(1_000_003) * big(1_000_003);
+        let d = pollard_brent(&n, &BigUint::one(), 1 << 24).unwrap();
+        assert_eq!(d, big(1_000_003));
+    }
+
+    #[test]
+    fn p_minus_1_finds_the_smooth_factor() {
+        // 1000000009 - 1 = 2^3 3^2 7 109^2 167 while 1000000000547 - 1 is
+        // twice a prime
+        let n = big(1_000_000_009) * big(1_000_000_000_547);
+        assert_eq!(pollard_p_minus_1(&n, 100_000), Some(big(1_000_000_009)));
+        assert_eq!(pollard_p_minus_1(&n, 100), None);
+    }
+
+    #[test]
+    fn p_minus_1_gives_up_without_a_smooth_factor() {
+        // Both p - 1 are twice a prime
+        let n = big(1_000_000_007) * big(1_000_000_000_547);
+        assert_eq!(pollard_p_minus_1(&n, 100_000), None);
+    }
+}
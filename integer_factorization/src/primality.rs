@@ -0,0 +1,264 @@
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::trial::SMALL_PRIMES;
+
+/// Bases that make Miller–Rabin deterministic for every n < 3.3 * 10^24.
+const DETERMINISTIC_BASES: [u32; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Strong probable-prime test of odd `n > 3` to base `a`.
+pub fn miller_rabin(n: &BigUint, a: &BigUint) -> bool {
+    let one = BigUint::one();
+    let n_minus_one = n - &one;
+    let s = n_minus_one.trailing_zeros().unwrap_or(0);
+    let d = &n_minus_one >> s;
+
+    let mut x = a.modpow(&d, n);
+    if x == one || x == n_minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = x.modpow(&BigUint::from(2u32), n);
+        if x == n_minus_one {
+            return true;
+        }
+        if x == one {
+            return false;
+        }
+    }
+    false
+}
+
+/// Jacobi symbol (a / n) for odd positive `n`.
+pub fn jacobi(a: &BigInt, n: &BigUint) -> i32 {
+    let mut n = BigInt::from(n.clone());
+    let mut a = a.mod_floor(&n);
+    let mut result = 1;
+
+    while !a.is_zero() {
+        while a.is_even() {
+            a >>= 1;
+            let r = (&n % 8u32).to_u32().unwrap();
+            if r == 3 || r == 5 {
+                result = -result;
+            }
+        }
+        std::mem::swap(&mut a, &mut n);
+        if (&a % 4u32).to_u32() == Some(3) && (&n % 4u32).to_u32() == Some(3) {
+            result = -result;
+        }
+        a = a.mod_floor(&n);
+    }
+    if n.is_one() {
+        result
+    } else {
+        0
+    }
+}
+
+/// Halves `x` modulo odd `n`.
+fn half_mod(x: BigInt, n: &BigInt) -> BigInt {
+    if x.is_odd() {
+        ((x + n) >> 1u32).mod_floor(n)
+    } else {
+        (x >> 1u32).mod_floor(n)
+    }
+}
+
+/// Strong Lucas probable-prime test with Selfridge's parameters: D is the
+/// first of 5, -7, 9, -11, ... with (D / n) = -1, P = 1 and Q = (1 - D) / 4.
+pub fn strong_lucas(n: &BigUint) -> bool {
+    let root = n.sqrt();
+    if &root * &root == *n {
+        return false;
+    }
+
+    let mut d = BigInt::from(5);
+    loop {
+        match jacobi(&d, n) {
+            -1 => break,
+            0 if d.abs() != BigInt::from(n.clone()) => return false,
+            _ => {}
+        }
+        d = if d.is_positive() {
+            -(d + 2u32)
+        } else {
+            -(d - 2u32)
+        };
+    }
+    let q = (BigInt::one() - &d) / 4u32;
+
+    let modulus = BigInt::from(n.clone());
+    let n_plus_one = n + 1u32;
+    let s = n_plus_one.trailing_zeros().unwrap_or(0);
+    let k = &n_plus_one >> s;
+
+    // Left-to-right evaluation of U_k, V_k and Q^k with P = 1
+    let mut u = BigInt::one();
+    let mut v = BigInt::one();
+    let mut q_k = q.mod_floor(&modulus);
+    let d = d.mod_floor(&modulus);
+    for i in (0..k.bits() - 1).rev() {
+        u = (&u * &v).mod_floor(&modulus);
+        v = (&v * &v - &q_k * 2u32).mod_floor(&modulus);
+        q_k = (&q_k * &q_k).mod_floor(&modulus);
+        if k.bit(i) {
+            let next_u = half_mod(&u + &v, &modulus);
+            v = half_mod(&d * &u + &v, &modulus);
+            u = next_u;
+            q_k = (&q_k * &q).mod_floor(&modulus);
+        }
+    }
+
+    if u.is_zero() || v.is_zero() {
+        return true;
+    }
+    for _ in 1..s {
+        v = (&v * &v - &q_k * 2u32).mod_floor(&modulus);
+        q_k = (&q_k * &q_k).mod_floor(&modulus);
+        if v.is_zero() {
+            return true;
+        }
+    }
+    false
+}
+
+/// Baillie–PSW: trial division by small primes, a base-2 strong probable-prime
+/// test and a strong Lucas test. No composite passing it is known.
+pub fn is_probable_prime(n: &BigUint) -> bool {
+    if n < &BigUint::from(2u32) {
+        return false;
+    }
+    for &p in SMALL_PRIMES.iter() {
+        let p = BigUint::from(p);
+        if *n == p {
+            return true;
+        }
+        if (n % &p).is_zero() {
+            return false;
+        }
+    }
+    miller_rabin(n, &BigUint::from(2u32)) && strong_lucas(n)
+}
+
+/// Miller–Rabin over a fixed set of bases, deterministic below 3.3 * 10^24
+/// and a strong probabilistic test above that.
+pub fn is_prime_miller_rabin(n: &BigUint) -> bool {
+    if n < &BigUint::from(2u32) {
+        return false;
+    }
+    for &p in DETERMINISTIC_BASES.iter() {
+        let p = BigUint::from(p);
+        if *n == p {
+            return true;
+        }
+        if (n % &p).is_zero() {
+            return false;
+        }
+    }
+    DETERMINISTIC_BASES
+        .iter()
+        .all(|&a| miller_rabin(n, &BigUint::from(a)))
+}
+
+/// Convenience for callers working with machine integers.
+pub fn is_prime_u64(n: u64) -> bool {
+    is_probable_prime(&BigUint::from(n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primes_up_to;
+
+    fn big(n: u64) -> BigUint {
+        BigUint::from(n)
+    }
+
+    #[test]
+    fn agrees_with_the_sieve() {
+        let primes = primes_up_to(10_000);
+        for n in 0..10_000u64 {
+            let expected = primes.binary_search(&n).is_ok();
+            assert_eq!(is_probable_prime(&big(n)), expected, "BPSW on {}", n);
+            assert_eq!(is_prime_miller_rabin(&big(n)), expected, "MR on {}", n);
+        }
+    }
+
+    #[test]
+    fn accepts_large_primes() {
+        for n in [1_000_000_007, (1 << 61) - 1, 18_446_744_073_709_551_557] {
+            assert!(is_probable_prime(&big(n)), "{}", n);
+            assert!(is_prime_miller_rabin(&big(n)), "{}", n);
+        }
+        let m127 = (BigUint::one() << 127u32) - 1u32;
+        assert!(is_probable_prime(&m127));
+        assert!(is_prime_miller_rabin(&m127));
+    }
+
+    #[test]
+    fn rejects_strong_pseudoprimes_to_base_2() {
+        // 2047 and 3215031751 are the smallest strong pseudoprimes to the
+        // first one and first four prime bases, 3825123056546413051 to the
+        // first nine; all of them fool a single base-2 round
+        for n in [
+            2_047,
+            3_215_031_751,
+            2_152_302_898_747,
+            3_474_749_660_383,
+            341_550_071_728_321,
+            3_825_123_056_546_413_051,
+        ] {
+            assert!(miller_rabin(&big(n), &big(2)), "{}", n);
+            assert!(!is_probable_prime(&big(n)), "BPSW on {}", n);
+            assert!(!is_prime_miller_rabin(&big(n)), "MR on {}", n);
+        }
+    }
+
+    #[test]
+    fn rejects_carmichael_numbers() {
+        for n in [
+            561,
+            41_041,
+            825_265,
+            321_197_185,
+            5_394_826_801,
+            232_250_619_601,
+            9_746_347_772_161,
+            2_152_302_898_747,
+        ] {
+            assert_eq!(big(2).modpow(&big(n - 1), &big(n)), BigUint::one());
+            assert!(!is_probable_prime(&big(n)), "BPSW on {}", n);
+            assert!(!is_prime_miller_rabin(&big(n)), "MR on {}", n);
+        }
+    }
+
+    #[test]
+    fn base_2_catches_strong_lucas_pseudoprimes() {
+        for n in [5_459, 5_777, 10_877, 16_109, 18_971] {
+            assert!(strong_lucas(&big(n)), "{}", n);
+            assert!(!miller_rabin(&big(n), &big(2)), "{}", n);
+            assert!(!is_probable_prime(&big(n)), "{}", n);
+        }
+    }
+
+    #[test]
+    fn jacobi_matches_euler_criterion() {
+        let p = big(1_009);
+        for a in 0..1_009u64 {
+            let power = big(a).modpow(&big(504), &p);
+            let expected = if a == 0 {
+                0
+            } else if power.is_one() {
+                1
+            } else {
+                -1
+            };
+            assert_eq!(jacobi(&BigInt::from(a), &p), expected, "({} / 1009)", a);
+        }
+        assert_eq!(jacobi(&BigInt::from(-1), &big(15)), -1);
+        assert_eq!(jacobi(&BigInt::from(2), &big(15)), 1);
+        assert_eq!(jacobi(&BigInt::from(5), &big(15)), 0);
+    }
+}
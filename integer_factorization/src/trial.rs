@@ -0,0 +1,88 @@
+use num_bigint::BigUint;
+use num_traits::{One, Zero};
+
+/// Primes below 100, used to screen candidates before the expensive tests.
+pub const SMALL_PRIMES: [u32; 25] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+/// All primes up to and including `limit`, by the sieve of Eratosthenes.
+pub fn primes_up_to(limit: u64) -> Vec<u64> {
+    if limit < 2 {
+        return Vec::new();
+    }
+    let limit = limit as usize;
+    let mut is_composite = vec![false; limit + 1];
+    let mut primes = Vec::new();
+    for i in 2..=limit {
+        if is_composite[i] {
+            continue;
+        }
+        primes.push(i as u64);
+        let mut multiple = i * i;
+        while multiple <= limit {
+            is_composite[multiple] = true;
+            multiple += i;
+        }
+    }
+    primes
+}
+
+/// Width of one block of the segmented sieve.
+const SEGMENT_WIDTH: u64 = 1 << 16;
+
+/// The primes p with `low < p <= high`, sieved one block of `SEGMENT_WIDTH`
+/// at a time with the primes up to √high, so memory stays at about
+/// √high + `SEGMENT_WIDTH` however wide the range is.
+pub fn primes_between(low: u64, high: u64) -> impl Iterator<Item = u64> {
+    let base = primes_up_to(high.isqrt());
+    let start = low.saturating_add(1).max(2);
+    (start..=high)
+        .step_by(SEGMENT_WIDTH as usize)
+        .flat_map(move |segment_start| {
+            let segment_end = segment_start.saturating_add(SEGMENT_WIDTH - 1).min(high);
+            let mut is_composite = vec![false; (segment_end - segment_start + 1) as usize];
+            for &p in base.iter().take_while(|&&p| p * p <= segment_end) {
+                let mut multiple = (p * p).max(segment_start.div_ceil(p) * p);
+                while multiple <= segment_end {
+                    is_composite[(multiple - segment_start) as usize] = true;
+                    multiple += p;
+                }
+            }
+            let primes: Vec<u64> = (segment_start..=segment_end)
+                .filter(|&i| !is_composite[(i - segment_start) as usize])
+                .collect();
+            primes
+        })
+}
+
+/// Strips every prime factor up to `bound` from `n`.
+///
+/// Returns the factors found with their exponents and the remaining cofactor,
+/// which is either 1 or has no prime factor up to `bound`.
+pub fn trial_division(n: &BigUint, bound: u64) -> (Vec<(BigUint, u32)>, BigUint) {
+    let mut factors = Vec::new();
+    let mut n = n.clone();
+
+    for p in primes_up_to(bound) {
+        if n.is_one() {
+            break;
+        }
+        let big_p = BigUint::from(p);
+        if &big_p * &big_p > n {
+            // No factor below sqrt(n) is left, so what remains is prime
+            factors.push((n, 1));
+            n = BigUint::one();
+            break;
+        }
+        let mut exponent = 0;
+        while (&n % &big_p).is_zero() {
+            n /= &big_p;
+            exponent += 1;
+        }
+        if exponent > 0 {
+            factors.push((big_p, exponent));
+        }
+    }
+    (factors, n)
+}
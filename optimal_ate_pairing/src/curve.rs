@@ -0,0 +1,107 @@
+use std::fmt;
+
+use num_bigint::BigUint;
+
+use crate::fields::Field;
+
+/// An affine point on y² = x³ + b over F, or the point at infinity.
+#[derive(Clone, PartialEq)]
+pub enum Point<F: Field> {
+    Infinity,
+    Affine(F, F),
+}
+
+impl<F: Field> Point<F> {
+    pub fn is_infinity(&self) -> bool {
+        matches!(self, Point::Infinity)
+    }
+}
+
+/// y² = x³ + b, the shape of both pairing-friendly curves and their twists.
+#[derive(Clone)]
+pub struct Curve<F: Field> {
+    pub b: F,
+}
+
+impl<F: Field> Curve<F> {
+    pub fn new(b: F) -> Self {
+        Curve { b }
+    }
+
+    pub fn contains(&self, point: &Point<F>) -> bool {
+        match point {
+            Point::Infinity => true,
+            Point::Affine(x, y) => y.square() == x.square().mul(x).add(&self.b),
+        }
+    }
+
+    pub fn neg(&self, point: &Point<F>) -> Point<F> {
+        match point {
+            Point::Infinity => Point::Infinity,
+            Point::Affine(x, y) => Point::Affine(x.clone(), y.neg()),
+        }
+    }
+
+    /// The slope of the chord through P and Q, or of the tangent when they
+    /// coincide; `None` when the line is vertical.
+    pub fn slope(&self, p: &Point<F>, q: &Point<F>) -> Option<F> {
+        let (Point::Affine(x1, y1), Point::Affine(x2, y2)) = (p, q) else {
+            return None;
+        };
+        if x1 != x2 {
+            return Some(y2.sub(y1).mul(&x2.sub(x1).inverse()?));
+        }
+        if y1 != y2 || y1.is_zero() {
+            return None;
+        }
+        let numerator = x1.square();
+        let numerator = numerator.double().add(&numerator);
+        Some(numerator.mul(&y1.double().inverse()?))
+    }
+
+    pub fn add(&self, p: &Point<F>, q: &Point<F>) -> Point<F> {
+        let (Point::Affine(x1, y1), Point::Affine(x2, _)) = (p, q) else {
+            return if p.is_infinity() {
+                q.clone()
+            } else {
+                p.clone()
+            };
+        };
+        let Some(lambda) = self.slope(p, q) else {
+            return Point::Infinity;
+        };
+        let x3 = lambda.square().sub(x1).sub(x2);
+        let y3 = lambda.mul(&x1.sub(&x3)).sub(y1);
+        Point::Affine(x3, y3)
+    }
+
+    pub fn double(&self, p: &Point<F>) -> Point<F> {
+        self.add(p, p)
+    }
+
+    pub fn multiply(&self, k: &BigUint, p: &Point<F>) -> Point<F> {
+        let mut result = Point::Infinity;
+        for i in (0..k.bits()).rev() {
+            result = self.double(&result);
+            if k.bit(i) {
+                result = self.add(&result, p);
+            }
+        }
+        result
+    }
+}
+
+impl<F: Field + fmt::Display> fmt::Display for Point<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Point::Infinity => write!(f, "O"),
+            Point::Affine(x, y) => write!(f, "({}, {})", x, y),
+        }
+    }
+}
+
+impl<F: Field + fmt::Display> fmt::Debug for Point<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
@@ -0,0 +1,174 @@
+use std::rc::Rc;
+
+use num_bigint::{BigInt, BigUint};
+
+use crate::curve::{Curve, Point};
+use crate::fields::{Field, Fp, Fp2, Tower};
+
+/// The two families with embedding degree 12 we support.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    /// Barreto–Naehrig: p = 36u⁴ + 36u³ + 24u² + 6u + 1, r = p + 1 - (6u² + 1)
+    Bn,
+    /// Barreto–Lynn–Scott: p = (x - 1)² (x⁴ - x² + 1) / 3 + x, r = x⁴ - x² + 1
+    Bls12,
+}
+
+/// How the sextic twist E': y² = x³ + b' maps into E(F_p12).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Twist {
+    /// b' = b / ξ, untwisted by (x, y) -> (x w², y w³)
+    D,
+    /// b' = b ξ, untwisted by (x, y) -> (x / w², y / w³)
+    M,
+}
+
+/// A pairing-friendly curve E: y² = x³ + b over F_p with G1 ⊂ E(F_p) and G2
+/// represented on the sextic twist E'(F_p2).
+pub struct PairingCurve {
+    pub name: &'static str,
+    pub family: Family,
+    pub twist: Twist,
+    pub tower: Rc<Tower>,
+    /// The BN seed u or the BLS parameter x
+    pub x: i128,
+    pub r: BigUint,
+    pub g1_curve: Curve<Fp>,
+    pub g2_curve: Curve<Fp2>,
+    pub g1: Point<Fp>,
+    pub g2: Point<Fp2>,
+}
+
+fn parse(digits: &str) -> BigUint {
+    match digits.strip_prefix("0x") {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16),
+        None => BigUint::parse_bytes(digits.as_bytes(), 10),
+    }
+    .expect("malformed curve constant")
+}
+
+impl PairingCurve {
+    /// BN254 (alt_bn128) as used by the Ethereum precompiles of EIP-196 and
+    /// EIP-197, with ξ = 9 + u and a D-type twist.
+    pub fn bn254() -> Self {
+        let tower = Tower::new(
+            parse("21888242871839275222246405745257275088696311157297823662689037894645226208583"),
+            (9, 1),
+        );
+        let b = Fp::from_u64(3, &tower);
+        let b_twist = Fp2::new(b.clone(), b.zero_like()).mul(&tower.xi().inverse().unwrap());
+        let g2 = Point::Affine(
+            Fp2::from_biguints(
+                parse(
+                    "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+                ),
+                parse(
+                    "11559732032986387107991004021392285783925812861821192530917403151452391805634",
+                ),
+                &tower,
+            ),
+            Fp2::from_biguints(
+                parse(
+                    "8495653923123431417604973247489272438418190587263600148770280649306958101930",
+                ),
+                parse(
+                    "4082367875863433681332203403145435568316851327593401208105741076214120093531",
+                ),
+                &tower,
+            ),
+        );
+        PairingCurve {
+            name: "BN254",
+            family: Family::Bn,
+            twist: Twist::D,
+            x: 4965661367192848881,
+            r: parse(
+                "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+            ),
+            g1_curve: Curve::new(b),
+            g2_curve: Curve::new(b_twist),
+            g1: Point::Affine(Fp::from_u64(1, &tower), Fp::from_u64(2, &tower)),
+            g2,
+            tower,
+        }
+    }
+
+    /// BLS12-381 with the generators of the IETF pairing-friendly curves
+    /// draft and Zcash, with ξ = 1 + u and an M-type twist.
+    pub fn bls12_381() -> Self {
+        let tower = Tower::new(
+            parse("0x1a0111ea397fe69a4b1ba7b6434bacd764774b84f38512bf6730d2a0f6b0f6241eabfffeb153ffffb9feffffffffaaab"),
+            (1, 1),
+        );
+        let b = Fp::from_u64(4, &tower);
+        let b_twist = tower.xi().scale(&b);
+        let g1 = Point::Affine(
+            Fp::new(
+                parse("0x17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb"),
+                &tower,
+            ),
+            Fp::new(
+                parse("0x08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1"),
+                &tower,
+            ),
+        );
+        let g2 = Point::Affine(
+            Fp2::from_biguints(
+                parse("0x024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8"),
+                parse("0x13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e"),
+                &tower,
+            ),
+            Fp2::from_biguints(
+                parse("0x0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801"),
+                parse("0x0606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be"),
+                &tower,
+            ),
+        );
+        PairingCurve {
+            name: "BLS12-381",
+            family: Family::Bls12,
+            twist: Twist::M,
+            x: -0xd201000000010000,
+            r: parse("0x73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001"),
+            g1_curve: Curve::new(b),
+            g2_curve: Curve::new(b_twist),
+            g1,
+            g2,
+            tower,
+        }
+    }
+
+    pub fn p(&self) -> &BigUint {
+        &self.tower.p
+    }
+
+    /// Checks that p and r are the family polynomials evaluated at x and
+    /// that both generators lie on their curves and have order r.
+    pub fn validate(&self) -> Result<(), &'static str> {
+        let x = BigInt::from(self.x);
+        let (p, r): (BigInt, BigInt) = match self.family {
+            Family::Bn => {
+                let r = x.pow(4) * 36u32 + x.pow(3) * 36u32 + x.pow(2) * 18u32 + &x * 6u32 + 1u32;
+                let p = &r + x.pow(2) * 6u32;
+                (p, r)
+            }
+            Family::Bls12 => {
+                let r = x.pow(4) - x.pow(2) + 1u32;
+                let p = (&x - 1u32).pow(2) * &r / 3u32 + &x;
+                (p, r)
+            }
+        };
+        if p.to_biguint().as_ref() != Some(self.p()) || r.to_biguint().as_ref() != Some(&self.r) {
+            return Err("p and r do not match the family polynomials at x");
+        }
+        if !self.g1_curve.contains(&self.g1) || !self.g2_curve.contains(&self.g2) {
+            return Err("a generator is not on its curve");
+        }
+        if !self.g1_curve.multiply(&self.r, &self.g1).is_infinity()
+            || !self.g2_curve.multiply(&self.r, &self.g2).is_infinity()
+        {
+            return Err("a generator does not have order r");
+        }
+        Ok(())
+    }
+}
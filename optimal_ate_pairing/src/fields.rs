@@ -0,0 +1,528 @@
+use std::fmt;
+use std::rc::Rc;
+
+use num_bigint::BigUint;
+use num_traits::Zero;
+
+/// The tower F_p ⊂ F_p2 = F_p[u]/(u² + 1) ⊂ F_p6 = F_p2[v]/(v³ - ξ)
+/// ⊂ F_p12 = F_p6[w]/(w² - v) shared by BN254 and BLS12-381, which differ
+/// only in p and the non-residue ξ = xi.0 + xi.1 u.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Tower {
+    pub p: BigUint,
+    pub xi: (u64, u64),
+    /// γ_i = ξ^(i (p - 1) / 6) for i = 0..6, so that (w^i)^p = γ_i w^i
+    frobenius: Vec<(BigUint, BigUint)>,
+}
+
+impl Tower {
+    pub fn new(p: BigUint, xi: (u64, u64)) -> Rc<Self> {
+        let bare = Rc::new(Tower {
+            p: p.clone(),
+            xi,
+            frobenius: Vec::new(),
+        });
+        let xi_element = Fp2::new(Fp::from_u64(xi.0, &bare), Fp::from_u64(xi.1, &bare));
+        let step = xi_element.pow(&((&p - 1u32) / 6u32));
+        let mut frobenius = Vec::with_capacity(6);
+        let mut gamma = Fp2::one(&bare);
+        for _ in 0..6 {
+            frobenius.push((gamma.c0.value.clone(), gamma.c1.value.clone()));
+            gamma = gamma.mul(&step);
+        }
+        Rc::new(Tower { p, xi, frobenius })
+    }
+
+    pub fn xi(self: &Rc<Self>) -> Fp2 {
+        Fp2::new(Fp::from_u64(self.xi.0, self), Fp::from_u64(self.xi.1, self))
+    }
+
+    /// γ_i = ξ^(i (p - 1) / 6), the Frobenius twist of w^i.
+    pub fn gamma(self: &Rc<Self>, i: usize) -> Fp2 {
+        let (c0, c1) = &self.frobenius[i];
+        Fp2::new(Fp::new(c0.clone(), self), Fp::new(c1.clone(), self))
+    }
+}
+
+/// The arithmetic every level of the tower provides, so that curve code can
+/// be written once for points over F_p and over F_p2.
+pub trait Field: Clone + PartialEq {
+    fn zero_like(&self) -> Self;
+    fn one_like(&self) -> Self;
+    fn is_zero(&self) -> bool;
+    fn add(&self, other: &Self) -> Self;
+    fn sub(&self, other: &Self) -> Self;
+    fn neg(&self) -> Self;
+    fn mul(&self, other: &Self) -> Self;
+    fn inverse(&self) -> Option<Self>;
+
+    fn square(&self) -> Self {
+        self.mul(self)
+    }
+
+    fn double(&self) -> Self {
+        self.add(self)
+    }
+
+    fn is_one(&self) -> bool {
+        *self == self.one_like()
+    }
+
+    /// self^exponent by square-and-multiply.
+    fn pow(&self, exponent: &BigUint) -> Self {
+        let mut result = self.one_like();
+        for i in (0..exponent.bits()).rev() {
+            result = result.square();
+            if exponent.bit(i) {
+                result = result.mul(self);
+            }
+        }
+        result
+    }
+}
+
+/// An element of the prime field F_p.
+#[derive(Clone)]
+pub struct Fp {
+    pub value: BigUint,
+    pub tower: Rc<Tower>,
+}
+
+impl Fp {
+    pub fn new(value: BigUint, tower: &Rc<Tower>) -> Self {
+        Fp {
+            value: value % &tower.p,
+            tower: Rc::clone(tower),
+        }
+    }
+
+    pub fn from_u64(value: u64, tower: &Rc<Tower>) -> Self {
+        Fp::new(BigUint::from(value), tower)
+    }
+}
+
+impl PartialEq for Fp {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl Field for Fp {
+    fn zero_like(&self) -> Self {
+        Fp::from_u64(0, &self.tower)
+    }
+
+    fn one_like(&self) -> Self {
+        Fp::from_u64(1, &self.tower)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.value.is_zero()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        let mut value = &self.value + &other.value;
+        if value >= self.tower.p {
+            value -= &self.tower.p;
+        }
+        Fp {
+            value,
+            tower: Rc::clone(&self.tower),
+        }
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        let value = if self.value >= other.value {
+            &self.value - &other.value
+        } else {
+            &self.tower.p - &other.value + &self.value
+        };
+        Fp {
+            value,
+            tower: Rc::clone(&self.tower),
+        }
+    }
+
+    fn neg(&self) -> Self {
+        self.zero_like().sub(self)
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        Fp {
+            value: &self.value * &other.value % &self.tower.p,
+            tower: Rc::clone(&self.tower),
+        }
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        if self.is_zero() {
+            return None;
+        }
+        let exponent = &self.tower.p - 2u32;
+        Some(Fp {
+            value: self.value.modpow(&exponent, &self.tower.p),
+            tower: Rc::clone(&self.tower),
+        })
+    }
+}
+
+/// c0 + c1 u with u² = -1.
+#[derive(Clone, PartialEq)]
+pub struct Fp2 {
+    pub c0: Fp,
+    pub c1: Fp,
+}
+
+impl Fp2 {
+    pub fn new(c0: Fp, c1: Fp) -> Self {
+        Fp2 { c0, c1 }
+    }
+
+    pub fn from_biguints(c0: BigUint, c1: BigUint, tower: &Rc<Tower>) -> Self {
+        Fp2::new(Fp::new(c0, tower), Fp::new(c1, tower))
+    }
+
+    pub fn one(tower: &Rc<Tower>) -> Self {
+        Fp2::new(Fp::from_u64(1, tower), Fp::from_u64(0, tower))
+    }
+
+    pub fn scale(&self, c: &Fp) -> Self {
+        Fp2::new(self.c0.mul(c), self.c1.mul(c))
+    }
+
+    /// The p-power Frobenius, which on F_p2 is conjugation u -> -u.
+    pub fn conjugate(&self) -> Self {
+        Fp2::new(self.c0.clone(), self.c1.neg())
+    }
+
+    /// Multiplication by the non-residue ξ = x0 + x1 u, for small x0, x1.
+    pub fn mul_by_xi(&self) -> Self {
+        let tower = &self.c0.tower;
+        let x0 = Fp::from_u64(tower.xi.0, tower);
+        let x1 = Fp::from_u64(tower.xi.1, tower);
+        Fp2::new(
+            self.c0.mul(&x0).sub(&self.c1.mul(&x1)),
+            self.c0.mul(&x1).add(&self.c1.mul(&x0)),
+        )
+    }
+}
+
+impl Field for Fp2 {
+    fn zero_like(&self) -> Self {
+        Fp2::new(self.c0.zero_like(), self.c0.zero_like())
+    }
+
+    fn one_like(&self) -> Self {
+        Fp2::new(self.c0.one_like(), self.c0.zero_like())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Fp2::new(self.c0.add(&other.c0), self.c1.add(&other.c1))
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Fp2::new(self.c0.sub(&other.c0), self.c1.sub(&other.c1))
+    }
+
+    fn neg(&self) -> Self {
+        Fp2::new(self.c0.neg(), self.c1.neg())
+    }
+
+    /// Karatsuba: three base-field products instead of four.
+    fn mul(&self, other: &Self) -> Self {
+        let a = self.c0.mul(&other.c0);
+        let b = self.c1.mul(&other.c1);
+        let cross = self.c0.add(&self.c1).mul(&other.c0.add(&other.c1));
+        Fp2::new(a.sub(&b), cross.sub(&a).sub(&b))
+    }
+
+    /// (c0 + c1 u)² = (c0 + c1)(c0 - c1) + 2 c0 c1 u
+    fn square(&self) -> Self {
+        let real = self.c0.add(&self.c1).mul(&self.c0.sub(&self.c1));
+        Fp2::new(real, self.c0.mul(&self.c1).double())
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let norm = self.c0.square().add(&self.c1.square());
+        let inverse = norm.inverse()?;
+        Some(Fp2::new(self.c0.mul(&inverse), self.c1.neg().mul(&inverse)))
+    }
+}
+
+/// c0 + c1 v + c2 v² with v³ = ξ.
+#[derive(Clone, PartialEq)]
+pub struct Fp6 {
+    pub c0: Fp2,
+    pub c1: Fp2,
+    pub c2: Fp2,
+}
+
+impl Fp6 {
+    pub fn new(c0: Fp2, c1: Fp2, c2: Fp2) -> Self {
+        Fp6 { c0, c1, c2 }
+    }
+
+    /// Multiplication by v, which shifts coefficients and wraps v³ to ξ.
+    pub fn mul_by_v(&self) -> Self {
+        Fp6::new(self.c2.mul_by_xi(), self.c0.clone(), self.c1.clone())
+    }
+}
+
+impl Field for Fp6 {
+    fn zero_like(&self) -> Self {
+        let zero = self.c0.zero_like();
+        Fp6::new(zero.clone(), zero.clone(), zero)
+    }
+
+    fn one_like(&self) -> Self {
+        let zero = self.c0.zero_like();
+        Fp6::new(self.c0.one_like(), zero.clone(), zero)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Fp6::new(
+            self.c0.add(&other.c0),
+            self.c1.add(&other.c1),
+            self.c2.add(&other.c2),
+        )
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Fp6::new(
+            self.c0.sub(&other.c0),
+            self.c1.sub(&other.c1),
+            self.c2.sub(&other.c2),
+        )
+    }
+
+    fn neg(&self) -> Self {
+        Fp6::new(self.c0.neg(), self.c1.neg(), self.c2.neg())
+    }
+
+    /// Karatsuba-style interpolation with six F_p2 products.
+    fn mul(&self, other: &Self) -> Self {
+        let t0 = self.c0.mul(&other.c0);
+        let t1 = self.c1.mul(&other.c1);
+        let t2 = self.c2.mul(&other.c2);
+        let c0 = self
+            .c1
+            .add(&self.c2)
+            .mul(&other.c1.add(&other.c2))
+            .sub(&t1)
+            .sub(&t2)
+            .mul_by_xi()
+            .add(&t0);
+        let c1 = self
+            .c0
+            .add(&self.c1)
+            .mul(&other.c0.add(&other.c1))
+            .sub(&t0)
+            .sub(&t1)
+            .add(&t2.mul_by_xi());
+        let c2 = self
+            .c0
+            .add(&self.c2)
+            .mul(&other.c0.add(&other.c2))
+            .sub(&t0)
+            .sub(&t2)
+            .add(&t1);
+        Fp6::new(c0, c1, c2)
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let t0 = self.c0.square().sub(&self.c1.mul(&self.c2).mul_by_xi());
+        let t1 = self.c2.square().mul_by_xi().sub(&self.c0.mul(&self.c1));
+        let t2 = self.c1.square().sub(&self.c0.mul(&self.c2));
+        let norm = self
+            .c0
+            .mul(&t0)
+            .add(&self.c2.mul(&t1).mul_by_xi())
+            .add(&self.c1.mul(&t2).mul_by_xi());
+        let inverse = norm.inverse()?;
+        Some(Fp6::new(
+            t0.mul(&inverse),
+            t1.mul(&inverse),
+            t2.mul(&inverse),
+        ))
+    }
+}
+
+/// c0 + c1 w with w² = v, the target field of both pairings.
+#[derive(Clone, PartialEq)]
+pub struct Fp12 {
+    pub c0: Fp6,
+    pub c1: Fp6,
+}
+
+impl Fp12 {
+    pub fn new(c0: Fp6, c1: Fp6) -> Self {
+        Fp12 { c0, c1 }
+    }
+
+    pub fn one(tower: &Rc<Tower>) -> Self {
+        let one = Fp2::one(tower);
+        let zero = one.zero_like();
+        let c0 = Fp6::new(one, zero.clone(), zero.clone());
+        let c1 = c0.zero_like();
+        Fp12::new(c0, c1)
+    }
+
+    /// The p^6-power Frobenius w -> -w. On the cyclotomic subgroup, which
+    /// holds every value after the easy part of the final exponentiation,
+    /// this is the inverse.
+    pub fn conjugate(&self) -> Self {
+        Fp12::new(self.c0.clone(), self.c1.neg())
+    }
+
+    /// The p-power Frobenius, coefficient by coefficient in the basis w^i:
+    /// (a w^i)^p = conj(a) γ_i w^i.
+    pub fn frobenius(&self) -> Self {
+        let tower = &self.c0.c0.c0.tower;
+        let twist = |a: &Fp2, i: usize| a.conjugate().mul(&tower.gamma(i));
+        Fp12::new(
+            Fp6::new(
+                twist(&self.c0.c0, 0),
+                twist(&self.c0.c1, 2),
+                twist(&self.c0.c2, 4),
+            ),
+            Fp6::new(
+                twist(&self.c1.c0, 1),
+                twist(&self.c1.c1, 3),
+                twist(&self.c1.c2, 5),
+            ),
+        )
+    }
+
+    pub fn frobenius_power(&self, power: usize) -> Self {
+        (0..power).fold(self.clone(), |f, _| f.frobenius())
+    }
+
+    /// self^exponent for elements of the cyclotomic subgroup, where negative
+    /// exponents cost only a conjugation.
+    pub fn cyclotomic_pow(&self, exponent: i128) -> Self {
+        let power = self.pow(&BigUint::from(exponent.unsigned_abs()));
+        if exponent < 0 {
+            power.conjugate()
+        } else {
+            power
+        }
+    }
+
+    /// The coefficients of the twelve F_p components, in the order c0.c0.c0,
+    /// c0.c0.c1, c0.c1.c0, ..., c1.c2.c1.
+    pub fn coefficients(&self) -> Vec<BigUint> {
+        [&self.c0, &self.c1]
+            .iter()
+            .flat_map(|c| [&c.c0, &c.c1, &c.c2])
+            .flat_map(|c| [c.c0.value.clone(), c.c1.value.clone()])
+            .collect()
+    }
+}
+
+impl Field for Fp12 {
+    fn zero_like(&self) -> Self {
+        Fp12::new(self.c0.zero_like(), self.c0.zero_like())
+    }
+
+    fn one_like(&self) -> Self {
+        Fp12::new(self.c0.one_like(), self.c0.zero_like())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    fn add(&self, other: &Self) -> Self {
+        Fp12::new(self.c0.add(&other.c0), self.c1.add(&other.c1))
+    }
+
+    fn sub(&self, other: &Self) -> Self {
+        Fp12::new(self.c0.sub(&other.c0), self.c1.sub(&other.c1))
+    }
+
+    fn neg(&self) -> Self {
+        Fp12::new(self.c0.neg(), self.c1.neg())
+    }
+
+    fn mul(&self, other: &Self) -> Self {
+        let a = self.c0.mul(&other.c0);
+        let b = self.c1.mul(&other.c1);
+        let cross = self
+            .c0
+            .add(&self.c1)
+            .mul(&other.c0.add(&other.c1))
+            .sub(&a)
+            .sub(&b);
+        Fp12::new(a.add(&b.mul_by_v()), cross)
+    }
+
+    /// (c0 + c1 w)² = (c0 + c1)(c0 + v c1) - (1 + v) c0 c1 + 2 c0 c1 w
+    fn square(&self) -> Self {
+        let product = self.c0.mul(&self.c1);
+        let real = self
+            .c0
+            .add(&self.c1)
+            .mul(&self.c0.add(&self.c1.mul_by_v()))
+            .sub(&product)
+            .sub(&product.mul_by_v());
+        Fp12::new(real, product.double())
+    }
+
+    fn inverse(&self) -> Option<Self> {
+        let norm = self.c0.square().sub(&self.c1.square().mul_by_v());
+        let inverse = norm.inverse()?;
+        Some(Fp12::new(
+            self.c0.mul(&inverse),
+            self.c1.neg().mul(&inverse),
+        ))
+    }
+}
+
+impl fmt::Display for Fp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#x}", self.value)
+    }
+}
+
+impl fmt::Display for Fp2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} + {} u", self.c0, self.c1)
+    }
+}
+
+impl fmt::Display for Fp12 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let coefficients: Vec<String> = self
+            .coefficients()
+            .iter()
+            .map(|c| format!("{:#x}", c))
+            .collect();
+        write!(f, "[{}]", coefficients.join(", "))
+    }
+}
+
+impl fmt::Debug for Fp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Debug for Fp2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+impl fmt::Debug for Fp12 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
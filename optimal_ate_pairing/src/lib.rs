@@ -0,0 +1,9 @@
+//! Optimal ate pairings on BN254 and BLS12-381 over the tower
+//! F_p ⊂ F_p2 ⊂ F_p6 ⊂ F_p12, with the final exponentiation split into its
+//! easy part and an addition chain in the curve parameter for the hard part.
+
+pub mod curve;
+pub mod curves;
+pub mod fields;
+pub mod pairing;
+pub mod vectors;
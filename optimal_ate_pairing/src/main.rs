@@ -0,0 +1,62 @@
+use std::path::Path;
+use std::time::Instant;
+
+use num_bigint::BigUint;
+use optimal_ate_pairing::curves::PairingCurve;
+use optimal_ate_pairing::fields::Field;
+use optimal_ate_pairing::vectors::TestVectors;
+
+fn report(curve: &PairingCurve, vectors: &str) {
+    println!("{}", curve.name);
+    match curve.validate() {
+        Ok(()) => println!("  parameters and generators valid"),
+        Err(e) => println!("  INVALID: {}", e),
+    }
+
+    // π acts on G2 as multiplication by p, which checks the Frobenius constants
+    let p_mod_r = curve.p() % &curve.r;
+    let frobenius = curve.twisted_frobenius(&curve.g2);
+    println!(
+        "  π(G2) = [p]G2: {}",
+        frobenius == curve.g2_curve.multiply(&p_mod_r, &curve.g2)
+    );
+
+    let start = Instant::now();
+    let e = curve.pairing(&curve.g1, &curve.g2);
+    println!("  e(G1, G2) computed in {:?}", start.elapsed());
+    println!("  e(G1, G2) = {}", e);
+    println!(
+        "  non-degenerate: {}, in μ_r: {}",
+        !e.is_one(),
+        e.pow(&curve.r).is_one()
+    );
+
+    let (a, b) = (BigUint::from(1234567u32), BigUint::from(7654321u32));
+    let p = curve.g1_curve.multiply(&a, &curve.g1);
+    let q = curve.g2_curve.multiply(&b, &curve.g2);
+    println!(
+        "  e([a]G1, [b]G2) = e(G1, G2)^(ab): {}",
+        curve.pairing(&p, &q) == e.pow(&(&a * &b))
+    );
+
+    // The addition chain for the hard part against plain exponentiation
+    let easy = curve.final_exponentiation_easy(&curve.miller_loop(&curve.g1, &curve.g2));
+    println!(
+        "  hard part matches f^(c (p^4 - p^2 + 1) / r): {}",
+        curve.final_exponentiation_hard(&easy) == easy.pow(&curve.hard_exponent())
+    );
+
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("test_vectors")
+        .join(vectors);
+    match TestVectors::load(&path).and_then(|v| v.check(curve)) {
+        Ok(passed) => println!("  {}: {} checks passed", vectors, passed),
+        Err(e) => println!("  {}: FAILED: {}", vectors, e),
+    }
+    println!();
+}
+
+fn main() {
+    report(&PairingCurve::bn254(), "bn254.txt");
+    report(&PairingCurve::bls12_381(), "bls12_381.txt");
+}
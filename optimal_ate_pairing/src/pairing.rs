@@ -0,0 +1,318 @@
+use num_bigint::{BigInt, BigUint};
+
+use crate::curve::Point;
+use crate::curves::{Family, PairingCurve, Twist};
+use crate::fields::{Field, Fp, Fp12, Fp2, Fp6};
+
+impl PairingCurve {
+    /// The untwisted Frobenius π acting on E'(F_p2): the point whose image
+    /// in E(F_p12) is π applied to the image of Q. On G2 this is [p].
+    pub fn twisted_frobenius(&self, q: &Point<Fp2>) -> Point<Fp2> {
+        let Point::Affine(x, y) = q else {
+            return Point::Infinity;
+        };
+        let (gamma_x, gamma_y) = (self.tower.gamma(2), self.tower.gamma(3));
+        let (gamma_x, gamma_y) = match self.twist {
+            Twist::D => (gamma_x, gamma_y),
+            Twist::M => (gamma_x.inverse().unwrap(), gamma_y.inverse().unwrap()),
+        };
+        Point::Affine(x.conjugate().mul(&gamma_x), y.conjugate().mul(&gamma_y))
+    }
+
+    /// The line through T and S on the twist, evaluated at P and embedded
+    /// in F_p12 as a sparse element. Vertical lines lie in F_p6 and are
+    /// erased by the final exponentiation, so they evaluate to 1.
+    ///
+    /// With λ the slope on the twist the untwisted line is
+    ///   D-type: y_P - λ x_P w + (λ x_T - y_T) w³
+    ///   M-type: (λ x_T - y_T) - λ x_P w² + y_P w³, scaled by w³ ∈ F_p4
+    fn line(&self, t: &Point<Fp2>, s: &Point<Fp2>, p: &Point<Fp>) -> Fp12 {
+        let one = Fp12::one(&self.tower);
+        let (Point::Affine(xt, yt), Point::Affine(xp, yp)) = (t, p) else {
+            return one;
+        };
+        let Some(lambda) = self.g2_curve.slope(t, s) else {
+            return one;
+        };
+        let zero = xt.zero_like();
+        let constant = lambda.mul(xt).sub(yt);
+        let linear = lambda.scale(xp).neg();
+        let y_term = Fp2::new(yp.clone(), yp.zero_like());
+        match self.twist {
+            Twist::D => Fp12::new(
+                Fp6::new(y_term, zero.clone(), zero.clone()),
+                Fp6::new(linear, constant, zero),
+            ),
+            Twist::M => Fp12::new(
+                Fp6::new(constant, linear, zero.clone()),
+                Fp6::new(zero.clone(), y_term, zero),
+            ),
+        }
+    }
+
+    /// Miller's loop for f_{n,Q}(P) on the twist, returning the accumulated
+    /// value and [n]Q.
+    fn miller_loop_with(&self, n: &BigUint, p: &Point<Fp>, q: &Point<Fp2>) -> (Fp12, Point<Fp2>) {
+        let mut f = Fp12::one(&self.tower);
+        let mut t = q.clone();
+        for i in (0..n.bits() - 1).rev() {
+            f = f.square().mul(&self.line(&t, &t, p));
+            t = self.g2_curve.double(&t);
+            if n.bit(i) {
+                f = f.mul(&self.line(&t, q, p));
+                t = self.g2_curve.add(&t, q);
+            }
+        }
+        (f, t)
+    }
+
+    /// The optimal ate Miller loop, before the final exponentiation:
+    ///   BN:    f_{6u+2,Q}(P) l_{[6u+2]Q, π(Q)}(P) l_{[6u+2]Q + π(Q), -π²(Q)}(P)
+    ///   BLS12: f_{x,Q}(P), conjugated when x < 0
+    pub fn miller_loop(&self, p: &Point<Fp>, q: &Point<Fp2>) -> Fp12 {
+        if p.is_infinity() || q.is_infinity() {
+            return Fp12::one(&self.tower);
+        }
+        match self.family {
+            Family::Bn => {
+                let n = (BigInt::from(self.x) * 6u32 + 2u32).to_biguint().unwrap();
+                let (mut f, mut t) = self.miller_loop_with(&n, p, q);
+                let q1 = self.twisted_frobenius(q);
+                let q2 = self.g2_curve.neg(&self.twisted_frobenius(&q1));
+                f = f.mul(&self.line(&t, &q1, p));
+                t = self.g2_curve.add(&t, &q1);
+                f.mul(&self.line(&t, &q2, p))
+            }
+            Family::Bls12 => {
+                let n = BigUint::from(self.x.unsigned_abs());
+                let (f, _) = self.miller_loop_with(&n, p, q);
+                if self.x < 0 {
+                    f.conjugate()
+                } else {
+                    f
+                }
+            }
+        }
+    }
+
+    /// f^((p^6 - 1)(p^2 + 1)), which lands in the cyclotomic subgroup.
+    pub fn final_exponentiation_easy(&self, f: &Fp12) -> Fp12 {
+        let f = f
+            .conjugate()
+            .mul(&f.inverse().expect("Miller loop value is zero"));
+        f.frobenius_power(2).mul(&f)
+    }
+
+    /// f^(c (p^4 - p^2 + 1) / r) for f in the cyclotomic subgroup, by the
+    /// addition chains of the reference implementations, so that e(P, Q)
+    /// agrees with their published values. The multiple c, prime to r, is
+    /// what lets the chains use only exponentiations by x and Frobenius maps:
+    ///   BN:    c = 2x(6x² + 3x + 1), Fuentes-Castañeda, Knapp and
+    ///          Rodríguez-Henríquez, as in libff and substrate-bn
+    ///   BLS12: c = 3, from 3λ = (x - 1)² (x + p)(x² + p² - 1) + 3, as in
+    ///          zkcrypto's bls12_381 and blst
+    pub fn final_exponentiation_hard(&self, f: &Fp12) -> Fp12 {
+        match self.family {
+            Family::Bn => {
+                let exp_by_neg_x = |g: &Fp12| g.cyclotomic_pow(-self.x);
+                let a = exp_by_neg_x(f);
+                let b = a.square();
+                let c = b.square();
+                let d = c.mul(&b);
+                let e = exp_by_neg_x(&d);
+                let g = exp_by_neg_x(&e.square());
+                let k = g.conjugate().mul(&e).mul(&d.conjugate());
+                let l = k.mul(&b);
+                let n = f.mul(&k.mul(&e));
+                l.frobenius()
+                    .mul(&n)
+                    .mul(&k.frobenius_power(2))
+                    .mul(&f.conjugate().mul(&l).frobenius_power(3))
+            }
+            Family::Bls12 => {
+                let a = f.cyclotomic_pow(self.x).mul(&f.conjugate());
+                let a = a.cyclotomic_pow(self.x).mul(&a.conjugate());
+                let b = a.cyclotomic_pow(self.x).mul(&a.frobenius());
+                let c = b
+                    .cyclotomic_pow(self.x)
+                    .cyclotomic_pow(self.x)
+                    .mul(&b.frobenius_power(2))
+                    .mul(&b.conjugate());
+                c.mul(&f.square().mul(f))
+            }
+        }
+    }
+
+    pub fn final_exponentiation(&self, f: &Fp12) -> Fp12 {
+        self.final_exponentiation_hard(&self.final_exponentiation_easy(f))
+    }
+
+    /// The optimal ate pairing e(P, Q) for P in G1 and Q in G2.
+    pub fn pairing(&self, p: &Point<Fp>, q: &Point<Fp2>) -> Fp12 {
+        self.final_exponentiation(&self.miller_loop(p, q))
+    }
+
+    /// Whether Π e(P_i, Q_i) = 1, sharing one final exponentiation as the
+    /// EIP-197 precompile does.
+    pub fn pairing_check(&self, pairs: &[(Point<Fp>, Point<Fp2>)]) -> bool {
+        let product = pairs.iter().fold(Fp12::one(&self.tower), |f, (p, q)| {
+            f.mul(&self.miller_loop(p, q))
+        });
+        self.final_exponentiation(&product).is_one()
+    }
+
+    /// The multiple c of the hard part above.
+    pub fn hard_part_multiple(&self) -> BigInt {
+        let x = BigInt::from(self.x);
+        match self.family {
+            Family::Bn => &x * 2 * (&x * &x * 6 + &x * 3 + 1),
+            Family::Bls12 => BigInt::from(3),
+        }
+    }
+
+    /// c (p^4 - p^2 + 1) / r, the exponent of the hard part, for checking the
+    /// addition chains above against plain exponentiation.
+    pub fn hard_exponent(&self) -> BigUint {
+        let p = self.p();
+        let p2 = p * p;
+        let multiple = self.hard_part_multiple().to_biguint().unwrap();
+        multiple * ((&p2 * &p2 - &p2 + 1u32) / &self.r)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_integer::Integer;
+    use num_traits::{One, Zero};
+
+    use super::*;
+    use crate::curve::Curve;
+
+    fn lift(a: &Fp2) -> Fp12 {
+        let zero = a.zero_like();
+        Fp12::new(
+            Fp6::new(a.clone(), zero.clone(), zero.clone()),
+            Fp6::new(zero.clone(), zero.clone(), zero),
+        )
+    }
+
+    fn lift_fp(a: &Fp) -> Fp12 {
+        lift(&Fp2::new(a.clone(), a.zero_like()))
+    }
+
+    /// The image of Q on E(F_p12): (x w², y w³) for a D-type twist and
+    /// (x / w², y / w³) for an M-type one.
+    fn untwist(curve: &PairingCurve, q: &Point<Fp2>) -> Point<Fp12> {
+        let Point::Affine(x, y) = q else {
+            return Point::Infinity;
+        };
+        let one = Fp12::one(&curve.tower);
+        let w = Fp12::new(one.c1.clone(), one.c0.clone());
+        let (w2, w3) = (w.square(), w.square().mul(&w));
+        let (w2, w3) = match curve.twist {
+            Twist::D => (w2, w3),
+            Twist::M => (w2.inverse().unwrap(), w3.inverse().unwrap()),
+        };
+        Point::Affine(lift(x).mul(&w2), lift(y).mul(&w3))
+    }
+
+    /// The reduced Tate pairing f_{r,Q}(P)^((p^12 - 1) / r) by the textbook
+    /// Miller loop on E(F_p12), sharing only the field arithmetic with the
+    /// optimal ate code. Vertical lines lie in F_p6 and are left out.
+    fn tate(curve: &PairingCurve, p: &Point<Fp>, q: &Point<Fp2>) -> Fp12 {
+        let Point::Affine(xp, yp) = p else {
+            return Fp12::one(&curve.tower);
+        };
+        let (xp, yp) = (lift_fp(xp), lift_fp(yp));
+        let e = Curve::new(lift_fp(&curve.g1_curve.b));
+        let q = untwist(curve, q);
+        assert!(e.contains(&q));
+        let line = |t: &Point<Fp12>, s: &Point<Fp12>| {
+            let Point::Affine(xt, yt) = t else {
+                unreachable!("Q has order r");
+            };
+            match e.slope(t, s) {
+                Some(lambda) => yp.sub(yt).sub(&lambda.mul(&xp.sub(xt))),
+                None => xp.sub(xt),
+            }
+        };
+        let mut f = Fp12::one(&curve.tower);
+        let mut t = q.clone();
+        for i in (0..curve.r.bits() - 1).rev() {
+            f = f.square().mul(&line(&t, &t));
+            t = e.double(&t);
+            if curve.r.bit(i) {
+                f = f.mul(&line(&t, &q));
+                t = e.add(&t, &q);
+            }
+        }
+        assert!(t.is_infinity());
+        f.pow(&((curve.p().pow(12) - 1u32) / &curve.r))
+    }
+
+    /// Vercauteren's optimal ate pairing comes from λ = Σ c_i p^i = m r: the
+    /// Miller function f_{λ,Q} equals f_{r,Q}^m and splits into the optimal
+    /// ate value times the ate pairings for p^i, and the ate pairing for p
+    /// raised to K = 12 p^11 is t^((p^12 - 1) / r). With the multiple c of
+    /// the hard part on top,
+    ///   e^K = t^(c (m K - d (p^12 - 1) / r)) with d = Σ i c_i p^(i-1),
+    /// which a wrong line, Miller loop or final exponentiation breaks.
+    fn assert_matches_tate(curve: &PairingCurve, coefficients: &[BigInt]) {
+        let (p, r) = (
+            BigInt::from(curve.p().clone()),
+            BigInt::from(curve.r.clone()),
+        );
+        let (mut lambda, mut d) = (BigInt::zero(), BigInt::zero());
+        for (i, c) in coefficients.iter().enumerate() {
+            lambda += c * p.pow(i as u32);
+            if i > 0 {
+                d += c * i * p.pow(i as u32 - 1);
+            }
+        }
+        assert!(lambda.is_multiple_of(&r));
+        let m = lambda / &r;
+        let k = p.pow(11) * 12u32;
+        let l = (p.pow(12) - 1u32) / &r;
+        let reduce = |e: BigInt| e.mod_floor(&r).to_biguint().unwrap();
+
+        let (g1, g2) = (&curve.g1, &curve.g2);
+        let e = curve.pairing(g1, g2);
+        let t = tate(curve, g1, g2);
+        assert!(!e.is_one() && !t.is_one());
+        let c = curve.hard_part_multiple();
+        assert_eq!(
+            e.pow(&reduce(k.clone())),
+            t.pow(&reduce(c * (m * k - d * l)))
+        );
+    }
+
+    #[test]
+    fn bn254_matches_the_tate_pairing() {
+        let curve = PairingCurve::bn254();
+        let u = BigInt::from(curve.x);
+        let coefficients = [
+            u * 6u32 + 2u32,
+            BigInt::one(),
+            -BigInt::one(),
+            BigInt::one(),
+        ];
+        assert_matches_tate(&curve, &coefficients);
+    }
+
+    #[test]
+    fn bls12_381_matches_the_tate_pairing() {
+        let curve = PairingCurve::bls12_381();
+        assert_matches_tate(&curve, &[BigInt::from(curve.x), -BigInt::one()]);
+    }
+
+    #[test]
+    fn hard_part_matches_plain_exponentiation() {
+        for curve in [PairingCurve::bn254(), PairingCurve::bls12_381()] {
+            let easy = curve.final_exponentiation_easy(&curve.miller_loop(&curve.g1, &curve.g2));
+            assert_eq!(
+                curve.final_exponentiation_hard(&easy),
+                easy.pow(&curve.hard_exponent())
+            );
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::fs;
+use std::path::Path;
+
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::Zero;
+
+use crate::curve::Point;
+use crate::curves::PairingCurve;
+use crate::fields::{Fp, Fp2};
+
+/// Test vectors read from a plain `key = value` file, one entry per line and
+/// `#` for comments:
+///   p, r, x           the field prime, group order and curve parameter
+///   g1 = x y          the G1 generator
+///   g2 = x0 x1 y0 y1  the G2 generator on the twist, x = x0 + x1 u
+///   encoding = eip197|eip2537
+///                     the byte layout of the pairing check inputs
+///   pairing_check = hex -> true|false
+///                     a precompile input and whether Π e(P_i, Q_i) = 1
+///   gt = a b -> c0 ... c11
+///                     the expected e([a] G1, [b] G2), in
+///                     `Fp12::coefficients` order
+pub struct TestVectors {
+    pub p: BigUint,
+    pub r: BigUint,
+    pub x: BigInt,
+    pub g1: Vec<BigUint>,
+    pub g2: Vec<BigUint>,
+    pub encoding: Option<Encoding>,
+    pub pairing_checks: Vec<(Vec<u8>, bool)>,
+    pub gt: Option<(BigUint, BigUint, Vec<BigUint>)>,
+}
+
+/// One (G1, G2) argument of a pairing product.
+type Pair = (Point<Fp>, Point<Fp2>);
+
+/// How a pairing precompile lays out its (G1, G2) pairs.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Encoding {
+    /// EIP-197: 32-byte coordinates, F_p2 elements imaginary part first.
+    Eip197,
+    /// EIP-2537: 64-byte coordinates, F_p2 elements real part first.
+    Eip2537,
+}
+
+impl Encoding {
+    fn coordinate_len(self) -> usize {
+        match self {
+            Encoding::Eip197 => 32,
+            Encoding::Eip2537 => 64,
+        }
+    }
+}
+
+fn parse_int(digits: &str) -> Option<BigInt> {
+    let (sign, digits) = match digits.strip_prefix('-') {
+        Some(rest) => (Sign::Minus, rest),
+        None => (Sign::Plus, digits),
+    };
+    let magnitude = match digits.strip_prefix("0x") {
+        Some(hex) => BigUint::parse_bytes(hex.as_bytes(), 16)?,
+        None => BigUint::parse_bytes(digits.as_bytes(), 10)?,
+    };
+    Some(BigInt::from_biguint(sign, magnitude))
+}
+
+fn parse_uints(values: &str) -> Option<Vec<BigUint>> {
+    values
+        .split_whitespace()
+        .map(|v| parse_int(v)?.to_biguint())
+        .collect()
+}
+
+fn parse_hex(digits: &str) -> Option<Vec<u8>> {
+    if !digits.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(digits.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
+impl TestVectors {
+    pub fn load(path: &Path) -> Result<Self, &'static str> {
+        let text = fs::read_to_string(path).map_err(|_| "could not read the test vector file")?;
+        Self::parse(&text)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, &'static str> {
+        let malformed = "malformed test vector line";
+        let (mut p, mut r, mut x, mut g1, mut g2, mut gt) = (None, None, None, None, None, None);
+        let mut encoding = None;
+        let mut pairing_checks = Vec::new();
+
+        for line in text.lines().map(str::trim) {
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or(malformed)?;
+            let value = value.trim();
+            match key.trim() {
+                "p" => {
+                    p = Some(
+                        parse_int(value)
+                            .and_then(|v| v.to_biguint())
+                            .ok_or(malformed)?,
+                    )
+                }
+                "r" => {
+                    r = Some(
+                        parse_int(value)
+                            .and_then(|v| v.to_biguint())
+                            .ok_or(malformed)?,
+                    )
+                }
+                "x" => x = Some(parse_int(value).ok_or(malformed)?),
+                "g1" => {
+                    g1 = Some(
+                        parse_uints(value)
+                            .filter(|v| v.len() == 2)
+                            .ok_or(malformed)?,
+                    )
+                }
+                "g2" => {
+                    g2 = Some(
+                        parse_uints(value)
+                            .filter(|v| v.len() == 4)
+                            .ok_or(malformed)?,
+                    )
+                }
+                "encoding" => {
+                    encoding = Some(match value {
+                        "eip197" => Encoding::Eip197,
+                        "eip2537" => Encoding::Eip2537,
+                        _ => return Err(malformed),
+                    })
+                }
+                "gt" => {
+                    let (scalars, coefficients) = value.split_once("->").ok_or(malformed)?;
+                    let scalars = parse_uints(scalars)
+                        .filter(|v| v.len() == 2)
+                        .ok_or(malformed)?;
+                    let coefficients = parse_uints(coefficients)
+                        .filter(|v| v.len() == 12)
+                        .ok_or(malformed)?;
+                    gt = Some((scalars[0].clone(), scalars[1].clone(), coefficients));
+                }
+                "pairing_check" => {
+                    let (input, expected) = value.split_once("->").ok_or(malformed)?;
+                    let input = parse_hex(input.trim()).ok_or(malformed)?;
+                    let expected = expected.trim().parse().map_err(|_| malformed)?;
+                    pairing_checks.push((input, expected));
+                }
+                _ => return Err("unknown test vector key"),
+            }
+        }
+
+        let missing = "test vector file is missing p, r, x, g1 or g2";
+        if !pairing_checks.is_empty() && encoding.is_none() {
+            return Err("pairing checks need an encoding");
+        }
+        Ok(TestVectors {
+            p: p.ok_or(missing)?,
+            r: r.ok_or(missing)?,
+            x: x.ok_or(missing)?,
+            g1: g1.ok_or(missing)?,
+            g2: g2.ok_or(missing)?,
+            encoding,
+            pairing_checks,
+            gt,
+        })
+    }
+
+    /// Runs every vector against the curve, returning how many checks passed
+    /// or the first failure.
+    pub fn check(&self, curve: &PairingCurve) -> Result<usize, &'static str> {
+        if &self.p != curve.p() || self.r != curve.r || self.x != BigInt::from(curve.x) {
+            return Err("curve parameters differ from the test vectors");
+        }
+        let tower = &curve.tower;
+        let g1 = Point::Affine(
+            Fp::new(self.g1[0].clone(), tower),
+            Fp::new(self.g1[1].clone(), tower),
+        );
+        let g2 = Point::Affine(
+            Fp2::from_biguints(self.g2[0].clone(), self.g2[1].clone(), tower),
+            Fp2::from_biguints(self.g2[2].clone(), self.g2[3].clone(), tower),
+        );
+        if g1 != curve.g1 || g2 != curve.g2 {
+            return Err("generators differ from the test vectors");
+        }
+        let mut passed = 2;
+
+        for (input, expected) in &self.pairing_checks {
+            let pairs = self.decode_pairs(curve, input)?;
+            if curve.pairing_check(&pairs) != *expected {
+                return Err("a pairing product check failed");
+            }
+            passed += 1;
+        }
+
+        if let Some((a, b, gt)) = &self.gt {
+            let p = curve.g1_curve.multiply(a, &g1);
+            let q = curve.g2_curve.multiply(b, &g2);
+            if curve.pairing(&p, &q).coefficients() != *gt {
+                return Err("e([a] G1, [b] G2) differs from the stored value");
+            }
+            passed += 1;
+        }
+        Ok(passed)
+    }
+
+    /// The (G1, G2) pairs of a precompile input, each point checked to lie on
+    /// its curve; all-zero coordinates stand for the point at infinity.
+    fn decode_pairs(&self, curve: &PairingCurve, input: &[u8]) -> Result<Vec<Pair>, &'static str> {
+        let encoding = self.encoding.ok_or("pairing checks need an encoding")?;
+        let size = encoding.coordinate_len();
+        if !input.len().is_multiple_of(6 * size) {
+            return Err("pairing check input is not a whole number of pairs");
+        }
+        let tower = &curve.tower;
+        input
+            .chunks(6 * size)
+            .map(|pair| {
+                let c: Vec<BigUint> = pair.chunks(size).map(BigUint::from_bytes_be).collect();
+                if c.iter().any(|c| c >= curve.p()) {
+                    return Err("pairing check coordinate is not reduced modulo p");
+                }
+                let p = if c[0].is_zero() && c[1].is_zero() {
+                    Point::Infinity
+                } else {
+                    Point::Affine(Fp::new(c[0].clone(), tower), Fp::new(c[1].clone(), tower))
+                };
+                let (x0, x1, y0, y1) = match encoding {
+                    Encoding::Eip197 => (&c[3], &c[2], &c[5], &c[4]),
+                    Encoding::Eip2537 => (&c[2], &c[3], &c[4], &c[5]),
+                };
+                let q = if c[2..].iter().all(Zero::is_zero) {
+                    Point::Infinity
+                } else {
+                    Point::Affine(
+                        Fp2::from_biguints(x0.clone(), x1.clone(), tower),
+                        Fp2::from_biguints(y0.clone(), y1.clone(), tower),
+                    )
+                };
+                if !curve.g1_curve.contains(&p) || !curve.g2_curve.contains(&q) {
+                    return Err("pairing check point is not on the curve");
+                }
+                Ok((p, q))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(name: &str) -> TestVectors {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("test_vectors")
+            .join(name);
+        TestVectors::load(&path).unwrap()
+    }
+
+    #[test]
+    fn bn254_matches_published_vectors() {
+        let vectors = load("bn254.txt");
+        assert_eq!(vectors.encoding, Some(Encoding::Eip197));
+        assert_eq!(vectors.pairing_checks.len(), 14);
+        assert!(vectors.gt.is_some());
+        assert_eq!(vectors.check(&PairingCurve::bn254()), Ok(17));
+    }
+
+    #[test]
+    fn bls12_381_matches_published_vectors() {
+        let vectors = load("bls12_381.txt");
+        assert_eq!(vectors.encoding, Some(Encoding::Eip2537));
+        assert_eq!(vectors.pairing_checks.len(), 6);
+        assert!(vectors.gt.is_some());
+        assert_eq!(vectors.check(&PairingCurve::bls12_381()), Ok(9));
+    }
+
+    #[test]
+    fn a_different_gt_is_rejected() {
+        let mut vectors = load("bn254.txt");
+        vectors.pairing_checks.clear();
+        vectors.gt.as_mut().unwrap().2[11] += 1u32;
+        assert!(vectors.check(&PairingCurve::bn254()).is_err());
+    }
+
+    #[test]
+    fn a_flipped_pairing_check_is_rejected() {
+        let mut vectors = load("bn254.txt");
+        vectors.pairing_checks.truncate(1);
+        vectors.gt = None;
+        vectors.pairing_checks[0].1 = !vectors.pairing_checks[0].1;
+        assert_eq!(
+            vectors.check(&PairingCurve::bn254()),
+            Err("a pairing product check failed")
+        );
+    }
+
+    #[test]
+    fn points_off_the_curve_are_rejected() {
+        let mut vectors = load("bn254.txt");
+        vectors.gt = None;
+        vectors.pairing_checks = vec![(vec![0x11; 192], true)];
+        assert_eq!(
+            vectors.check(&PairingCurve::bn254()),
+            Err("pairing check point is not on the curve")
+        );
+    }
+}
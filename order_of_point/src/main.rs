@@ -1,3 +1,13 @@
+mod pohlig_hellman;
+mod singular;
+mod smart_attack;
+
+use integer_factorization::Factorization;
+use num_bigint::BigUint;
+use num_traits::ToPrimitive;
+use pohlig_hellman::SubgroupSolver;
+use singular::{singular_cubic_with_root, Singularity};
+use smart_attack::anomalous_curves;
 use std::fmt;
 
 /// Represents a point on an elliptic curve
@@ -15,10 +25,7 @@ impl Point {
 
     /// Returns the point at infinity
     fn infinity() -> Self {
-        Point {
-            x: -1,
-            y: -1,
-        }
+        Point { x: -1, y: -1 }
     }
 
     /// Checks if this point is the point at infinity
@@ -50,7 +57,18 @@ impl EllipticCurve {
         if p <= 0 {
             return Err("Modulus must be positive");
         }
-        Ok(EllipticCurve { a, b, p })
+        let curve = EllipticCurve { a, b, p };
+        if curve.discriminant() == 0 {
+            return Err("Curve is singular, use SingularCubic");
+        }
+        Ok(curve)
+    }
+
+    /// 4a³ + 27b² mod p, zero exactly when the cubic has a repeated root
+    fn discriminant(&self) -> i64 {
+        let a = self.a.rem_euclid(self.p);
+        let b = self.b.rem_euclid(self.p);
+        (4 * (a * a % self.p) % self.p * a + 27 * (b * b % self.p)) % self.p
     }
 
     /// Verifies if a point lies on the curve
@@ -64,11 +82,11 @@ impl EllipticCurve {
         let ax = (self.a * point.x) % self.p;
         let right = (x3 + ax + self.b) % self.p;
         let left = (point.y * point.y) % self.p;
-        
+
         // Ensure positive modulo
         let left = (left + self.p) % self.p;
         let right = (right + self.p) % self.p;
-        
+
         left == right
     }
 
@@ -96,10 +114,10 @@ impl EllipticCurve {
             if y1 == 0 {
                 return Ok(Point::infinity());
             }
-            
+
             let numerator = (3 * x1 * x1 + self.a) % self.p;
             let denominator = (2 * y1) % self.p;
-            
+
             match mod_inverse(denominator, self.p) {
                 Some(inv) => (numerator * inv) % self.p,
                 None => return Err("Could not compute modular inverse"),
@@ -108,7 +126,7 @@ impl EllipticCurve {
             // Point addition
             let numerator = (y2 - y1 + self.p) % self.p;
             let denominator = (x2 - x1 + self.p) % self.p;
-            
+
             match mod_inverse(denominator, self.p) {
                 Some(inv) => (numerator * inv) % self.p,
                 None => return Err("Could not compute modular inverse"),
@@ -139,9 +157,11 @@ impl EllipticCurve {
 
         while !current.is_infinity() {
             let next = self.add(&current, point)?;
-            println!("Step {}: Adding {} to {} gives {}", 
-                    order, point, current, next);
-            
+            println!(
+                "Step {}: Adding {} to {} gives {}",
+                order, point, current, next
+            );
+
             current = next;
             order += 1;
 
@@ -152,6 +172,69 @@ impl EllipticCurve {
 
         Ok(order)
     }
+
+    /// Computes k * point by double-and-add
+    fn multiply(&self, k: i64, point: &Point) -> Result<Point, &'static str> {
+        let mut result = Point::infinity();
+        let mut addend = *point;
+        let mut k = k;
+
+        while k > 0 {
+            if k & 1 == 1 {
+                result = self.add(&result, &addend)?;
+            }
+            addend = self.add(&addend, &addend)?;
+            k >>= 1;
+        }
+        Ok(result)
+    }
+
+    /// Counts the points on the curve, including the point at infinity,
+    /// using Euler's criterion on x³ + ax + b for every x
+    fn count_points(&self) -> i64 {
+        let mut count = 1;
+        for x in 0..self.p {
+            let rhs = ((x * x % self.p * x + self.a * x + self.b) % self.p + self.p) % self.p;
+            if rhs == 0 {
+                count += 1;
+            } else if mod_pow(rhs, (self.p - 1) / 2, self.p) == 1 {
+                count += 2;
+            }
+        }
+        count
+    }
+
+    /// Finds the order of a point from the factored group order N: start
+    /// from N and divide out each prime q while [N/q]P is still the identity
+    fn find_order_from_group_order(
+        &self,
+        point: &Point,
+        group_order: &Factorization,
+    ) -> Result<i64, &'static str> {
+        if !self.contains(point) {
+            return Err("Point is not on the curve");
+        }
+
+        let mut order = group_order
+            .value()
+            .to_i64()
+            .ok_or("Group order does not fit in an i64")?;
+        if !self.multiply(order, point)?.is_infinity() {
+            return Err("Group order does not annihilate the point");
+        }
+
+        for (q, exponent) in group_order.factors() {
+            let q = q.to_i64().ok_or("Prime factor does not fit in an i64")?;
+            for _ in 0..*exponent {
+                if !self.multiply(order / q, point)?.is_infinity() {
+                    break;
+                }
+                order /= q;
+            }
+        }
+
+        Ok(order)
+    }
 }
 
 /// Computes the modular multiplicative inverse
@@ -176,25 +259,198 @@ fn mod_inverse(a: i64, m: i64) -> Option<i64> {
     Some(t)
 }
 
+/// Computes base^exponent mod m by square-and-multiply
+fn mod_pow(base: i64, exponent: i64, m: i64) -> i64 {
+    let mut result = 1;
+    let mut base = (base % m + m) % m;
+    let mut exponent = exponent;
+
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exponent >>= 1;
+    }
+
+    result
+}
+
+/// Square root of n mod an odd prime p, `None` for non-residues.
+fn sqrt_mod(n: i64, p: i64) -> Option<i64> {
+    let n = BigUint::from(n.rem_euclid(p) as u64);
+    cm_curves::sqrt_mod(&n, &BigUint::from(p as u64))?.to_i64()
+}
+
+/// The curves used across the examples of this project, (a, b, p).
+const CATALOGUE: [(i64, i64, i64); 12] = [
+    (8, 2, 17),
+    (4, 4, 7),
+    (8, 8, 13),
+    (1, 1, 5),
+    (3, 5, 19),
+    (2, 7, 11),
+    (1, 6, 17),
+    (1, 0, 1_000_003),
+    (2, 5, 1_000_003),
+    (2, 4, 1_000_003),
+    (2, 3, 100_003),
+    (1, 0, 1_000_171),
+];
+
 fn main() -> Result<(), &'static str> {
     let curve = EllipticCurve::new(8, 2, 17)?;
-    let point = Point::new(0, 5);
+    // 6² = 36 ≡ 2 = 0³ + 8·0 + 2 (mod 17), so (0, 6) is on the curve
+    let point = Point::new(0, 6);
 
     // Verify the point is on the curve
     if !curve.contains(&point) {
         println!("Point verification failed!");
         println!("Left side (y²): {}", (point.y * point.y) % 7);
-        println!("Right side (x³ + ax + b): {}", (point.x.pow(3) + 4 * point.x + 4) % 7);
+        println!(
+            "Right side (x³ + ax + b): {}",
+            (point.x.pow(3) + 4 * point.x + 4) % 7
+        );
         return Err("Initial point is not on the curve");
     }
 
-    println!("Point {} is on the curve y² = x³ + {}x + {} mod {}", 
-             point, curve.a, curve.b, curve.p);
+    println!(
+        "Point {} is on the curve y² = x³ + {}x + {} mod {}",
+        point, curve.a, curve.b, curve.p
+    );
 
     match curve.find_order(&point) {
         Ok(order) => println!("Order of {} is: {}", point, order),
         Err(e) => println!("Error computing order: {}", e),
     }
 
+    let group_order = Factorization::of_u64(curve.count_points() as u64)?;
+    println!("\n#E = {} = {}", group_order.value(), group_order);
+    match curve.find_order_from_group_order(&point, &group_order) {
+        Ok(order) => println!("Order of {} from the group order: {}", point, order),
+        Err(e) => println!("Error computing order: {}", e),
+    }
+
+    // Pohlig–Hellman on groups whose orders are products of small primes;
+    // both fields have p ≡ 3 (mod 4), so square roots are powers
+    for (a, b, p) in [(1, 0, 1_000_003), (2, 5, 1_000_003)] {
+        let curve = EllipticCurve::new(a, b, p)?;
+        let base = (1..p)
+            .map(|x| {
+                let rhs = ((x * x % p * x + a * x + b) % p + p) % p;
+                Point::new(x, mod_pow(rhs, (p + 1) / 4, p))
+            })
+            .find(|point| curve.contains(point))
+            .ok_or("No point found")?;
+        let target = curve.multiply(123_457, &base)?;
+        println!(
+            "\ny² = x³ + {}x + {} mod {}, Q = [123457]{} = {}",
+            a, b, p, base, target
+        );
+        for solver in [
+            SubgroupSolver::BabyStepGiantStep,
+            SubgroupSolver::PollardRho,
+        ] {
+            match curve.pohlig_hellman(&base, &target, solver) {
+                Ok(log) => println!(
+                    "{:?}: k = {} mod ord(P) = {} = {}",
+                    solver, log.k, log.order, log.factorization
+                ),
+                Err(e) => println!("Error: {}", e),
+            }
+        }
+    }
+
+    println!("\nPohlig–Hellman exposure of the catalogue:");
+    for (a, b, p) in CATALOGUE {
+        let curve = EllipticCurve::new(a, b, p)?;
+        let report = curve.smoothness_report()?;
+        println!(
+            "y² = x³ + {}x + {} mod {}: #E = {}, q = {}, rho costs 2^{:.1} instead of 2^{:.1}{}",
+            a,
+            b,
+            p,
+            report.group_order,
+            report.largest_prime,
+            report.actual_bits,
+            report.ideal_bits,
+            if report.is_dangerous() {
+                ", DANGEROUSLY SMOOTH"
+            } else {
+                ""
+            }
+        );
+        if curve.is_anomalous() {
+            println!("  ANOMALOUS: #E = p, broken by Smart's attack");
+        }
+    }
+
+    // An anomalous curve from CM by a class number one discriminant, where
+    // the discrete logarithm costs one lift to Z/p² and a division
+    println!("\nSmart's attack on a generated anomalous curve:");
+    let curve = anomalous_curves(100_000)
+        .into_iter()
+        .next()
+        .ok_or("No anomalous curve found")?;
+    let base = curve.first_point().ok_or("No point found")?;
+    let k = 31_337 % curve.p;
+    let target = curve.multiply(k, &base)?;
+    println!(
+        "y² = x³ + {}x + {} mod {}, #E = {}, Q = [{}]{}: k = {}",
+        curve.a,
+        curve.b,
+        curve.p,
+        curve.count_points(),
+        k,
+        base,
+        curve.smart_attack(&base, &target)?
+    );
+    let curve = EllipticCurve::new(2, 5, 1_000_003)?;
+    let base = curve.first_point().ok_or("No point found")?;
+    if let Err(e) = curve.smart_attack(&base, &base) {
+        println!("y² = x³ + 2x + 5 mod 1000003: {}", e);
+    }
+
+    // Singular cubics: the nonsingular points form F_p^*, the norm-one
+    // subgroup of F_{p²}^* or F_p^+, where the logarithm is easy
+    let p = 1_000_003;
+    if let Err(e) = EllipticCurve::new(p - 3, 2, p) {
+        println!("\ny² = x³ - 3x + 2 mod {}: {}", p, e);
+    }
+    let mut nodes = (1..p).filter_map(|alpha| singular_cubic_with_root(alpha, p).ok());
+    let split = nodes
+        .find(|cubic| matches!(cubic.singularity, Singularity::SplitNode { .. }))
+        .ok_or("No split node found")?;
+    let non_split = nodes
+        .find(|cubic| cubic.singularity == Singularity::NonSplitNode)
+        .ok_or("No non-split node found")?;
+    let cusp = singular_cubic_with_root(0, p)?;
+    for cubic in [split, non_split, cusp] {
+        let base = cubic.first_point().ok_or("No point found")?;
+        let target = cubic.multiply(123_457, &base)?;
+        let image = cubic.map(&base)?;
+        println!(
+            "\ny² = x³ + {}x + {} mod {}: {:?} at {}, {} nonsingular points",
+            cubic.curve.a,
+            cubic.curve.b,
+            p,
+            cubic.singularity,
+            cubic.singular_point,
+            cubic.group_order()
+        );
+        println!(
+            "P = {} ↦ {:?} ↦ {}, Q = [123457]P ↦ {:?}",
+            base,
+            image,
+            cubic.point(&image)?,
+            cubic.map(&target)?
+        );
+        println!("P + Q ↦ {:?}", cubic.map(&cubic.add(&base, &target)?)?);
+        match cubic.discrete_log(&base, &target) {
+            Ok(k) => println!("k = {}", k),
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
     Ok(())
-}
\ No newline at end of file
+}
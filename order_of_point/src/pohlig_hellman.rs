@@ -0,0 +1,264 @@
+use integer_factorization::Factorization;
+use num_traits::ToPrimitive;
+use std::collections::HashMap;
+
+use crate::{mod_inverse, EllipticCurve, Point};
+
+/// Prime factors below this are always solved by baby-step giant-step,
+/// since rho needs a few dozen steps before its walk becomes random.
+const RHO_THRESHOLD: i64 = 1000;
+
+/// Starting points tried by rho before giving up on a prime factor.
+const RHO_ATTEMPTS: i64 = 20;
+
+/// Which algorithm solves the discrete logarithm in each prime subgroup.
+#[derive(Debug, Clone, Copy)]
+pub enum SubgroupSolver {
+    BabyStepGiantStep,
+    PollardRho,
+}
+
+/// The logarithm k of Q = [k]P together with ord(P) and its factorization.
+#[derive(Debug)]
+pub struct DiscreteLog {
+    pub k: i64,
+    pub order: i64,
+    pub factorization: Factorization,
+}
+
+impl EllipticCurve {
+    fn negate(&self, point: &Point) -> Point {
+        if point.is_infinity() {
+            return *point;
+        }
+        Point::new(point.x, (self.p - point.y) % self.p)
+    }
+
+    fn subtract(&self, p1: &Point, p2: &Point) -> Result<Point, &'static str> {
+        self.add(p1, &self.negate(p2))
+    }
+
+    /// x with [x]G = H and 0 ≤ x < n, storing the baby steps [j]G for
+    /// j < m = ⌈√n⌉ and taking giant steps H - [im]G.
+    fn baby_step_giant_step(
+        &self,
+        g: &Point,
+        h: &Point,
+        n: i64,
+    ) -> Result<Option<i64>, &'static str> {
+        let m = (n as f64).sqrt().ceil() as i64;
+        let mut baby_steps = HashMap::new();
+        let mut current = Point::infinity();
+        for j in 0..m {
+            baby_steps.entry((current.x, current.y)).or_insert(j);
+            current = self.add(&current, g)?;
+        }
+        let giant = self.negate(&self.multiply(m, g)?);
+        let mut current = *h;
+        for i in 0..m {
+            if let Some(j) = baby_steps.get(&(current.x, current.y)) {
+                return Ok(Some((i * m + j) % n));
+            }
+            current = self.add(&current, &giant)?;
+        }
+        Ok(None)
+    }
+
+    /// x with [x]G = H for G of prime order n, by Pollard's rho with the
+    /// classic three-way partition on x mod 3 and Floyd's cycle finding.
+    /// Each point is kept as [a]G + [b]H.
+    fn pollard_rho(&self, g: &Point, h: &Point, n: i64) -> Result<Option<i64>, &'static str> {
+        let step = |(x, a, b): (Point, i64, i64)| -> Result<(Point, i64, i64), &'static str> {
+            Ok(match x.x.rem_euclid(3) {
+                0 => (self.add(&x, h)?, a, (b + 1) % n),
+                1 => (self.add(&x, &x)?, 2 * a % n, 2 * b % n),
+                _ => (self.add(&x, g)?, (a + 1) % n, b),
+            })
+        };
+        for start in 1..=RHO_ATTEMPTS {
+            let x0 = self.add(&self.multiply(start, g)?, h)?;
+            let mut tortoise = step((x0, start % n, 1))?;
+            let mut hare = step(tortoise)?;
+            while tortoise.0 != hare.0 {
+                tortoise = step(tortoise)?;
+                hare = step(step(hare)?)?;
+            }
+            // a1 + b1 x = a2 + b2 x, solvable when b1 ≠ b2
+            let denominator = (hare.2 - tortoise.2).rem_euclid(n);
+            if let Some(inverse) = mod_inverse(denominator, n) {
+                let numerator = (tortoise.1 - hare.1).rem_euclid(n);
+                return Ok(Some(numerator * inverse % n));
+            }
+        }
+        Ok(None)
+    }
+
+    fn subgroup_log(
+        &self,
+        g: &Point,
+        h: &Point,
+        q: i64,
+        solver: SubgroupSolver,
+    ) -> Result<i64, &'static str> {
+        let x = match solver {
+            SubgroupSolver::PollardRho if q >= RHO_THRESHOLD => self.pollard_rho(g, h, q)?,
+            _ => self.baby_step_giant_step(g, h, q)?,
+        };
+        x.ok_or("Target is not in the subgroup generated by the base point")
+    }
+
+    /// Solves Q = [k]P by Pohlig–Hellman: with ord(P) = Π q^e, the
+    /// logarithm mod each q^e is read off one base-q digit at a time from
+    /// discrete logarithms in the subgroup of order q, and the residues are
+    /// recombined with the CRT. The cost is governed by the largest q.
+    pub fn pohlig_hellman(
+        &self,
+        p: &Point,
+        q: &Point,
+        solver: SubgroupSolver,
+    ) -> Result<DiscreteLog, &'static str> {
+        if !self.contains(p) || !self.contains(q) {
+            return Err("Point is not on the curve");
+        }
+        let group_order = Factorization::of_u64(self.count_points() as u64)?;
+        let order = self.find_order_from_group_order(p, &group_order)?;
+        let factorization = Factorization::of_u64(order as u64)?;
+
+        let (mut k, mut modulus) = (0i64, 1i64);
+        for (prime, exponent) in factorization.factors() {
+            let prime = prime
+                .to_i64()
+                .ok_or("Prime factor does not fit in an i64")?;
+            let prime_power = prime.pow(*exponent);
+            let cofactor = order / prime_power;
+            let (p_i, q_i) = (self.multiply(cofactor, p)?, self.multiply(cofactor, q)?);
+
+            // gamma has order q; each digit d_j solves
+            //   [q^(e-1-j)](Q_i - [x]P_i) = [d_j]gamma
+            let gamma = self.multiply(prime_power / prime, &p_i)?;
+            let mut x = 0i64;
+            let mut digit_weight = 1i64;
+            for _ in 0..*exponent {
+                let remaining = self.subtract(&q_i, &self.multiply(x, &p_i)?)?;
+                let target = self.multiply(prime_power / prime / digit_weight, &remaining)?;
+                x += self.subgroup_log(&gamma, &target, prime, solver)? * digit_weight;
+                digit_weight *= prime;
+            }
+            k = crt(k, modulus, x, prime_power);
+            modulus *= prime_power;
+        }
+
+        if self.multiply(k, p)? != *q {
+            return Err("Target is not in the subgroup generated by the base point");
+        }
+        Ok(DiscreteLog {
+            k,
+            order,
+            factorization,
+        })
+    }
+}
+
+/// The x mod m1 m2 with x ≡ a1 (mod m1) and x ≡ a2 (mod m2), m1 and m2 coprime.
+fn crt(a1: i64, m1: i64, a2: i64, m2: i64) -> i64 {
+    let inverse = mod_inverse(m1 % m2, m2).unwrap_or(0);
+    let difference = (a2 - a1).rem_euclid(m2);
+    a1 + m1 * (difference as i128 * inverse as i128 % m2 as i128) as i64
+}
+
+/// How badly the group order of a curve exposes it to Pohlig–Hellman.
+#[derive(Debug)]
+pub struct SmoothnessReport {
+    pub group_order: Factorization,
+    pub largest_prime: i64,
+    /// log2 √n, what rho would cost in a group of prime order n.
+    pub ideal_bits: f64,
+    /// log2 √q for the largest prime q, what Pohlig–Hellman actually costs.
+    pub actual_bits: f64,
+}
+
+impl SmoothnessReport {
+    /// Dangerously smooth: the largest prime factor is below √#E, so the
+    /// attack needs at most the fourth root of the group order.
+    pub fn is_dangerous(&self) -> bool {
+        2.0 * self.actual_bits < self.ideal_bits
+    }
+}
+
+impl EllipticCurve {
+    pub fn smoothness_report(&self) -> Result<SmoothnessReport, &'static str> {
+        let group_order = Factorization::of_u64(self.count_points() as u64)?;
+        let largest_prime = group_order
+            .largest_prime()
+            .and_then(|q| q.to_i64())
+            .unwrap_or(1);
+        let n = group_order.value().to_f64().unwrap_or(f64::INFINITY);
+        Ok(SmoothnessReport {
+            largest_prime,
+            ideal_bits: n.log2() / 2.0,
+            actual_bits: (largest_prime as f64).log2() / 2.0,
+            group_order,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crt_combines_coprime_residues() {
+        assert_eq!(crt(2, 3, 3, 5), 8);
+        assert_eq!(crt(0, 1, 4, 7), 4);
+        assert_eq!(crt(8, 15, 5, 7), 68);
+        let (mut x, mut m) = (0, 1);
+        for q in [4, 9, 25, 7, 11] {
+            x = crt(x, m, 123_457 % q, q);
+            m *= q;
+        }
+        assert_eq!(x, 123_457 % m);
+    }
+
+    #[test]
+    fn pohlig_hellman_recovers_a_known_logarithm() {
+        // #E = 2² 3⁶ 7³ and 2¹⁴ 61, so every prime power needs several digits
+        for (a, b, p) in [(2, 23, 1_000_003), (2, 7, 1_000_003)] {
+            let curve = EllipticCurve::new(a, b, p).unwrap();
+            let base = curve.first_point().unwrap();
+            for k in [1, 2, 123_457, 999_999] {
+                let target = curve.multiply(k, &base).unwrap();
+                for solver in [
+                    SubgroupSolver::BabyStepGiantStep,
+                    SubgroupSolver::PollardRho,
+                ] {
+                    let log = curve.pohlig_hellman(&base, &target, solver).unwrap();
+                    assert_eq!(log.k, k % log.order, "b = {}, k = {}, {:?}", b, k, solver);
+                    assert_eq!(curve.multiply(log.k, &base).unwrap(), target);
+                    assert_eq!(log.factorization.value().to_i64(), Some(log.order));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pohlig_hellman_rejects_a_target_outside_the_subgroup() {
+        // [2]P has odd index in a group of order 2¹⁴ 61, so P is not in <[2]P>
+        let curve = EllipticCurve::new(2, 7, 1_000_003).unwrap();
+        let p = curve.first_point().unwrap();
+        let base = curve.multiply(2, &p).unwrap();
+        assert_eq!(
+            curve
+                .pohlig_hellman(&base, &p, SubgroupSolver::BabyStepGiantStep)
+                .err(),
+            Some("Target is not in the subgroup generated by the base point")
+        );
+    }
+
+    #[test]
+    fn smoothness_report_flags_smooth_orders() {
+        let smooth = EllipticCurve::new(2, 23, 1_000_003).unwrap();
+        let report = smooth.smoothness_report().unwrap();
+        assert_eq!(report.largest_prime, 7);
+        assert!(report.is_dangerous());
+    }
+}
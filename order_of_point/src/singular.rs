@@ -0,0 +1,429 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::{mod_inverse, sqrt_mod, EllipticCurve, Point};
+
+/// The shape of the singular point of y² = x³ + ax + b when 4a³ + 27b² ≡ 0.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Singularity {
+    /// A node whose tangents y = ±s(x - α) are defined over F_p.
+    SplitNode { s: i64 },
+    /// A node whose tangents are conjugate over F_{p²}.
+    NonSplitNode,
+    /// A cusp, the curve being y² = x³.
+    Cusp,
+}
+
+/// Where a nonsingular point lands: F_p^* for a split node, the norm-one
+/// elements c + d√δ of F_{p²}^* for a non-split node and F_p^+ for a cusp.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Image {
+    Multiplicative(i64),
+    Quadratic(i64, i64),
+    Additive(i64),
+}
+
+/// A singular cubic y² = x³ + ax + b = (x - α)²(x - α + δ) over F_p, whose
+/// nonsingular points still form a group under the chord-and-tangent law.
+pub struct SingularCubic {
+    pub curve: EllipticCurve,
+    pub singular_point: Point,
+    pub singularity: Singularity,
+    /// The δ = 3α with y² = u²(u + δ) in u = x - α.
+    delta: i64,
+}
+
+impl SingularCubic {
+    /// Recognizes a singular cubic: the double root is α = -3b / 2a, or 0
+    /// when a = b = 0, and the node splits when δ = 3α is a square mod p.
+    pub fn new(a: i64, b: i64, p: i64) -> Result<Self, &'static str> {
+        if p <= 3 {
+            return Err("Modulus must be a prime larger than 3");
+        }
+        let curve = EllipticCurve {
+            a: a.rem_euclid(p),
+            b: b.rem_euclid(p),
+            p,
+        };
+        if curve.discriminant() != 0 {
+            return Err("Curve is not singular");
+        }
+        let alpha = match mod_inverse(2 * curve.a % p, p) {
+            Some(inverse) => (p - 3 * curve.b % p) % p * inverse % p,
+            None => 0,
+        };
+        let delta = 3 * alpha % p;
+        let singularity = if delta == 0 {
+            Singularity::Cusp
+        } else {
+            match sqrt_mod(delta, p) {
+                Some(s) => Singularity::SplitNode { s },
+                None => Singularity::NonSplitNode,
+            }
+        };
+        Ok(SingularCubic {
+            curve,
+            singular_point: Point::new(alpha, 0),
+            singularity,
+            delta,
+        })
+    }
+
+    /// The number of nonsingular points including infinity: p - 1, p + 1 or p.
+    pub fn group_order(&self) -> i64 {
+        match self.singularity {
+            Singularity::SplitNode { .. } => self.curve.p - 1,
+            Singularity::NonSplitNode => self.curve.p + 1,
+            Singularity::Cusp => self.curve.p,
+        }
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.curve.contains(point) && *point != self.singular_point
+    }
+
+    /// The group law, which the singular point would break.
+    pub fn add(&self, p1: &Point, p2: &Point) -> Result<Point, &'static str> {
+        if !self.contains(p1) || !self.contains(p2) {
+            return Err("Point is singular or not on the curve");
+        }
+        self.curve.add(p1, p2)
+    }
+
+    pub fn multiply(&self, k: i64, point: &Point) -> Result<Point, &'static str> {
+        if !self.contains(point) {
+            return Err("Point is singular or not on the curve");
+        }
+        self.curve.multiply(k, point)
+    }
+
+    /// The isomorphism onto the multiplicative or additive group: with
+    /// u = x - α it sends a point to (y + su) / (y - su), with s = √δ taken
+    /// in F_{p²} for a non-split node, and to x / y for a cusp.
+    pub fn map(&self, point: &Point) -> Result<Image, &'static str> {
+        if !self.contains(point) {
+            return Err("Point is singular or not on the curve");
+        }
+        let p = self.curve.p;
+        if point.is_infinity() {
+            return Ok(self.identity());
+        }
+        let u = (point.x - self.singular_point.x).rem_euclid(p);
+        let y = point.y.rem_euclid(p);
+        let inverse = |value: i64| mod_inverse(value, p).ok_or("Could not compute modular inverse");
+        Ok(match self.singularity {
+            Singularity::SplitNode { s } => {
+                let su = s * u % p;
+                Image::Multiplicative((y + su) % p * inverse((y - su).rem_euclid(p))? % p)
+            }
+            // (y + u√δ)² / (y² - δu²), where y² - δu² = u³
+            Singularity::NonSplitNode => {
+                let u3 = inverse(u * u % p * u % p)?;
+                let c = (y * y + self.delta * (u * u % p)) % p * u3 % p;
+                let d = 2 * y % p * u % p * u3 % p;
+                Image::Quadratic(c, d)
+            }
+            Singularity::Cusp => Image::Additive(u * inverse(y)? % p),
+        })
+    }
+
+    /// The inverse isomorphism: from w = (y + su) / (y - su) follow
+    /// u = 4δw / (w - 1)² and y = su (w + 1) / (w - 1), and from t = x / y
+    /// follow x = 1 / t² and y = 1 / t³.
+    pub fn point(&self, image: &Image) -> Result<Point, &'static str> {
+        let p = self.curve.p;
+        if *image == self.identity() {
+            return Ok(Point::infinity());
+        }
+        let (w, s) = match (self.singularity, *image) {
+            (Singularity::SplitNode { s }, Image::Multiplicative(w)) => {
+                ((w.rem_euclid(p), 0), (s, 0))
+            }
+            (Singularity::NonSplitNode, Image::Quadratic(c, d)) => {
+                ((c.rem_euclid(p), d.rem_euclid(p)), (0, 1))
+            }
+            (Singularity::Cusp, Image::Additive(t)) => {
+                let inverse = mod_inverse(t.rem_euclid(p), p).ok_or("Could not invert t")?;
+                let x = inverse * inverse % p;
+                return Ok(Point::new(x, x * inverse % p));
+            }
+            _ => return Err("Image does not belong to the group of this curve"),
+        };
+        let field = QuadraticField {
+            p,
+            delta: self.delta,
+        };
+        if self.singularity == Singularity::NonSplitNode && field.norm(w) != 1 {
+            return Err("Element is not of norm one");
+        }
+        let w_minus_1 = field.inverse(((w.0 - 1).rem_euclid(p), w.1))?;
+        let u = field.mul(
+            field.mul((4 * self.delta % p, 0), w),
+            field.mul(w_minus_1, w_minus_1),
+        );
+        let y = field.mul(field.mul(s, u), field.mul(((w.0 + 1) % p, w.1), w_minus_1));
+        if u.1 != 0 || y.1 != 0 {
+            return Err("Element is not in the image of the curve");
+        }
+        Ok(Point::new((u.0 + self.singular_point.x) % p, y.0))
+    }
+
+    /// The nonsingular point with y ≠ 0 and the smallest x ≥ 1.
+    pub fn first_point(&self) -> Option<Point> {
+        let p = self.curve.p;
+        (1..p).find_map(|x| {
+            let rhs = (x * x % p * x + self.curve.a * x + self.curve.b) % p;
+            sqrt_mod(rhs, p)
+                .filter(|&y| y != 0)
+                .map(|y| Point::new(x, y))
+        })
+    }
+
+    fn identity(&self) -> Image {
+        match self.singularity {
+            Singularity::SplitNode { .. } => Image::Multiplicative(1),
+            Singularity::NonSplitNode => Image::Quadratic(1, 0),
+            Singularity::Cusp => Image::Additive(0),
+        }
+    }
+
+    /// Solves Q = [k]P by moving it into F_p^+, where it is a division,
+    /// or into F_p^* or F_{p²}^*, where baby-step giant-step takes √p steps
+    /// (and an index calculus would take far fewer).
+    pub fn discrete_log(&self, p: &Point, q: &Point) -> Result<i64, &'static str> {
+        let n = self.curve.p;
+        let k = match (self.map(p)?, self.map(q)?) {
+            (Image::Additive(g), Image::Additive(h)) => {
+                let inverse =
+                    mod_inverse(g, n).ok_or("Base point must not be the point at infinity")?;
+                Some(h * inverse % n)
+            }
+            (Image::Multiplicative(g), Image::Multiplicative(h)) => {
+                baby_step_giant_step(g, h, 1, n - 1, |x, y| x * y % n)
+            }
+            (Image::Quadratic(c, d), Image::Quadratic(e, f)) => {
+                let field = QuadraticField {
+                    p: n,
+                    delta: self.delta,
+                };
+                baby_step_giant_step((c, d), (e, f), (1, 0), n + 1, |x, y| field.mul(x, y))
+            }
+            _ => None,
+        };
+        let k = k.ok_or("Target is not in the subgroup generated by the base point")?;
+        if self.multiply(k, p)? != *q {
+            return Err("Target is not in the subgroup generated by the base point");
+        }
+        Ok(k)
+    }
+}
+
+/// F_{p²} = F_p(√δ) for a non-residue δ, elements c + d√δ as pairs (c, d).
+struct QuadraticField {
+    p: i64,
+    delta: i64,
+}
+
+impl QuadraticField {
+    fn mul(&self, x: (i64, i64), y: (i64, i64)) -> (i64, i64) {
+        let p = self.p;
+        let c = (x.0 * y.0 % p + x.1 * y.1 % p * self.delta) % p;
+        let d = (x.0 * y.1 + x.1 * y.0) % p;
+        (c, d)
+    }
+
+    /// c² - δd², the product of an element with its conjugate.
+    fn norm(&self, x: (i64, i64)) -> i64 {
+        let p = self.p;
+        (x.0 * x.0 % p - x.1 * x.1 % p * self.delta % p).rem_euclid(p)
+    }
+
+    fn inverse(&self, x: (i64, i64)) -> Result<(i64, i64), &'static str> {
+        let p = self.p;
+        let inverse = mod_inverse(self.norm(x), p).ok_or("Could not invert zero")?;
+        Ok((x.0 * inverse % p, (p - x.1) % p * inverse % p))
+    }
+}
+
+/// g^e by square-and-multiply for any multiplication.
+fn power<T: Copy>(g: T, e: i64, one: T, mul: &impl Fn(T, T) -> T) -> T {
+    let (mut result, mut base, mut e) = (one, g, e);
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mul(result, base);
+        }
+        base = mul(base, base);
+        e >>= 1;
+    }
+    result
+}
+
+/// x with g^x = h and 0 ≤ x < n in a group of order n.
+fn baby_step_giant_step<T: Copy + Eq + Hash>(
+    g: T,
+    h: T,
+    one: T,
+    n: i64,
+    mul: impl Fn(T, T) -> T,
+) -> Option<i64> {
+    let m = (n as f64).sqrt().ceil() as i64;
+    let mut baby_steps = HashMap::new();
+    let mut current = one;
+    for j in 0..m {
+        baby_steps.entry(current).or_insert(j);
+        current = mul(current, g);
+    }
+    let giant = power(g, n - m % n, one, &mul);
+    let mut current = h;
+    for i in 0..=m {
+        if let Some(j) = baby_steps.get(&current) {
+            return Some((i * m + j) % n);
+        }
+        current = mul(current, giant);
+    }
+    None
+}
+
+/// The singular cubic with double root α: y² = (x - α)²(x + 2α), that is
+/// a = -3α² and b = 2α³, a cusp for α = 0.
+pub fn singular_cubic_with_root(alpha: i64, p: i64) -> Result<SingularCubic, &'static str> {
+    let alpha = alpha.rem_euclid(p);
+    let a = (p - 3 * (alpha * alpha % p) % p) % p;
+    let b = 2 * (alpha * alpha % p) % p * alpha % p;
+    SingularCubic::new(a, b, p)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P: i64 = 1_009;
+
+    fn cubics() -> Vec<SingularCubic> {
+        let mut nodes = (1..P).filter_map(|alpha| singular_cubic_with_root(alpha, P).ok());
+        let split = nodes
+            .find(|cubic| matches!(cubic.singularity, Singularity::SplitNode { .. }))
+            .unwrap();
+        let non_split = nodes
+            .find(|cubic| cubic.singularity == Singularity::NonSplitNode)
+            .unwrap();
+        vec![split, non_split, singular_cubic_with_root(0, P).unwrap()]
+    }
+
+    /// Every nonsingular point, the point at infinity first.
+    fn points(cubic: &SingularCubic) -> Vec<Point> {
+        let mut points = vec![Point::infinity()];
+        for x in 0..P {
+            for y in 0..P {
+                let point = Point::new(x, y);
+                if cubic.contains(&point) {
+                    points.push(point);
+                }
+            }
+        }
+        points
+    }
+
+    /// The group operation on the images.
+    fn combine(cubic: &SingularCubic, x: Image, y: Image) -> Image {
+        let field = QuadraticField {
+            p: P,
+            delta: cubic.delta,
+        };
+        match (x, y) {
+            (Image::Multiplicative(g), Image::Multiplicative(h)) => {
+                Image::Multiplicative(g * h % P)
+            }
+            (Image::Quadratic(c, d), Image::Quadratic(e, f)) => {
+                let (c, d) = field.mul((c, d), (e, f));
+                Image::Quadratic(c, d)
+            }
+            (Image::Additive(g), Image::Additive(h)) => Image::Additive((g + h) % P),
+            _ => panic!("images from different groups"),
+        }
+    }
+
+    #[test]
+    fn group_orders_match_enumeration() {
+        for cubic in cubics() {
+            assert_eq!(
+                points(&cubic).len() as i64,
+                cubic.group_order(),
+                "{:?}",
+                cubic.singularity
+            );
+        }
+    }
+
+    #[test]
+    fn maps_are_homomorphisms() {
+        for cubic in cubics() {
+            let points = points(&cubic);
+            for (i, p1) in points.iter().enumerate().step_by(37) {
+                for p2 in points.iter().skip(i % 5).step_by(41) {
+                    let sum = cubic.add(p1, p2).unwrap();
+                    assert_eq!(
+                        cubic.map(&sum).unwrap(),
+                        combine(&cubic, cubic.map(p1).unwrap(), cubic.map(p2).unwrap()),
+                        "{:?}: {} + {}",
+                        cubic.singularity,
+                        p1,
+                        p2
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn inverse_map_undoes_the_map() {
+        for cubic in cubics() {
+            let points = points(&cubic);
+            for point in &points {
+                let image = cubic.map(point).unwrap();
+                assert_eq!(
+                    cubic.point(&image).unwrap(),
+                    *point,
+                    "{:?}",
+                    cubic.singularity
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_the_singular_point() {
+        for cubic in cubics() {
+            let singular = cubic.singular_point;
+            assert!(!cubic.contains(&singular));
+            assert_eq!(
+                cubic.map(&singular),
+                Err("Point is singular or not on the curve")
+            );
+        }
+        assert_eq!(
+            SingularCubic::new(2, 5, P).err(),
+            Some("Curve is not singular")
+        );
+    }
+
+    #[test]
+    fn discrete_log_recovers_a_known_k() {
+        for cubic in cubics() {
+            let base = cubic.first_point().unwrap();
+            let order = (1..=cubic.group_order())
+                .find(|&n| cubic.multiply(n, &base).unwrap().is_infinity())
+                .unwrap();
+            for k in [1, 2, 500, order - 1] {
+                let target = cubic.multiply(k, &base).unwrap();
+                assert_eq!(
+                    cubic.discrete_log(&base, &target),
+                    Ok(k % order),
+                    "{:?}, k = {}",
+                    cubic.singularity,
+                    k
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use integer_factorization::is_prime_u64;
+
+use crate::{mod_inverse, mod_pow, sqrt_mod, EllipticCurve, Point};
+
+/// Class number one discriminants -D with their j-invariants, the curves
+/// with trace one over p = (1 + Dy²) / 4 being anomalous.
+const CM_DISCRIMINANTS: [(i64, i128); 5] = [
+    (11, -32_768),
+    (19, -884_736),
+    (43, -884_736_000),
+    (67, -147_197_952_000),
+    (163, -262_537_412_640_768_000),
+];
+
+/// Lifts a + tp of the coefficient tried before giving up, in case one is
+/// the canonical lift, on which the attack degenerates.
+const LIFT_ATTEMPTS: i64 = 5;
+
+/// y² = x³ + ax + b over Z / p²Z, a lift of a curve over F_p. The modulus
+/// stays below 2^62 so the sum of two reduced values fits in an i64.
+struct LiftedCurve {
+    a: i64,
+    b: i64,
+    p: i64,
+    modulus: i64,
+}
+
+/// An affine point mod p², `None` being the point at infinity.
+type LiftedPoint = Option<(i64, i64)>;
+
+impl LiftedCurve {
+    fn mul(&self, x: i64, y: i64) -> i64 {
+        (x as i128 * y as i128).rem_euclid(self.modulus as i128) as i64
+    }
+
+    fn rhs(&self, x: i64) -> i64 {
+        let x3 = self.mul(self.mul(x, x), x);
+        ((x3 + self.mul(self.a, x)) % self.modulus + self.b).rem_euclid(self.modulus)
+    }
+
+    /// Hensel lift of (x, y) to the point (x, y + tp) with y + tp a square
+    /// root of x³ + ax + b mod p², which needs y ≠ 0.
+    fn lift(&self, point: &Point) -> Result<LiftedPoint, &'static str> {
+        if point.is_infinity() {
+            return Ok(None);
+        }
+        let error = (self.rhs(point.x) - self.mul(point.y, point.y)).rem_euclid(self.modulus);
+        let inverse =
+            mod_inverse(2 * point.y % self.p, self.p).ok_or("Cannot lift a point with y = 0")?;
+        let t = (error / self.p) * inverse % self.p;
+        Ok(Some((point.x, point.y + t * self.p)))
+    }
+
+    /// The chord-and-tangent law mod p², valid while the denominators are
+    /// units, that is while the points stay apart mod p.
+    fn add(&self, p1: &LiftedPoint, p2: &LiftedPoint) -> Result<LiftedPoint, &'static str> {
+        let (Some((x1, y1)), Some((x2, y2))) = (*p1, *p2) else {
+            return Ok(p1.or(*p2));
+        };
+        let (numerator, denominator) = if (x1, y1) == (x2, y2) {
+            (self.mul(3, self.mul(x1, x1)) + self.a, self.mul(2, y1))
+        } else {
+            (y2 - y1, x2 - x1)
+        };
+        let inverse = mod_inverse(denominator.rem_euclid(self.modulus), self.modulus)
+            .ok_or("Lifted points meet modulo p")?;
+        let lambda = self.mul(numerator.rem_euclid(self.modulus), inverse);
+        let x3 = (self.mul(lambda, lambda) - x1 - x2).rem_euclid(self.modulus);
+        let y3 = (self.mul(lambda, x1 - x3) - y1).rem_euclid(self.modulus);
+        Ok(Some((x3, y3)))
+    }
+
+    fn multiply(&self, k: i64, point: &LiftedPoint) -> Result<LiftedPoint, &'static str> {
+        let mut result = None;
+        let mut addend = *point;
+        let mut k = k;
+        while k > 0 {
+            if k & 1 == 1 {
+                result = self.add(&result, &addend)?;
+            }
+            addend = self.add(&addend, &addend)?;
+            k >>= 1;
+        }
+        Ok(result)
+    }
+
+    /// The p-adic elliptic logarithm of [p]P̃ divided by p, mod p. With
+    /// R = [p-1]P̃ ≡ -P̃ mod p, the slope λ of P̃ + R has valuation -1 and
+    /// ψ([p]P̃) = -x/y = 1/λ + O(p³) = (x_P - x_R) / (y_P - y_R) + O(p³).
+    fn p_adic_log(&self, point: &Point) -> Result<i64, &'static str> {
+        let lifted = self.lift(point)?;
+        let Some((x, y)) = lifted else {
+            return Ok(0);
+        };
+        let Some((x_r, y_r)) = self.multiply(self.p - 1, &lifted)? else {
+            return Err("Point order is not p");
+        };
+        let dx = (x - x_r).rem_euclid(self.modulus);
+        let dy = (y - y_r).rem_euclid(self.p);
+        if dx % self.p != 0 {
+            return Err("Point order is not p");
+        }
+        let inverse = mod_inverse(dy, self.p).ok_or("Point order is not p")?;
+        Ok(dx / self.p * inverse % self.p)
+    }
+}
+
+impl EllipticCurve {
+    /// Anomalous curves, with exactly p points, fall to Smart's attack.
+    pub fn is_anomalous(&self) -> bool {
+        self.count_points() == self.p
+    }
+
+    /// Smart's attack on an anomalous curve: the p-adic elliptic logarithm
+    /// of the lifts to Q_p turns Q = [k]P into k ψ([p]P̃) = ψ([p]Q̃) mod p²,
+    /// solved with a single division in F_p. Needs p < 2^31, so that p²
+    /// leaves headroom in an i64.
+    pub fn smart_attack(&self, p: &Point, q: &Point) -> Result<i64, &'static str> {
+        if !self.contains(p) || !self.contains(q) {
+            return Err("Point is not on the curve");
+        }
+        if p.is_infinity() {
+            return Err("Base point must not be the point at infinity");
+        }
+        if !self.is_anomalous() {
+            return Err("Curve is not anomalous");
+        }
+
+        if self.p >= 1 << 31 {
+            return Err("p must be below 2^31");
+        }
+        let modulus = self.p * self.p;
+        for t in 0..LIFT_ATTEMPTS {
+            let lift = LiftedCurve {
+                a: (self.a.rem_euclid(self.p) + t * self.p) % modulus,
+                b: self.b.rem_euclid(self.p),
+                p: self.p,
+                modulus,
+            };
+            let log_p = lift.p_adic_log(p)?;
+            if log_p == 0 {
+                continue;
+            }
+            let log_q = lift.p_adic_log(q)?;
+            let k = log_q * mod_inverse(log_p, self.p).ok_or("Could not invert log(P)")? % self.p;
+            if self.multiply(k, p)? != *q {
+                return Err("Target is not a multiple of the base point");
+            }
+            return Ok(k);
+        }
+        Err("Every lift of the curve was canonical")
+    }
+
+    /// The point with the smallest x ≥ 1.
+    pub fn first_point(&self) -> Option<Point> {
+        (1..self.p).find_map(|x| {
+            let rhs = ((x * x % self.p * x + self.a * x + self.b) % self.p + self.p) % self.p;
+            sqrt_mod(rhs, self.p).map(|y| Point::new(x, y))
+        })
+    }
+}
+
+/// Anomalous curves with p ≥ `min_p`, one per CM discriminant: for the
+/// first prime p = (1 + Dy²) / 4, the curve with j = j(-D) or its quadratic
+/// twist has trace one.
+pub fn anomalous_curves(min_p: i64) -> Vec<EllipticCurve> {
+    let mut curves = Vec::new();
+    for (d, j) in CM_DISCRIMINANTS {
+        let p = (1..)
+            .step_by(2)
+            .map(|y: i64| (1 + d * y * y) / 4)
+            .find(|&p| p >= min_p.max(5) && is_prime_u64(p as u64))
+            .unwrap();
+
+        // y² = x³ + 3kx + 2k with k = j / (1728 - j) and its twist by a non-residue
+        let j = j.rem_euclid(p as i128) as i64;
+        let Some(inverse) = mod_inverse((1728 - j).rem_euclid(p), p) else {
+            continue;
+        };
+        let k = j * inverse % p;
+        let c = (2..p)
+            .find(|&c| mod_pow(c, (p - 1) / 2, p) == p - 1)
+            .unwrap();
+        let (c2, c3) = (c * c % p, c * c % p * c % p);
+        let twists = [
+            (3 * k % p, 2 * k % p),
+            (3 * k % p * c2 % p, 2 * k % p * c3 % p),
+        ];
+        curves.extend(
+            twists
+                .into_iter()
+                .filter_map(|(a, b)| EllipticCurve::new(a, b, p).ok())
+                .find(|curve| curve.is_anomalous()),
+        );
+    }
+    curves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smart_attack_recovers_the_log_on_every_generated_curve() {
+        let curves = anomalous_curves(100_000);
+        assert!(!curves.is_empty());
+        for curve in curves {
+            assert!(curve.is_anomalous());
+            let base = curve.first_point().unwrap();
+            for k in [1, 2, 31_337 % curve.p, curve.p - 1] {
+                let target = curve.multiply(k, &base).unwrap();
+                assert_eq!(curve.smart_attack(&base, &target), Ok(k), "p = {}", curve.p);
+            }
+        }
+    }
+
+    #[test]
+    fn lifted_doubling_does_not_overflow_for_the_largest_p() {
+        // The largest prime below 2^31, and an x with x² mod p² > 3p²/4, so
+        // 3x² would not fit in an i64 before reduction
+        let (p, x, y) = (2_147_483_647, 2_000_000_000, 12_345);
+        let modulus = p * p;
+        let mut lift = LiftedCurve {
+            a: 7,
+            b: 0,
+            p,
+            modulus,
+        };
+        lift.b = (lift.mul(y, y) - lift.rhs(x)).rem_euclid(modulus);
+
+        let point = Some((x, y));
+        let (x2, y2) = lift.add(&point, &point).unwrap().unwrap();
+        assert_eq!(lift.rhs(x2), lift.mul(y2, y2));
+        let (x3, y3) = lift.multiply(3, &point).unwrap().unwrap();
+        assert_eq!(lift.rhs(x3), lift.mul(y3, y3));
+    }
+
+    #[test]
+    fn smart_attack_rejects_a_curve_that_is_not_anomalous() {
+        let curve = EllipticCurve::new(2, 5, 1_000_003).unwrap();
+        let base = curve.first_point().unwrap();
+        assert_eq!(
+            curve.smart_attack(&base, &base),
+            Err("Curve is not anomalous")
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use crate::windowed::OpCount;
+use crate::{add, mod_inv, Point};
+
+// Points handled per inversion when enumerating multiples
+const BATCH_SIZE: usize = 1024;
+
+fn mul_mod(x: i64, y: i64, m: i64) -> i64 {
+    (x as i128 * y as i128 % m as i128) as i64
+}
+
+// Montgomery's trick: with the prefix products c_i = x_0 ... x_i, one
+// inversion of c_(n-1) is unwound from the back, 1/x_i = c_(i-1) / c_i and
+// 1/c_(i-1) = x_i / c_i, so n inverses cost one inversion and 3(n - 1)
+// multiplications
+pub fn batch_invert(values: &[i64], m: i64) -> Result<Vec<i64>, &'static str> {
+    let values: Vec<i64> = values.iter().map(|x| x.rem_euclid(m)).collect();
+    if values.contains(&0) {
+        return Err("Cannot invert zero");
+    }
+    let mut prefix = Vec::with_capacity(values.len());
+    let mut product = 1;
+    for &x in &values {
+        product = mul_mod(product, x, m);
+        prefix.push(product);
+    }
+    let mut inverses = vec![0; values.len()];
+    let mut inverse = mod_inv(product, m);
+    for i in (1..values.len()).rev() {
+        inverses[i] = mul_mod(inverse, prefix[i - 1], m);
+        inverse = mul_mod(inverse, values[i], m);
+    }
+    if let Some(first) = inverses.first_mut() {
+        *first = inverse;
+    }
+    Ok(inverses)
+}
+
+// Jacobian coordinates, (X : Y : Z) for the affine point (X/Z², Y/Z³),
+// Z = 0 at infinity; no inversions until the points are made affine
+#[derive(Debug, Clone, Copy)]
+pub struct Jacobian {
+    x: i64,
+    y: i64,
+    z: i64,
+}
+
+impl Jacobian {
+    pub fn infinity() -> Self {
+        Jacobian { x: 1, y: 1, z: 0 }
+    }
+
+    pub fn from_affine(p: &Point) -> Self {
+        if p.is_at_infinity() {
+            return Self::infinity();
+        }
+        Jacobian {
+            x: p.x,
+            y: p.y,
+            z: 1,
+        }
+    }
+
+    pub fn is_at_infinity(&self) -> bool {
+        self.z == 0
+    }
+}
+
+// Jacobian arithmetic on y² = x³ + ax + b, with the same tally as Counter
+pub(crate) struct JacobianCounter {
+    a: i64,
+    m: i64,
+    pub(crate) ops: OpCount,
+}
+
+impl JacobianCounter {
+    pub(crate) fn new(a: i64, m: i64) -> Self {
+        JacobianCounter {
+            a: a.rem_euclid(m),
+            m,
+            ops: OpCount::default(),
+        }
+    }
+
+    pub(crate) fn double(&mut self, p: &Jacobian) -> Jacobian {
+        let m = self.m;
+        if p.is_at_infinity() || p.y == 0 {
+            return Jacobian::infinity();
+        }
+        self.ops.doublings += 1;
+        let yy = mul_mod(p.y, p.y, m);
+        let zz = mul_mod(p.z, p.z, m);
+        let s = mul_mod(4 * p.x % m, yy, m);
+        let xx = mul_mod(p.x, p.x, m);
+        let slope = (3 * xx + mul_mod(self.a, mul_mod(zz, zz, m), m)) % m;
+        let x = (mul_mod(slope, slope, m) - 2 * s).rem_euclid(m);
+        let y = (mul_mod(slope, s - x, m) - 8 * mul_mod(yy, yy, m) % m).rem_euclid(m);
+        let z = mul_mod(2 * p.y % m, p.z, m);
+        Jacobian { x, y, z }
+    }
+
+    pub(crate) fn add(&mut self, p: &Jacobian, q: &Jacobian) -> Jacobian {
+        let m = self.m;
+        if p.is_at_infinity() {
+            return *q;
+        }
+        if q.is_at_infinity() {
+            return *p;
+        }
+        let (pz2, qz2) = (mul_mod(p.z, p.z, m), mul_mod(q.z, q.z, m));
+        let u1 = mul_mod(p.x, qz2, m);
+        let u2 = mul_mod(q.x, pz2, m);
+        let s1 = mul_mod(p.y, mul_mod(qz2, q.z, m), m);
+        let s2 = mul_mod(q.y, mul_mod(pz2, p.z, m), m);
+        if u1 == u2 {
+            return if s1 == s2 {
+                self.double(p)
+            } else {
+                Jacobian::infinity()
+            };
+        }
+        self.ops.additions += 1;
+        let h = (u2 - u1).rem_euclid(m);
+        let r = (s2 - s1).rem_euclid(m);
+        let hh = mul_mod(h, h, m);
+        let hhh = mul_mod(h, hh, m);
+        let v = mul_mod(u1, hh, m);
+        let x = (mul_mod(r, r, m) - hhh - 2 * v).rem_euclid(m);
+        let y = (mul_mod(r, v - x, m) - mul_mod(s1, hhh, m)).rem_euclid(m);
+        let z = mul_mod(mul_mod(p.z, q.z, m), h, m);
+        Jacobian { x, y, z }
+    }
+}
+
+// Affine forms of all the points through a single batch inversion of the
+// Z coordinates, points at infinity passing through untouched
+pub fn batch_to_affine(points: &[Jacobian], m: i64) -> Vec<Point> {
+    let finite: Vec<i64> = points
+        .iter()
+        .filter(|p| !p.is_at_infinity())
+        .map(|p| p.z)
+        .collect();
+    // No finite Z is zero, so the inversion cannot fail
+    let mut inverses = batch_invert(&finite, m).unwrap().into_iter();
+    points
+        .iter()
+        .map(|p| {
+            if p.is_at_infinity() {
+                return Point::at_infinity();
+            }
+            let z_inv = inverses.next().unwrap();
+            let z_inv2 = mul_mod(z_inv, z_inv, m);
+            Point {
+                x: mul_mod(p.x, z_inv2, m),
+                y: mul_mod(p.y, mul_mod(z_inv2, z_inv, m), m),
+                infinity: false,
+            }
+        })
+        .collect()
+}
+
+// P_i + Q for every P_i, with the slopes' denominators x_Q - x_(P_i)
+// inverted together; the few sums that need the tangent or give infinity
+// go through the ordinary addition
+pub fn batch_add(points: &[Point], q: &Point, a: i64, m: i64) -> Vec<Point> {
+    let generic = |p: &Point| !p.is_at_infinity() && !q.is_at_infinity() && p.x != q.x;
+    let denominators: Vec<i64> = points
+        .iter()
+        .map(|p| if generic(p) { q.x - p.x } else { 1 })
+        .collect();
+    // Every denominator is non-zero by construction
+    let inverses = batch_invert(&denominators, m).unwrap();
+    points
+        .iter()
+        .zip(inverses)
+        .map(|(p, inverse)| {
+            if !generic(p) {
+                return add(p, q, a, m);
+            }
+            let slope = mul_mod(q.y - p.y, inverse, m);
+            let x = (mul_mod(slope, slope, m) - p.x - q.x).rem_euclid(m);
+            let y = (mul_mod(slope, p.x - x, m) - p.y).rem_euclid(m);
+            Point {
+                x,
+                y,
+                infinity: false,
+            }
+        })
+        .collect()
+}
+
+// P, 2P, ..., count P: the first BATCH_SIZE multiples in Jacobian
+// coordinates with one batch normalization, then each further block as
+// the previous one plus [BATCH_SIZE]P by batch_add, so one inversion per
+// block instead of one per point
+pub fn multiples(p: &Point, count: usize, a: i64, m: i64) -> Vec<Point> {
+    let mut counter = JacobianCounter::new(a, m);
+    let base = Jacobian::from_affine(p);
+    let mut current = base;
+    let mut first = Vec::with_capacity(BATCH_SIZE);
+    for _ in 0..BATCH_SIZE.min(count) {
+        first.push(current);
+        current = counter.add(&current, &base);
+    }
+    let mut points = batch_to_affine(&first, m);
+    while points.len() < count {
+        let step = &points[BATCH_SIZE - 1];
+        let next = batch_add(&points[points.len() - BATCH_SIZE..], step, a, m);
+        points.extend(next);
+    }
+    points.truncate(count);
+    points
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scalar_mult;
+
+    // y² = x³ + 2x + 40 mod 1000003, of prime order 999023
+    const A: i64 = 2;
+    const M: i64 = 1_000_003;
+    const G: Point = Point {
+        x: 2,
+        y: 536_917,
+        infinity: false,
+    };
+
+    fn negate(p: &Point) -> Point {
+        Point {
+            x: p.x,
+            y: (M - p.y) % M,
+            infinity: p.infinity,
+        }
+    }
+
+    #[test]
+    fn inverts_every_value() {
+        let values = [1, 2, 3, 999_999, 123_456];
+        let inverses = batch_invert(&values, M).unwrap();
+        for (x, inverse) in values.iter().zip(inverses) {
+            assert_eq!(mul_mod(*x, inverse, M), 1);
+        }
+    }
+
+    #[test]
+    fn inverts_an_empty_slice() {
+        assert_eq!(batch_invert(&[], M), Ok(vec![]));
+    }
+
+    #[test]
+    fn rejects_zero() {
+        assert_eq!(batch_invert(&[3, 0, 5], M), Err("Cannot invert zero"));
+        assert_eq!(batch_invert(&[3, M, 5], M), Err("Cannot invert zero"));
+    }
+
+    #[test]
+    fn reduces_negative_inputs() {
+        let inverses = batch_invert(&[-1, -2, -M - 7], M).unwrap();
+        assert_eq!(inverses, batch_invert(&[M - 1, M - 2, M - 7], M).unwrap());
+        assert_eq!(inverses[0], M - 1);
+    }
+
+    #[test]
+    fn passes_infinity_through_to_affine() {
+        let mut counter = JacobianCounter::new(A, M);
+        let g = Jacobian::from_affine(&G);
+        let g2 = counter.double(&g);
+        let points = [Jacobian::infinity(), g2, Jacobian::infinity(), g];
+        let affine = batch_to_affine(&points, M);
+        assert_eq!(
+            affine,
+            vec![
+                Point::at_infinity(),
+                scalar_mult(&G, 2, A, M),
+                Point::at_infinity(),
+                G,
+            ]
+        );
+        assert!(batch_to_affine(&[Jacobian::infinity()], M)[0].is_at_infinity());
+    }
+
+    #[test]
+    fn adds_equal_and_opposite_points() {
+        let g3 = scalar_mult(&G, 3, A, M);
+        let points = [G, negate(&G), Point::at_infinity(), g3];
+        let sums = batch_add(&points, &G, A, M);
+        assert_eq!(sums[0], scalar_mult(&G, 2, A, M));
+        assert!(sums[1].is_at_infinity());
+        assert_eq!(sums[2], G);
+        assert_eq!(sums[3], scalar_mult(&G, 4, A, M));
+        assert_eq!(batch_add(&[G], &Point::at_infinity(), A, M), vec![G]);
+    }
+
+    #[test]
+    fn multiples_cross_the_batch_boundary() {
+        for count in [
+            0,
+            1,
+            BATCH_SIZE - 1,
+            BATCH_SIZE,
+            BATCH_SIZE + 1,
+            2 * BATCH_SIZE + 1,
+        ] {
+            let points = multiples(&G, count, A, M);
+            assert_eq!(points.len(), count);
+            let mut expected = Point::at_infinity();
+            for (i, p) in points.iter().enumerate() {
+                expected = add(&expected, &G, A, M);
+                assert_eq!(*p, expected, "{} G of {}", i + 1, count);
+            }
+        }
+    }
+}
@@ -0,0 +1,201 @@
+use num_bigint::BigUint;
+
+// 64-bit words per element, enough for every m up to 255
+pub const LIMBS: usize = 4;
+
+// A polynomial over F_2 of degree below 64 LIMBS, bit i the coefficient of z^i
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Gf2m([u64; LIMBS]);
+
+impl Gf2m {
+    pub fn zero() -> Self {
+        Gf2m([0; LIMBS])
+    }
+
+    pub fn one() -> Self {
+        let mut words = [0; LIMBS];
+        words[0] = 1;
+        Gf2m(words)
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|&w| w == 0)
+    }
+
+    fn bit(&self, i: u32) -> bool {
+        (self.0[(i / 64) as usize] >> (i % 64)) & 1 == 1
+    }
+
+    fn set_bit(&mut self, i: u32) {
+        self.0[(i / 64) as usize] |= 1 << (i % 64);
+    }
+
+    // The degree, with -1 for the zero polynomial
+    fn degree(&self) -> i32 {
+        for i in (0..LIMBS).rev() {
+            if self.0[i] != 0 {
+                return (64 * i as u32 + 63 - self.0[i].leading_zeros()) as i32;
+            }
+        }
+        -1
+    }
+
+    // Each word passes its top bit up to the next
+    fn shift_left(&self) -> Self {
+        let mut carry = 0;
+        Gf2m(self.0.map(|w| {
+            let shifted = w << 1 | carry;
+            carry = w >> 63;
+            shifted
+        }))
+    }
+
+    fn shift_right(&self) -> Self {
+        let mut words = self.0;
+        let mut carry = 0;
+        for w in words.iter_mut().rev() {
+            let shifted = *w >> 1 | carry;
+            carry = *w << 63;
+            *w = shifted;
+        }
+        Gf2m(words)
+    }
+
+    // Addition and subtraction are both the XOR of the coefficients
+    pub fn add(&self, other: &Self) -> Self {
+        let mut words = self.0;
+        for (w, o) in words.iter_mut().zip(other.0) {
+            *w ^= o;
+        }
+        Gf2m(words)
+    }
+}
+
+// F_(2^m) = F_2[z] / f(z) for an irreducible trinomial or pentanomial f,
+// the polynomial basis used by the NIST binary curves
+#[derive(Debug)]
+pub struct BinaryField {
+    pub m: u32,
+    // f(z) itself, z^m included
+    modulus: Gf2m,
+}
+
+impl BinaryField {
+    // f(z) = z^m + z^(k_1) + ... + 1 from the exponents of its middle terms;
+    // irreducibility is the caller's responsibility
+    pub fn new(m: u32, middle_terms: &[u32]) -> Result<Self, &'static str> {
+        if m < 2 || m >= 64 * LIMBS as u32 {
+            return Err("Extension degree must be between 2 and 255");
+        }
+        if middle_terms.iter().any(|&k| k == 0 || k >= m) {
+            return Err("Middle terms must lie strictly between 0 and m");
+        }
+        let mut modulus = Gf2m::one();
+        modulus.set_bit(m);
+        for &k in middle_terms {
+            modulus.set_bit(k);
+        }
+        Ok(BinaryField { m, modulus })
+    }
+
+    // The element whose coefficients are the bits of x, for x < 2^m
+    pub fn element(&self, x: &BigUint) -> Result<Gf2m, &'static str> {
+        if x.bits() > self.m as u64 {
+            return Err("Element does not fit in the field");
+        }
+        let mut words = [0; LIMBS];
+        for (w, digit) in words.iter_mut().zip(x.iter_u64_digits()) {
+            *w = digit;
+        }
+        Ok(Gf2m(words))
+    }
+
+    pub fn value(&self, x: &Gf2m) -> BigUint {
+        let bytes: Vec<u8> = x.0.iter().flat_map(|w| w.to_le_bytes()).collect();
+        BigUint::from_bytes_le(&bytes)
+    }
+
+    // Left-to-right shift-and-add: multiply the accumulator by z, folding
+    // z^m back through f, and add x wherever y has a one
+    pub fn mul(&self, x: &Gf2m, y: &Gf2m) -> Gf2m {
+        let mut result = Gf2m::zero();
+        for i in (0..self.m).rev() {
+            result = result.shift_left();
+            if result.bit(self.m) {
+                result = result.add(&self.modulus);
+            }
+            if y.bit(i) {
+                result = result.add(x);
+            }
+        }
+        result
+    }
+
+    pub fn square(&self, x: &Gf2m) -> Gf2m {
+        self.mul(x, x)
+    }
+
+    // The binary extended Euclidean algorithm on (x, f), keeping
+    // g1 x ≡ u and g2 x ≡ v (mod f) while dividing out factors of z
+    pub fn inverse(&self, x: &Gf2m) -> Result<Gf2m, &'static str> {
+        if x.is_zero() {
+            return Err("Cannot invert zero");
+        }
+        let one = Gf2m::one();
+        let (mut u, mut v) = (*x, self.modulus);
+        let (mut g1, mut g2) = (one, Gf2m::zero());
+        while u != one && v != one {
+            // Only a reducible modulus can share a factor with x
+            if u.is_zero() || v.is_zero() {
+                return Err("Element is not invertible, the modulus is reducible");
+            }
+            for (w, g) in [(&mut u, &mut g1), (&mut v, &mut g2)] {
+                while !w.is_zero() && !w.bit(0) {
+                    *w = w.shift_right();
+                    if g.bit(0) {
+                        *g = g.add(&self.modulus);
+                    }
+                    *g = g.shift_right();
+                }
+            }
+            if u.degree() > v.degree() {
+                u = u.add(&v);
+                g1 = g1.add(&g2);
+            } else {
+                v = v.add(&u);
+                g2 = g2.add(&g1);
+            }
+        }
+        Ok(if u == one { g1 } else { g2 })
+    }
+
+    pub fn div(&self, x: &Gf2m, y: &Gf2m) -> Result<Gf2m, &'static str> {
+        Ok(self.mul(x, &self.inverse(y)?))
+    }
+
+    // Tr(c) = c + c² + c⁴ + ... + c^(2^(m-1)), always 0 or 1
+    pub fn trace(&self, c: &Gf2m) -> bool {
+        let mut power = *c;
+        let mut sum = *c;
+        for _ in 1..self.m {
+            power = self.square(&power);
+            sum = sum.add(&power);
+        }
+        !sum.is_zero()
+    }
+
+    // For odd m, H(c) = Σ c^(2^(2i)) over 0 ≤ i ≤ (m-1)/2 solves
+    // w² + w = c whenever Tr(c) = 0
+    pub fn half_trace(&self, c: &Gf2m) -> Result<Gf2m, &'static str> {
+        if self.m.is_multiple_of(2) {
+            return Err("Half-trace needs an odd extension degree");
+        }
+        let mut power = *c;
+        let mut sum = *c;
+        for _ in 0..(self.m - 1) / 2 {
+            power = self.square(&self.square(&power));
+            sum = sum.add(&power);
+        }
+        Ok(sum)
+    }
+}
@@ -0,0 +1,89 @@
+use rand::Rng;
+use std::hint::black_box;
+use std::time::Instant;
+
+// |t| above this is taken as evidence that the two classes are timed
+// differently, the threshold used by dudect
+pub const T_THRESHOLD: f64 = 4.5;
+
+// Measurements above this quantile are dropped as interrupts and other
+// noise from outside the function under test
+const CROP_QUANTILE: f64 = 0.9;
+
+// Welch's t statistic for the difference in means of two samples
+pub fn welch_t(a: &[f64], b: &[f64]) -> f64 {
+    let stats = |xs: &[f64]| {
+        let n = xs.len() as f64;
+        let mean = xs.iter().sum::<f64>() / n;
+        let variance = xs.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (n, mean, variance)
+    };
+    let (n_a, mean_a, var_a) = stats(a);
+    let (n_b, mean_b, var_b) = stats(b);
+    (mean_a - mean_b) / (var_a / n_a + var_b / n_b).sqrt()
+}
+
+// A dudect-style fixed-versus-random test (Reparaz, Balasch and
+// Verbauwhede): each measurement runs f on either the fixed scalar or a
+// fresh random one, the class drawn at random so that drift and noise hit
+// both classes alike, and Welch's t-test compares the two timing
+// distributions after cropping the slowest measurements.
+pub fn fixed_vs_random<R: Rng, T, F: FnMut(u64) -> T>(
+    mut f: F,
+    fixed: u64,
+    samples: usize,
+    rng: &mut R,
+) -> f64 {
+    let mut timings = Vec::with_capacity(samples);
+    for _ in 0..samples {
+        let is_fixed = rng.gen::<bool>();
+        let k = if is_fixed { fixed } else { rng.gen() };
+        let start = Instant::now();
+        black_box(f(black_box(k)));
+        timings.push((is_fixed, start.elapsed().as_nanos() as f64));
+    }
+
+    let mut sorted: Vec<f64> = timings.iter().map(|&(_, t)| t).collect();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let cutoff = sorted[((samples as f64 * CROP_QUANTILE) as usize).min(samples - 1)];
+    let class = |fixed: bool| -> Vec<f64> {
+        timings
+            .iter()
+            .filter(|&&(is_fixed, t)| is_fixed == fixed && t <= cutoff)
+            .map(|&(_, t)| t)
+            .collect()
+    };
+    welch_t(&class(true), &class(false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn welch_t_of_shifted_samples() {
+        let a = [1.0, 2.0, 3.0, 4.0];
+        let b = [3.0, 4.0, 5.0, 6.0];
+        assert_eq!(welch_t(&a, &a), 0.0);
+        // Means 2.5 and 4.5, both variances 5/3
+        let expected = -2.0 / (2.0 * 5.0 / 3.0 / 4.0_f64).sqrt();
+        assert!((welch_t(&a, &b) - expected).abs() < 1e-12);
+        assert!((welch_t(&b, &a) + expected).abs() < 1e-12);
+    }
+
+    #[test]
+    fn flags_a_leaky_function() {
+        // Returns at once for k = 0 and spins otherwise
+        let leaky = |k: u64| {
+            let mut x = k;
+            if k != 0 {
+                for _ in 0..2_000 {
+                    x = black_box(x.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1));
+                }
+            }
+            x
+        };
+        let t = fixed_vs_random(leaky, 0, 5_000, &mut rand::thread_rng());
+        assert!(t.abs() > T_THRESHOLD, "t = {}", t);
+    }
+}
@@ -0,0 +1,240 @@
+use std::fs;
+use std::path::Path;
+
+use crate::batch::{batch_to_affine, Jacobian, JacobianCounter};
+use crate::windowed::{Counter, OpCount};
+use crate::{negate, Point};
+
+// First line of a saved table, bumped whenever the layout changes
+const HEADER: &str = "fixed-base-table 1";
+
+// Lim-Lee comb for a fixed base P: a scalar of up to `bits` bits is cut
+// into `width` rows of d = ⌈bits / width⌉ bits, and entry i of the table is
+// the sum of 2^(jd)P over the bits j set in i. One column of the comb, the
+// j-th bit of every row, is then a single table lookup, so [k]P costs
+// d - 1 doublings and at most d additions.
+#[derive(Debug)]
+pub struct FixedBaseTable {
+    a: i64,
+    m: i64,
+    bits: u32,
+    width: u32,
+    table: Vec<Point>,
+    cost: OpCount,
+}
+
+impl FixedBaseTable {
+    // 2^width entries from width - 1 runs of d doublings and one addition per
+    // entry, in Jacobian coordinates with a single inversion at the end; the
+    // cost is paid once per base point
+    pub fn new(base: &Point, a: i64, m: i64, bits: u32, width: u32) -> Result<Self, &'static str> {
+        if !(1..=64).contains(&bits) {
+            return Err("Scalar size must be between 1 and 64 bits");
+        }
+        if !(1..=16).contains(&width) || width > bits {
+            return Err("Comb width must be between 1 and 16 and at most the scalar size");
+        }
+        let (table, cost) = Self::precompute(base, a, m, bits, width);
+        Ok(FixedBaseTable {
+            a,
+            m,
+            bits,
+            width,
+            table,
+            cost,
+        })
+    }
+
+    fn precompute(base: &Point, a: i64, m: i64, bits: u32, width: u32) -> (Vec<Point>, OpCount) {
+        let d = bits.div_ceil(width);
+        let mut counter = JacobianCounter::new(a, m);
+        let mut row_base = Jacobian::from_affine(base);
+        let mut table = vec![Jacobian::infinity(), row_base];
+        for j in 1..width {
+            for _ in 0..d {
+                row_base = counter.double(&row_base);
+            }
+            // Entries with top bit j are the earlier ones plus 2^(jd)P
+            for i in 0..1 << j {
+                let entry = counter.add(&table[i], &row_base);
+                table.push(entry);
+            }
+        }
+        (batch_to_affine(&table, m), counter.ops)
+    }
+
+    // The group operations spent building the table
+    pub fn precomputation_cost(&self) -> OpCount {
+        self.cost
+    }
+
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    // [k]P for |k| < 2^bits, reading the comb from its top column down
+    pub fn multiply(&self, k: i64) -> Result<(Point, OpCount), &'static str> {
+        let magnitude = k.unsigned_abs();
+        if self.bits < 64 && magnitude >> self.bits != 0 {
+            return Err("Scalar is larger than the table was built for");
+        }
+        let d = self.bits.div_ceil(self.width);
+        let mut counter = Counter::new(self.a, self.m);
+        let mut result = Point::at_infinity();
+        for column in (0..d).rev() {
+            result = counter.double(&result);
+            let mut index = 0;
+            for row in 0..self.width {
+                let bit = row * d + column;
+                if bit < 64 {
+                    index |= ((magnitude >> bit) & 1) << row;
+                }
+            }
+            result = counter.add(&result, &self.table[index as usize]);
+        }
+        if k < 0 {
+            result = negate(&result, self.m);
+        }
+        Ok((result, counter.ops))
+    }
+
+    // A text file: the header, then "a m bits width", then one entry per
+    // line as "x y", or "O" for the point at infinity
+    pub fn save(&self, path: &Path) -> Result<(), &'static str> {
+        let mut text = format!(
+            "{}\n{} {} {} {}\n",
+            HEADER, self.a, self.m, self.bits, self.width
+        );
+        for point in &self.table {
+            if point.is_at_infinity() {
+                text.push_str("O\n");
+            } else {
+                text.push_str(&format!("{} {}\n", point.x, point.y));
+            }
+        }
+        fs::write(path, text).map_err(|_| "Could not write the table file")
+    }
+
+    // Reads a table written by save, checking its shape and then every entry
+    // against a table rebuilt from entry 1, the base point, so a corrupted
+    // or edited file is rejected rather than giving wrong multiples
+    pub fn load(path: &Path) -> Result<Self, &'static str> {
+        let text = fs::read_to_string(path).map_err(|_| "Could not read the table file")?;
+        let mut lines = text.lines();
+        if lines.next() != Some(HEADER) {
+            return Err("Not a fixed-base table file");
+        }
+        let parameters: Vec<i64> = lines
+            .next()
+            .ok_or("Table file has no parameters")?
+            .split_whitespace()
+            .map(|field| field.parse().map_err(|_| "Malformed table parameters"))
+            .collect::<Result<_, _>>()?;
+        let [a, m, bits, width] = parameters[..] else {
+            return Err("Malformed table parameters");
+        };
+        if !(1..=64).contains(&bits) || !(1..=16).contains(&width) || width > bits || m < 2 {
+            return Err("Malformed table parameters");
+        }
+        let table: Vec<Point> = lines
+            .map(|line| {
+                if line == "O" {
+                    return Ok(Point::at_infinity());
+                }
+                let coordinates: Vec<i64> = line
+                    .split_whitespace()
+                    .map(|field| field.parse().map_err(|_| "Malformed table entry"))
+                    .collect::<Result<_, _>>()?;
+                match coordinates[..] {
+                    [x, y] if (0..m).contains(&x) && (0..m).contains(&y) => Ok(Point {
+                        x,
+                        y,
+                        infinity: false,
+                    }),
+                    _ => Err("Malformed table entry"),
+                }
+            })
+            .collect::<Result<_, _>>()?;
+        if table.len() != 1 << width || !table[0].is_at_infinity() || table[1].is_at_infinity() {
+            return Err("Table entries do not match its parameters");
+        }
+        let (bits, width) = (bits as u32, width as u32);
+        let (expected, cost) = Self::precompute(&table[1], a, m, bits, width);
+        if table != expected {
+            return Err("Table entries are not the multiples of its base point");
+        }
+        Ok(FixedBaseTable {
+            a,
+            m,
+            bits,
+            width,
+            table,
+            cost,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scalar_mult;
+
+    // y² = x³ + 2x + 40 mod 1000003, of prime order 999023
+    const P: Point = Point {
+        x: 2,
+        y: 536_917,
+        infinity: false,
+    };
+    const A: i64 = 2;
+    const M: i64 = 1_000_003;
+
+    fn round_trip(
+        name: &str,
+        edit: impl Fn(String) -> String,
+    ) -> Result<FixedBaseTable, &'static str> {
+        let table = FixedBaseTable::new(&P, A, M, 20, 4).unwrap();
+        let path = std::env::temp_dir().join(format!("fixed_base_test_{}.txt", name));
+        table.save(&path).unwrap();
+        fs::write(&path, edit(fs::read_to_string(&path).unwrap())).unwrap();
+        let loaded = FixedBaseTable::load(&path);
+        let _ = fs::remove_file(&path);
+        loaded
+    }
+
+    #[test]
+    fn multiplies_like_scalar_mult() {
+        let table = FixedBaseTable::new(&P, A, M, 20, 4).unwrap();
+        for k in [0, 1, -1, 2, 999_022, -999_023, 1 << 19, -((1 << 20) - 1)] {
+            assert_eq!(
+                table.multiply(k).unwrap().0,
+                scalar_mult(&P, k, A, M),
+                "k = {}",
+                k
+            );
+        }
+        assert!(table.multiply(1 << 20).is_err());
+    }
+
+    #[test]
+    fn reloads_a_saved_table() {
+        let table = FixedBaseTable::new(&P, A, M, 20, 4).unwrap();
+        let loaded = round_trip("saved", |text| text).unwrap();
+        assert_eq!(loaded.table, table.table);
+        assert_eq!(loaded.precomputation_cost(), table.precomputation_cost());
+    }
+
+    #[test]
+    fn rejects_an_edited_entry() {
+        // Replace the last entry with the base point
+        let edited = round_trip("edited", |text| {
+            let mut lines: Vec<&str> = text.lines().collect();
+            let last = lines.len() - 1;
+            lines[last] = lines[3];
+            lines.join("\n") + "\n"
+        });
+        assert_eq!(
+            edited.err(),
+            Some("Table entries are not the multiples of its base point")
+        );
+    }
+}
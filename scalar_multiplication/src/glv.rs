@@ -0,0 +1,291 @@
+use crate::windowed::{Counter, OpCount};
+use crate::{negate, scalar_mult, Point};
+
+// Bases tried when looking for roots of unity; each is a non-residue of
+// the right kind with probability at least 1/2
+const ROOT_ATTEMPTS: i64 = 64;
+
+fn pow_mod(base: i64, exponent: i64, m: i64) -> i64 {
+    let (mut result, mut base, mut exponent) = (1i128, base.rem_euclid(m) as i128, exponent);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result = result * base % m as i128;
+        }
+        base = base * base % m as i128;
+        exponent >>= 1;
+    }
+    result as i64
+}
+
+// A primitive r-th root of unity mod a prime m with r | m - 1, as h^((m-1)/r)
+fn root_of_unity(r: i64, m: i64) -> Option<i64> {
+    (2..ROOT_ATTEMPTS)
+        .map(|h| pow_mod(h, (m - 1) / r, m))
+        .find(|&root| (1..r).all(|e| pow_mod(root, e, m) != 1))
+}
+
+// The endomorphisms of the curves with extra automorphisms: a primitive
+// cube root of unity β gives (x, y) ↦ (βx, y) on y² = x³ + b, and a
+// square root i of -1 gives (x, y) ↦ (-x, iy) on y² = x³ + ax
+#[derive(Debug, Clone, Copy)]
+pub enum Endomorphism {
+    CubeRoot { beta: i64 },
+    FourthRoot { i: i64 },
+}
+
+// Everything GLV needs for a base point of prime order n: φ acts on the
+// subgroup as multiplication by λ, and a short basis of the lattice of
+// (x, y) with x + yλ ≡ 0 (mod n) splits any k into two half-size scalars
+#[derive(Debug)]
+pub struct Glv {
+    pub endomorphism: Endomorphism,
+    pub lambda: i64,
+    pub n: i64,
+    pub basis: [(i64, i64); 2],
+    a: i64,
+    m: i64,
+}
+
+impl Glv {
+    // Recognizes j = 0 (a = 0, m ≡ 1 mod 3) and j = 1728 (b = 0,
+    // m ≡ 1 mod 4) and pairs the eigenvalue λ with the right root by
+    // checking φ(G) = [λ]G
+    pub fn detect(g: &Point, n: i64, a: i64, b: i64, m: i64) -> Option<Self> {
+        let (a, b) = (a.rem_euclid(m), b.rem_euclid(m));
+        let (order, endomorphisms): (i64, Vec<Endomorphism>) =
+            if a == 0 && b != 0 && m % 3 == 1 && n % 3 == 1 {
+                let beta = root_of_unity(3, m)?;
+                let betas = [beta, pow_mod(beta, 2, m)];
+                (
+                    3,
+                    betas.map(|beta| Endomorphism::CubeRoot { beta }).to_vec(),
+                )
+            } else if b == 0 && a != 0 && m % 4 == 1 && n % 4 == 1 {
+                let i = root_of_unity(4, m)?;
+                (
+                    4,
+                    [i, m - i].map(|i| Endomorphism::FourthRoot { i }).to_vec(),
+                )
+            } else {
+                return None;
+            };
+
+        let lambda = root_of_unity(order, n)?;
+        let lambdas: Vec<i64> = (1..order)
+            .map(|e| pow_mod(lambda, e, n))
+            .filter(|&l| order == 3 || pow_mod(l, 2, n) == n - 1)
+            .collect();
+        for endomorphism in endomorphisms {
+            for &lambda in &lambdas {
+                let mut glv = Glv {
+                    endomorphism,
+                    lambda,
+                    n,
+                    basis: [(0, 0); 2],
+                    a,
+                    m,
+                };
+                if glv.apply(g) == scalar_mult(g, lambda, a, m) {
+                    glv.basis = reduced_basis(n, lambda);
+                    return Some(glv);
+                }
+            }
+        }
+        None
+    }
+
+    // φ(P), one or two field multiplications instead of a scalar multiplication
+    pub fn apply(&self, p: &Point) -> Point {
+        if p.is_at_infinity() {
+            return p.clone();
+        }
+        let m = self.m as i128;
+        let (x, y) = match self.endomorphism {
+            Endomorphism::CubeRoot { beta } => (p.x as i128 * beta as i128 % m, p.y as i128),
+            Endomorphism::FourthRoot { i } => ((m - p.x as i128) % m, p.y as i128 * i as i128 % m),
+        };
+        Point {
+            x: x as i64,
+            y: y as i64,
+            infinity: false,
+        }
+    }
+
+    // k ≡ k1 + k2λ (mod n) with |k1|, |k2| about √n: subtract from (k, 0)
+    // the lattice vector c1 v1 + c2 v2 nearest to it, with c1 and c2 the
+    // rounded coordinates of (k, 0) in the basis
+    pub fn decompose(&self, k: i64) -> (i64, i64) {
+        let [(a1, b1), (a2, b2)] = self.basis.map(|(x, y)| (x as i128, y as i128));
+        let k = k.rem_euclid(self.n) as i128;
+        let determinant = a1 * b2 - a2 * b1;
+        let c1 = round_div(b2 * k, determinant);
+        let c2 = round_div(-b1 * k, determinant);
+        let k1 = k - c1 * a1 - c2 * a2;
+        let k2 = -c1 * b1 - c2 * b2;
+        (k1 as i64, k2 as i64)
+    }
+
+    // [k]P = [k1]P + [k2]φ(P) by Shamir's trick: one pass of doublings over
+    // the half-length scalars, adding P, φ(P) or their precomputed sum
+    pub fn multiply(&self, p: &Point, k: i64) -> (Point, OpCount) {
+        let (k1, k2) = self.decompose(k);
+        let p1 = if k1 < 0 { negate(p, self.m) } else { p.clone() };
+        let phi = self.apply(p);
+        let p2 = if k2 < 0 { negate(&phi, self.m) } else { phi };
+        let (k1, k2) = (k1.unsigned_abs(), k2.unsigned_abs());
+
+        let mut counter = Counter::new(self.a, self.m);
+        let sum = counter.add(&p1, &p2);
+        let mut result = Point::at_infinity();
+        let bits = 64 - (k1 | k2).leading_zeros();
+        for i in (0..bits).rev() {
+            result = counter.double(&result);
+            let addend = match ((k1 >> i) & 1, (k2 >> i) & 1) {
+                (1, 1) => &sum,
+                (1, 0) => &p1,
+                (0, 1) => &p2,
+                _ => continue,
+            };
+            result = counter.add(&result, addend);
+        }
+        (result, counter.ops)
+    }
+}
+
+// Nearest integer to x / y
+fn round_div(x: i128, y: i128) -> i128 {
+    let (x, y) = if y < 0 { (-x, -y) } else { (x, y) };
+    (2 * x + y).div_euclid(2 * y)
+}
+
+// The basis of Gallant, Lambert and Vanstone: the extended Euclidean
+// algorithm on (n, λ) produces r_i ≡ t_i λ (mod n), so each (r_i, -t_i)
+// lies in the lattice; around r_l ≥ √n > r_(l+1) these vectors are short
+fn reduced_basis(n: i64, lambda: i64) -> [(i64, i64); 2] {
+    let root = (n as f64).sqrt();
+    let mut remainders = vec![n, lambda];
+    let mut coefficients = vec![0i64, 1];
+    while *remainders.last().unwrap() != 0 {
+        let len = remainders.len();
+        let quotient = remainders[len - 2] / remainders[len - 1];
+        remainders.push(remainders[len - 2] - quotient * remainders[len - 1]);
+        coefficients.push(coefficients[len - 2] - quotient * coefficients[len - 1]);
+    }
+    let l = remainders.iter().rposition(|&r| r as f64 >= root).unwrap();
+    let vector = |i: usize| (remainders[i], -coefficients[i]);
+    let norm = |(x, y): (i64, i64)| (x as i128).pow(2) + (y as i128).pow(2);
+    let v1 = vector(l + 1);
+    let v2 = if l + 2 < remainders.len() && norm(vector(l + 2)) < norm(vector(l)) {
+        vector(l + 2)
+    } else {
+        vector(l)
+    };
+    [v1, v2]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // y² = x³ + 5 mod 1000003 of prime order 999007 (j = 0)
+    fn j_0() -> (Point, Glv) {
+        let g = Point {
+            x: 1,
+            y: 586_770,
+            infinity: false,
+        };
+        let glv = Glv::detect(&g, 999_007, 0, 5, 1_000_003).unwrap();
+        (g, glv)
+    }
+
+    // y² = x³ + 2x mod 1000037, where g has prime order 500153 (j = 1728)
+    fn j_1728() -> (Point, Glv) {
+        let g = Point {
+            x: 301_864,
+            y: 331_917,
+            infinity: false,
+        };
+        let glv = Glv::detect(&g, 500_153, 2, 0, 1_000_037).unwrap();
+        (g, glv)
+    }
+
+    fn scalars(n: i64) -> Vec<i64> {
+        let mut scalars = vec![0, 1, -1, 2, n - 1, n, n + 1, -n + 1, -12_345, 2 * n + 7];
+        scalars.extend((1..200).map(|i| i * 7_919 % n));
+        scalars
+    }
+
+    #[test]
+    fn detects_both_endomorphisms() {
+        assert!(matches!(
+            j_0().1.endomorphism,
+            Endomorphism::CubeRoot { .. }
+        ));
+        assert!(matches!(
+            j_1728().1.endomorphism,
+            Endomorphism::FourthRoot { .. }
+        ));
+    }
+
+    #[test]
+    fn decomposition_is_short_and_correct() {
+        for (_, glv) in [j_0(), j_1728()] {
+            let n = glv.n as i128;
+            let bound = 2.0 * (glv.n as f64).sqrt();
+            for k in scalars(glv.n) {
+                let (k1, k2) = glv.decompose(k);
+                let sum = k1 as i128 + k2 as i128 * glv.lambda as i128;
+                assert_eq!(sum.rem_euclid(n), (k as i128).rem_euclid(n), "k = {}", k);
+                assert!((k1.abs() as f64) <= bound, "k1 = {} for k = {}", k1, k);
+                assert!((k2.abs() as f64) <= bound, "k2 = {} for k = {}", k2, k);
+            }
+        }
+    }
+
+    #[test]
+    fn multiply_matches_scalar_mult() {
+        for (g, glv) in [j_0(), j_1728()] {
+            for k in scalars(glv.n) {
+                let expected = scalar_mult(&g, k, glv.a, glv.m);
+                assert_eq!(glv.multiply(&g, k).0, expected, "k = {}", k);
+            }
+        }
+    }
+
+    #[test]
+    fn endomorphism_acts_as_lambda() {
+        for (g, glv) in [j_0(), j_1728()] {
+            let p = scalar_mult(&g, 4_242, glv.a, glv.m);
+            assert_eq!(glv.apply(&p), scalar_mult(&p, glv.lambda, glv.a, glv.m));
+        }
+    }
+
+    #[test]
+    fn ignores_curves_without_extra_automorphisms() {
+        let g = Point {
+            x: 2,
+            y: 536_917,
+            infinity: false,
+        };
+        assert!(Glv::detect(&g, 999_023, 2, 40, 1_000_003).is_none());
+        // j = 0 but m ≡ 2 (mod 3), so there is no cube root of unity
+        assert!(Glv::detect(&g, 999_023, 0, 7, 1_000_019).is_none());
+    }
+
+    #[test]
+    fn finds_cube_roots_past_32_bits() {
+        // m ≡ 1 (mod 3) is past 10^10, so β² no longer fits in an i64; the
+        // point at infinity keeps the curve arithmetic out of the check
+        let m = 10_000_000_033;
+        let beta = root_of_unity(3, m).unwrap();
+        assert!(beta as i128 * beta as i128 > i64::MAX as i128);
+        let glv = Glv::detect(&Point::at_infinity(), 999_007, 0, 8, m).unwrap();
+        match glv.endomorphism {
+            Endomorphism::CubeRoot { beta } => {
+                assert_ne!(beta, 1);
+                assert_eq!(pow_mod(beta, 3, m), 1);
+            }
+            Endomorphism::FourthRoot { .. } => panic!("Expected a cube root"),
+        }
+    }
+}
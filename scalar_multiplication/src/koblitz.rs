@@ -0,0 +1,410 @@
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Signed, Zero};
+
+use crate::binary_field::{BinaryField, Gf2m};
+
+// An affine point of a curve over F_(2^m)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BinaryPoint {
+    pub x: Gf2m,
+    pub y: Gf2m,
+    pub infinity: bool,
+}
+
+impl BinaryPoint {
+    pub fn at_infinity() -> Self {
+        BinaryPoint {
+            x: Gf2m::zero(),
+            y: Gf2m::zero(),
+            infinity: true,
+        }
+    }
+}
+
+// An element r0 + r1 τ of Z[τ], where τ² = μτ - 2
+#[derive(Debug, Clone, PartialEq)]
+struct ZTau(BigInt, BigInt);
+
+impl ZTau {
+    fn mul(&self, other: &ZTau, mu: i64) -> ZTau {
+        let (a0, a1, b0, b1) = (&self.0, &self.1, &other.0, &other.1);
+        let high = a1 * b1;
+        ZTau(a0 * b0 - 2 * &high, a0 * b1 + a1 * b0 + mu * high)
+    }
+
+    fn sub(&self, other: &ZTau) -> ZTau {
+        ZTau(&self.0 - &other.0, &self.1 - &other.1)
+    }
+
+    // The conjugate, with τ̄ = μ - τ
+    fn conjugate(&self, mu: i64) -> ZTau {
+        ZTau(&self.0 + mu * &self.1, -&self.1)
+    }
+
+    // r0² + μ r0 r1 + 2 r1², the product with the conjugate
+    fn norm(&self, mu: i64) -> BigInt {
+        &self.0 * &self.0 + mu * &self.0 * &self.1 + 2 * &self.1 * &self.1
+    }
+}
+
+// A Koblitz curve y² + xy = x³ + ax² + 1 over F_(2^m) with a ∈ {0, 1}:
+// defined over F_2, so the Frobenius map τ(x, y) = (x², y²) is an
+// endomorphism, and it satisfies τ² - μτ + 2 = 0 with μ = (-1)^(1-a)
+pub struct KoblitzCurve {
+    pub field: BinaryField,
+    pub a: u8,
+    mu: i64,
+    // #E(F_(2^m)) = h n, with h = #E(F_2) = 3 - μ
+    pub order: BigInt,
+    pub cofactor: BigInt,
+    pub n: BigInt,
+    // δ = (τ^m - 1) / (τ - 1), which kills the subgroup of order n
+    delta: ZTau,
+}
+
+impl KoblitzCurve {
+    pub fn new(m: u32, middle_terms: &[u32], a: u8) -> Result<Self, &'static str> {
+        if a > 1 {
+            return Err("Koblitz curves have a = 0 or a = 1");
+        }
+        let field = BinaryField::new(m, middle_terms)?;
+        let mu: i64 = if a == 1 { 1 } else { -1 };
+        // τ^m = U_m τ - 2 U_(m-1) and #E = 2^m + 1 - V_m, with the Lucas
+        // sequences U_(i+1) = μ U_i - 2 U_(i-1) from 0, 1 and V likewise from 2, μ
+        let (mut u_prev, mut u) = (BigInt::zero(), BigInt::one());
+        let (mut v_prev, mut v) = (BigInt::from(2), BigInt::from(mu));
+        for _ in 1..m {
+            let u_next = mu * &u - 2 * &u_prev;
+            u_prev = std::mem::replace(&mut u, u_next);
+            let v_next = mu * &v - 2 * &v_prev;
+            v_prev = std::mem::replace(&mut v, v_next);
+        }
+        let order = (BigInt::one() << m) + 1 - v;
+        let cofactor = BigInt::from(3 - mu);
+        let n = &order / &cofactor;
+
+        // (τ^m - 1)(τ̄ - 1) / N(τ - 1), the division being exact
+        let tau_m_minus_1 = ZTau(-2 * u_prev - 1, u);
+        let tau_minus_1 = ZTau(BigInt::from(-1), BigInt::one());
+        let numerator = tau_m_minus_1.mul(&tau_minus_1.conjugate(mu), mu);
+        let denominator = tau_minus_1.norm(mu);
+        let delta = ZTau(&numerator.0 / &denominator, &numerator.1 / &denominator);
+        Ok(KoblitzCurve {
+            field,
+            a,
+            mu,
+            order,
+            cofactor,
+            n,
+            delta,
+        })
+    }
+
+    pub fn contains(&self, p: &BinaryPoint) -> bool {
+        if p.infinity {
+            return true;
+        }
+        let f = &self.field;
+        let x2 = f.square(&p.x);
+        let lhs = f.square(&p.y).add(&f.mul(&p.x, &p.y));
+        let mut rhs = f.mul(&x2, &p.x).add(&Gf2m::one());
+        if self.a == 1 {
+            rhs = rhs.add(&x2);
+        }
+        lhs == rhs
+    }
+
+    pub fn negate(&self, p: &BinaryPoint) -> BinaryPoint {
+        BinaryPoint {
+            y: p.y.add(&p.x),
+            ..*p
+        }
+    }
+
+    // The chord-and-tangent law in characteristic 2: the slope is
+    // (y1 + y2) / (x1 + x2), or x + y/x for a doubling
+    pub fn add(&self, p: &BinaryPoint, q: &BinaryPoint) -> BinaryPoint {
+        if p.infinity {
+            return *q;
+        }
+        if q.infinity {
+            return *p;
+        }
+        let f = &self.field;
+        let a = if self.a == 1 {
+            Gf2m::one()
+        } else {
+            Gf2m::zero()
+        };
+        if p.x == q.x {
+            // Q = -P, or P = Q on the x = 0 line of points of order 2
+            if p.y != q.y || p.x.is_zero() {
+                return BinaryPoint::at_infinity();
+            }
+            let slope = p.x.add(&f.div(&p.y, &p.x).unwrap());
+            let x = f.square(&slope).add(&slope).add(&a);
+            let y = f.square(&p.x).add(&f.mul(&slope, &x)).add(&x);
+            return BinaryPoint {
+                x,
+                y,
+                infinity: false,
+            };
+        }
+        // x1 ≠ x2, so the denominator is invertible
+        let slope = f.div(&p.y.add(&q.y), &p.x.add(&q.x)).unwrap();
+        let x = f.square(&slope).add(&slope).add(&p.x).add(&q.x).add(&a);
+        let y = f.mul(&slope, &p.x.add(&x)).add(&x).add(&p.y);
+        BinaryPoint {
+            x,
+            y,
+            infinity: false,
+        }
+    }
+
+    // Two squarings in place of a doubling
+    pub fn frobenius(&self, p: &BinaryPoint) -> BinaryPoint {
+        if p.infinity {
+            return *p;
+        }
+        BinaryPoint {
+            x: self.field.square(&p.x),
+            y: self.field.square(&p.y),
+            infinity: false,
+        }
+    }
+
+    // A point with the given x: y = xw for w² + w = x + a + 1/x², which
+    // the half-trace solves when that right-hand side has trace zero
+    pub fn point_with_x(&self, x: &BigUint) -> Option<BinaryPoint> {
+        let f = &self.field;
+        let x = f.element(x).ok()?;
+        if x.is_zero() {
+            return None;
+        }
+        let mut c = x.add(&f.inverse(&f.square(&x)).ok()?);
+        if self.a == 1 {
+            c = c.add(&Gf2m::one());
+        }
+        if f.trace(&c) {
+            return None;
+        }
+        let w = f.half_trace(&c).ok()?;
+        Some(BinaryPoint {
+            x,
+            y: f.mul(&x, &w),
+            infinity: false,
+        })
+    }
+
+    // The first point with x = 2, 3, ... pushed into the subgroup of order
+    // n by the cofactor, and checked to lie there
+    pub fn base_point(&self) -> Option<BinaryPoint> {
+        (2u32..1000).find_map(|x| {
+            let p = self.point_with_x(&BigUint::from(x))?;
+            let g = self.double_and_add(&p, &self.cofactor);
+            let in_subgroup = !g.infinity && self.double_and_add(&g, &self.n).infinity;
+            in_subgroup.then_some(g)
+        })
+    }
+
+    // Right-to-left double-and-add, the reference for τ-NAF
+    pub fn double_and_add(&self, p: &BinaryPoint, k: &BigInt) -> BinaryPoint {
+        let mut addend = if k.is_negative() { self.negate(p) } else { *p };
+        let k = k.magnitude();
+        let mut result = BinaryPoint::at_infinity();
+        for i in 0..k.bits() {
+            if k.bit(i) {
+                result = self.add(&result, &addend);
+            }
+            addend = self.add(&addend, &addend);
+        }
+        result
+    }
+
+    // Solinas's partial reduction: ρ = k - qδ for q the rounding of
+    // k/δ = k δ̄ / n in Z[τ], so ρ ≡ k (mod δ) and N(ρ) is below about n,
+    // which keeps the τ-NAF of ρ near m digits instead of 2 log2 k
+    fn partial_reduce(&self, k: &BigInt) -> ZTau {
+        let s = self.delta.conjugate(self.mu);
+        let q = round_in_z_tau(&(k * &s.0), &(k * &s.1), &self.n, self.mu);
+        ZTau(k.clone(), BigInt::zero()).sub(&q.mul(&self.delta, self.mu))
+    }
+
+    // The τ-adic NAF of k after partial reduction, least significant first
+    pub fn tnaf(&self, k: &BigInt) -> Vec<i8> {
+        let ZTau(r0, r1) = self.partial_reduce(k);
+        tnaf(r0, r1, self.mu)
+    }
+
+    // [k]P for P of order n from the τ-NAF: a Frobenius map for every digit
+    // and an addition or subtraction of P for every non-zero one, about
+    // m/3 additions and no doublings at all
+    pub fn tnaf_mult(&self, p: &BinaryPoint, k: &BigInt) -> BinaryPoint {
+        let negated = self.negate(p);
+        let mut result = BinaryPoint::at_infinity();
+        for &digit in self.tnaf(k).iter().rev() {
+            result = self.frobenius(&result);
+            if digit == 1 {
+                result = self.add(&result, p);
+            } else if digit == -1 {
+                result = self.add(&result, &negated);
+            }
+        }
+        result
+    }
+}
+
+// Nearest integer to x / n for n > 0
+fn round(x: &BigInt, n: &BigInt) -> BigInt {
+    (BigInt::from(2) * x + n).div_floor(&(BigInt::from(2) * n))
+}
+
+// Rounding λ = (g0 + g1 τ) / n to a nearby element of Z[τ], Solinas's
+// Routine 60: round each coordinate, then correct by one unit where the
+// remainder η falls outside the region of points closest to zero
+fn round_in_z_tau(g0: &BigInt, g1: &BigInt, n: &BigInt, mu: i64) -> ZTau {
+    let (f0, f1) = (round(g0, n), round(g1, n));
+    // η_i = e_i / n, and every comparison below is scaled by n
+    let e0 = g0 - &f0 * n;
+    let e1 = g1 - &f1 * n;
+    let eta = 2 * &e0 + mu * &e1;
+    let (mut h0, mut h1) = (0i64, 0i64);
+    if eta >= *n {
+        if &e0 - 3 * mu * &e1 < -n {
+            h1 = mu;
+        } else {
+            h0 = 1;
+        }
+    } else if &e0 + 4 * mu * &e1 >= 2 * n {
+        h1 = mu;
+    }
+    if eta < -n {
+        if &e0 - 3 * mu * &e1 >= *n {
+            h1 = -mu;
+        } else {
+            h0 = -1;
+        }
+    } else if &e0 + 4 * mu * &e1 < -2 * n {
+        h1 = -mu;
+    }
+    ZTau(f0 + h0, f1 + h1)
+}
+
+// Digits in {0, ±1} with r0 + r1 τ = Σ u_i τ^i and no two adjacent
+// non-zero: an odd r0 takes the digit u ≡ r0 - 2r1 (mod 4), which makes
+// the next quotient divisible by τ again, then (r0 - u) / τ is
+// r1 + μ r0 / 2 - (r0 / 2) τ
+pub fn tnaf(mut r0: BigInt, mut r1: BigInt, mu: i64) -> Vec<i8> {
+    let mut digits = Vec::new();
+    while !r0.is_zero() || !r1.is_zero() {
+        let digit = if r0.is_odd() {
+            let difference: BigInt = &r0 - &r1 * 2;
+            let residue = difference.mod_floor(&BigInt::from(4));
+            let u = if residue == BigInt::one() { 1 } else { -1 };
+            r0 -= u;
+            u
+        } else {
+            0
+        };
+        digits.push(digit);
+        let half: BigInt = &r0 / 2;
+        r0 = &r1 + mu * &half;
+        r1 = -half;
+    }
+    digits
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    fn hex(s: &str) -> BigUint {
+        BigUint::parse_bytes(s.as_bytes(), 16).unwrap()
+    }
+
+    fn point(curve: &KoblitzCurve, x: &str, y: &str) -> BinaryPoint {
+        BinaryPoint {
+            x: curve.field.element(&hex(x)).unwrap(),
+            y: curve.field.element(&hex(y)).unwrap(),
+            infinity: false,
+        }
+    }
+
+    // K-163 from FIPS 186: f = z^163 + z^7 + z^6 + z^3 + 1 and a = 1, with
+    // the published order n and generator G
+    fn k163() -> (KoblitzCurve, BinaryPoint) {
+        let curve = KoblitzCurve::new(163, &[7, 6, 3], 1).unwrap();
+        let g = point(
+            &curve,
+            "2FE13C0537BBC11ACAA07D793DE4E6D5E5C94EEE8",
+            "289070FB05D38FF58321F2E800536D538CCDAA3D9",
+        );
+        (curve, g)
+    }
+
+    #[test]
+    fn k163_order_and_generator_are_the_published_ones() {
+        let (curve, g) = k163();
+        let n = hex("4000000000000000000020108A2E0CC0D99F8A5EF");
+        assert_eq!(curve.n, BigInt::from(n));
+        assert_eq!(curve.cofactor, BigInt::from(2));
+        assert!(curve.contains(&g));
+        assert!(curve.double_and_add(&g, &curve.n).infinity);
+    }
+
+    #[test]
+    fn k233_order_and_generator_are_the_published_ones() {
+        let curve = KoblitzCurve::new(233, &[74], 0).unwrap();
+        let n = hex("8000000000000000000000000000069D5BB915BCD46EFB1AD5F173ABDF");
+        assert_eq!(curve.n, BigInt::from(n));
+        assert_eq!(curve.cofactor, BigInt::from(4));
+        let g = point(
+            &curve,
+            "17232BA853A7E731AF129F22FF4149563A419C26BF50A4C9D6EEFAD6126",
+            "1DB537DECE819B7F70F555A67C427A8CD9BF18AEB9B56E0C11056FAE6A3",
+        );
+        assert!(curve.contains(&g));
+        assert!(curve.double_and_add(&g, &curve.n).infinity);
+    }
+
+    #[test]
+    fn tnaf_matches_double_and_add_on_k163() {
+        let (curve, g) = k163();
+        let derived = curve.base_point().unwrap();
+        let n = curve.n.magnitude();
+        let mut rng = rand::thread_rng();
+        let mut scalars: Vec<BigInt> = vec![
+            BigInt::from(0),
+            BigInt::from(1),
+            BigInt::from(-7),
+            &curve.n - 1,
+            curve.n.clone(),
+            BigInt::from(BigUint::from(u128::MAX) * n + 12_345u32),
+        ];
+        scalars.extend((0..8).map(|_| {
+            let bytes: Vec<u8> = (0..21).map(|_| rng.gen()).collect();
+            BigInt::from(BigUint::from_bytes_le(&bytes) % n)
+        }));
+        for p in [g, derived] {
+            for k in &scalars {
+                assert_eq!(
+                    curve.tnaf_mult(&p, k),
+                    curve.double_and_add(&p, k),
+                    "k = {}",
+                    k
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn tnaf_digits_are_non_adjacent() {
+        let (curve, _) = k163();
+        let k = &curve.n - 12_345;
+        let digits = curve.tnaf(&k);
+        assert!(digits.len() <= 165, "{} digits", digits.len());
+        assert!(digits.windows(2).all(|w| w[0] == 0 || w[1] == 0));
+    }
+}
@@ -0,0 +1,383 @@
+use integer_factorization::is_prime_u64;
+
+use crate::Point;
+
+// Every scalar is processed as this many bits, leading zeros included
+pub const SCALAR_BITS: u32 = 64;
+
+// All ones when the bit is set, zero otherwise
+fn mask(bit: u64) -> u64 {
+    0u64.wrapping_sub(bit & 1)
+}
+
+// a where the mask is all ones, b where it is zero, without a branch
+fn select(mask: u64, a: u64, b: u64) -> u64 {
+    (a & mask) | (b & !mask)
+}
+
+// An element of F_p in Montgomery form, x R mod p with R = 2^64
+#[derive(Clone, Copy, Debug)]
+struct Fe(u64);
+
+// Montgomery arithmetic mod an odd p < 2^63: products are 128-bit
+// multiplications followed by REDC, and every reduction subtracts p under a
+// mask instead of behind a comparison, so the instructions executed never
+// depend on the values
+struct Field {
+    p: u64,
+    // -p^(-1) mod 2^64
+    p_inv: u64,
+    // R² mod p, to move into Montgomery form
+    r2: u64,
+}
+
+impl Field {
+    fn new(p: u64) -> Result<Self, &'static str> {
+        if p.is_multiple_of(2) || p <= 3 || p >= 1 << 63 || !is_prime_u64(p) {
+            return Err("Modulus must be an odd prime between 3 and 2^63");
+        }
+        // Newton's iteration doubles the correct low bits of p^(-1) each time
+        let mut inv = p;
+        for _ in 0..5 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(p.wrapping_mul(inv)));
+        }
+        let r = (1u128 << 64) % p as u128;
+        Ok(Field {
+            p,
+            p_inv: inv.wrapping_neg(),
+            r2: (r * r % p as u128) as u64,
+        })
+    }
+
+    // x - p if that does not borrow, x otherwise, for x < 2p
+    fn reduce_once(&self, x: u64) -> u64 {
+        let (difference, borrow) = x.overflowing_sub(self.p);
+        select(mask(borrow as u64), x, difference)
+    }
+
+    // t R^(-1) mod p for t < p²
+    fn redc(&self, t: u128) -> u64 {
+        let m = (t as u64).wrapping_mul(self.p_inv);
+        let u = (t + m as u128 * self.p as u128) >> 64;
+        self.reduce_once(u as u64)
+    }
+
+    fn element(&self, x: u64) -> Fe {
+        Fe(self.redc((x % self.p) as u128 * self.r2 as u128))
+    }
+
+    fn value(&self, x: Fe) -> u64 {
+        self.redc(x.0 as u128)
+    }
+
+    fn add(&self, x: Fe, y: Fe) -> Fe {
+        Fe(self.reduce_once(x.0 + y.0))
+    }
+
+    fn sub(&self, x: Fe, y: Fe) -> Fe {
+        let (difference, borrow) = x.0.overflowing_sub(y.0);
+        Fe(difference.wrapping_add(self.p & mask(borrow as u64)))
+    }
+
+    fn mul(&self, x: Fe, y: Fe) -> Fe {
+        Fe(self.redc(x.0 as u128 * y.0 as u128))
+    }
+
+    // x^(p-2), which is 1/x for x ≠ 0 and 0 for x = 0; the exponent is
+    // public, so square-and-multiply is safe here
+    fn invert(&self, x: Fe) -> Fe {
+        let mut result = self.element(1);
+        let mut base = x;
+        let mut e = self.p - 2;
+        while e > 0 {
+            if e & 1 == 1 {
+                result = self.mul(result, base);
+            }
+            base = self.mul(base, base);
+            e >>= 1;
+        }
+        result
+    }
+}
+
+// Projective (X : Y : Z) with the identity (0 : 1 : 0)
+#[derive(Clone, Copy, Debug)]
+struct Projective {
+    x: Fe,
+    y: Fe,
+    z: Fe,
+}
+
+// Swaps the two points when the bit is set, touching every word either way
+fn conditional_swap(bit: u64, p: &mut Projective, q: &mut Projective) {
+    let mask = mask(bit);
+    for (a, b) in [
+        (&mut p.x, &mut q.x),
+        (&mut p.y, &mut q.y),
+        (&mut p.z, &mut q.z),
+    ] {
+        let t = (a.0 ^ b.0) & mask;
+        a.0 ^= t;
+        b.0 ^= t;
+    }
+}
+
+// x^e mod p on public values
+fn pow_mod(mut base: u128, mut e: u128, p: u128) -> u128 {
+    let mut result = 1;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = result * base % p;
+        }
+        base = base * base % p;
+        e >>= 1;
+    }
+    result
+}
+
+// u v mod x³ + ax + b for polynomials of degree below 3 over F_p, folding
+// x⁴ = -ax² - bx and x³ = -ax - b back in
+fn mul_mod_cubic(u: &[u128; 3], v: &[u128; 3], a: u128, b: u128, p: u128) -> [u128; 3] {
+    let mut c = [0; 5];
+    for (i, ui) in u.iter().enumerate() {
+        for (j, vj) in v.iter().enumerate() {
+            c[i + j] = (c[i + j] + ui * vj) % p;
+        }
+    }
+    for d in [4, 3] {
+        c[d - 2] = (c[d - 2] + (p - a) * c[d]) % p;
+        c[d - 3] = (c[d - 3] + (p - b) * c[d]) % p;
+    }
+    [c[0], c[1], c[2]]
+}
+
+// Whether x³ + ax + b has a root in F_p, that is whether the curve has a
+// point of order 2: gcd(x^p - x, x³ + ax + b) is not a constant
+fn cubic_has_root(a: u64, b: u64, p: u64) -> bool {
+    let (a, b, p) = (a as u128, b as u128, p as u128);
+    let (mut power, mut base, mut e) = ([1, 0, 0], [0, 1, 0], p);
+    while e > 0 {
+        if e & 1 == 1 {
+            power = mul_mod_cubic(&power, &base, a, b, p);
+        }
+        base = mul_mod_cubic(&base, &base, a, b, p);
+        e >>= 1;
+    }
+    power[1] = (power[1] + p - 1) % p;
+
+    // Euclid's algorithm, coefficients from the constant term up
+    let (mut f, mut g) = (vec![b, a, 0, 1], power.to_vec());
+    loop {
+        while g.last() == Some(&0) {
+            g.pop();
+        }
+        match g.len() {
+            0 => return f.len() > 1,
+            1 => return false,
+            _ => {}
+        }
+        let lead_inverse = pow_mod(g[g.len() - 1], p - 2, p);
+        while f.len() >= g.len() {
+            let coefficient = f[f.len() - 1] * lead_inverse % p;
+            let shift = f.len() - g.len();
+            for (i, gi) in g.iter().enumerate() {
+                f[shift + i] = (f[shift + i] + p - coefficient * gi % p) % p;
+            }
+            f.pop();
+        }
+        std::mem::swap(&mut f, &mut g);
+    }
+}
+
+// y² = x³ + ax + b prepared for the ladder. The complete formulas below
+// are only complete on curves of odd order over a prime field, so `new`
+// rejects a composite modulus, a singular curve and any curve with a point
+// of order 2.
+pub struct LadderCurve {
+    field: Field,
+    a: Fe,
+    // 3b, as it appears in the complete formulas
+    b3: Fe,
+}
+
+impl LadderCurve {
+    pub fn new(a: i64, b: i64, m: i64) -> Result<Self, &'static str> {
+        let field = Field::new(m as u64)?;
+        let (a, b, p) = (a.rem_euclid(m) as u64, b.rem_euclid(m) as u64, m as u128);
+        let discriminant = (4 * pow_mod(a as u128, 3, p) + 27 * pow_mod(b as u128, 2, p)) % p;
+        if discriminant == 0 {
+            return Err("Curve is singular");
+        }
+        if cubic_has_root(a, b, m as u64) {
+            return Err("Curve has a point of order 2, the complete formulas need odd order");
+        }
+        let b3 = field.element((3 * b as u128 % p) as u64);
+        let a = field.element(a);
+        Ok(LadderCurve { field, a, b3 })
+    }
+
+    // The complete addition law of Renes, Costello and Batina (Algorithm 1,
+    // 12M + 3 m_a + 2 m_3b): the same formulas for P + Q, P + P and P + O
+    // on any curve of odd order, so nothing branches on the operands
+    fn add(&self, p: &Projective, q: &Projective) -> Projective {
+        let f = &self.field;
+        let (x1, y1, z1, x2, y2, z2) = (p.x, p.y, p.z, q.x, q.y, q.z);
+        let mut t0 = f.mul(x1, x2);
+        let mut t1 = f.mul(y1, y2);
+        let mut t2 = f.mul(z1, z2);
+        let mut t3 = f.mul(f.add(x1, y1), f.add(x2, y2));
+        let mut t4 = f.add(t0, t1);
+        t3 = f.sub(t3, t4);
+        t4 = f.mul(f.add(x1, z1), f.add(x2, z2));
+        let mut t5 = f.add(t0, t2);
+        t4 = f.sub(t4, t5);
+        t5 = f.mul(f.add(y1, z1), f.add(y2, z2));
+        let mut x3 = f.add(t1, t2);
+        t5 = f.sub(t5, x3);
+        let mut z3 = f.mul(self.a, t4);
+        x3 = f.mul(self.b3, t2);
+        z3 = f.add(x3, z3);
+        x3 = f.sub(t1, z3);
+        z3 = f.add(t1, z3);
+        let mut y3 = f.mul(x3, z3);
+        t1 = f.add(f.add(t0, t0), t0);
+        t2 = f.mul(self.a, t2);
+        t4 = f.mul(self.b3, t4);
+        t1 = f.add(t1, t2);
+        t2 = f.mul(self.a, f.sub(t0, t2));
+        t4 = f.add(t4, t2);
+        t0 = f.mul(t1, t4);
+        y3 = f.add(y3, t0);
+        t0 = f.mul(t5, t4);
+        x3 = f.sub(f.mul(t3, x3), t0);
+        t0 = f.mul(t3, t1);
+        z3 = f.add(f.mul(t5, z3), t0);
+        Projective {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    // The input point is public, so it may be inspected freely
+    fn projective(&self, p: &Point) -> Projective {
+        let f = &self.field;
+        if p.is_at_infinity() {
+            return Projective {
+                x: f.element(0),
+                y: f.element(1),
+                z: f.element(0),
+            };
+        }
+        Projective {
+            x: f.element(p.x as u64),
+            y: f.element(p.y as u64),
+            z: f.element(1),
+        }
+    }
+
+    // X/Z and Y/Z through an inversion that also maps 0 to 0, so the
+    // identity comes out as (0, 0) with the flag set
+    fn affine(&self, p: &Projective) -> Point {
+        let f = &self.field;
+        let z_inv = f.invert(p.z);
+        Point {
+            x: f.value(f.mul(p.x, z_inv)) as i64,
+            y: f.value(f.mul(p.y, z_inv)) as i64,
+            infinity: f.value(p.z) == 0,
+        }
+    }
+
+    // The Montgomery ladder over all SCALAR_BITS bits of k, keeping
+    // R1 - R0 = P: each step is one addition and one doubling whatever the
+    // bit, and the bit only decides a masked swap of R0 and R1
+    pub fn multiply(&self, p: &Point, k: u64) -> Point {
+        let mut r0 = self.projective(&Point::at_infinity());
+        let mut r1 = self.projective(p);
+        let mut swap = 0;
+        for i in (0..SCALAR_BITS).rev() {
+            let bit = (k >> i) & 1;
+            conditional_swap(swap ^ bit, &mut r0, &mut r1);
+            swap = bit;
+            r1 = self.add(&r0, &r1);
+            r0 = self.add(&r0, &r0);
+        }
+        conditional_swap(swap, &mut r0, &mut r1);
+        self.affine(&r0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dudect::{fixed_vs_random, T_THRESHOLD};
+    use crate::windowed::double_and_add;
+    use num_bigint::BigInt;
+
+    // y² = x³ + 2x + 40 mod 1000003, of prime order 999023
+    const CURVE: (i64, i64, i64) = (2, 40, 1_000_003);
+    const P: Point = Point {
+        x: 2,
+        y: 536_917,
+        infinity: false,
+    };
+
+    #[test]
+    fn matches_double_and_add() {
+        let (a, b, m) = CURVE;
+        let curve = LadderCurve::new(a, b, m).unwrap();
+        for k in [0, 1, 2, 999_022, 999_023, 123_456_789, u64::MAX] {
+            let ladder = curve.multiply(&P, k);
+            let (expected, _) = double_and_add(&P, &BigInt::from(k), a, m);
+            assert_eq!(
+                ladder.is_at_infinity(),
+                expected.is_at_infinity(),
+                "k = {}",
+                k
+            );
+            if !expected.is_at_infinity() {
+                assert_eq!((ladder.x, ladder.y), (expected.x, expected.y), "k = {}", k);
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_composite_modulus() {
+        // 1000001 = 101 · 9901
+        assert!(LadderCurve::new(2, 40, 1_000_001).is_err());
+        assert!(LadderCurve::new(2, 40, 1_000_004).is_err());
+    }
+
+    #[test]
+    fn rejects_singular_curve() {
+        assert_eq!(
+            LadderCurve::new(0, 0, 1_000_003).err(),
+            Some("Curve is singular")
+        );
+    }
+
+    #[test]
+    fn rejects_points_of_order_two() {
+        // (0, 0) on y² = x³ + x, and (1, 0) on y² = x³ + x - 2
+        assert!(LadderCurve::new(1, 0, 1_000_003).is_err());
+        assert!(LadderCurve::new(1, -2, 1_000_003).is_err());
+        // x³ + 2x + 40 has no root mod 1000003
+        assert!(!cubic_has_root(2, 40, 1_000_003));
+    }
+
+    // Timing is only meaningful in release mode on a quiet machine:
+    // `cargo test --release -- --ignored`
+    #[test]
+    #[ignore]
+    fn ladder_timing_is_independent_of_the_scalar() {
+        let (a, b, m) = CURVE;
+        let curve = LadderCurve::new(a, b, m).unwrap();
+        let t = fixed_vs_random(
+            |k| curve.multiply(&P, k),
+            0,
+            200_000,
+            &mut rand::thread_rng(),
+        );
+        assert!(t.abs() <= T_THRESHOLD, "t = {}", t);
+    }
+}
@@ -1,4 +1,26 @@
-#[derive(Debug, Clone)]
+mod batch;
+mod binary_field;
+mod dudect;
+mod fixed_base;
+mod glv;
+mod koblitz;
+mod ladder;
+mod msm;
+mod windowed;
+
+use batch::{batch_invert, multiples};
+use dudect::{fixed_vs_random, T_THRESHOLD};
+use fixed_base::FixedBaseTable;
+use glv::Glv;
+use koblitz::{BinaryPoint, KoblitzCurve};
+use ladder::LadderCurve;
+use msm::{msm, pippenger, straus};
+use num_bigint::{BigInt, BigUint};
+use rand::Rng;
+use std::time::Instant;
+use windowed::{double_and_add, naf, naf_mult, sliding_window_mult, wnaf_mult};
+
+#[derive(Debug, Clone, PartialEq)]
 struct Point {
     x: i64,
     y: i64,
@@ -24,7 +46,7 @@ fn mod_pos(a: i64, m: i64) -> i64 {
 }
 
 fn mod_inv(a: i64, m: i64) -> i64 {
-    let (mut t, mut new_t, mut r, mut new_r) = (0, 1, m, a % m);
+    let (mut t, mut new_t, mut r, mut new_r) = (0, 1, m, mod_pos(a, m));
     while new_r != 0 {
         let quotient = r / new_r;
         let temp = t;
@@ -56,10 +78,7 @@ fn add(p1: &Point, p2: &Point, a: i64, m: i64) -> Point {
         if p1.y == 0 {
             return Point::at_infinity();
         }
-        let lambda = mod_pos(
-            (3 * p1.x * p1.x + a) * mod_inv(2 * p1.y, m),
-            m
-        );
+        let lambda = mod_pos((3 * p1.x * p1.x + a) * mod_inv(2 * p1.y, m), m);
         let x3 = mod_pos(lambda * lambda - 2 * p1.x, m);
         let y3 = mod_pos(lambda * (p1.x - x3) - p1.y, m);
         Point {
@@ -72,10 +91,7 @@ fn add(p1: &Point, p2: &Point, a: i64, m: i64) -> Point {
         if p1.x == p2.x {
             return Point::at_infinity();
         }
-        let lambda = mod_pos(
-            (p2.y - p1.y) * mod_inv(p2.x - p1.x, m),
-            m
-        );
+        let lambda = mod_pos((p2.y - p1.y) * mod_inv(p2.x - p1.x, m), m);
         let x3 = mod_pos(lambda * lambda - p1.x - p2.x, m);
         let y3 = mod_pos(lambda * (p1.x - x3) - p1.y, m);
         Point {
@@ -85,10 +101,22 @@ fn add(p1: &Point, p2: &Point, a: i64, m: i64) -> Point {
         }
     }
 }
+fn negate(p: &Point, m: i64) -> Point {
+    if p.is_at_infinity() {
+        return p.clone();
+    }
+    Point {
+        x: p.x,
+        y: mod_pos(-p.y, m),
+        infinity: false,
+    }
+}
+
+// [k]P = [-k](-P) for negative k
 fn scalar_mult(p: &Point, k: i64, a: i64, m: i64) -> Point {
     let mut result = Point::at_infinity();
-    let mut addend = p.clone();
-    let mut k = k;
+    let mut addend = if k < 0 { negate(p, m) } else { p.clone() };
+    let mut k = k.unsigned_abs();
     while k > 0 {
         if k & 1 == 1 {
             result = add(&result, &addend, a, m);
@@ -98,7 +126,376 @@ fn scalar_mult(p: &Point, k: i64, a: i64, m: i64) -> Point {
     }
     result
 }
+// Fixed-versus-random timing of the ladder, and of the branching
+// double-and-add as a control that the test can see a leak at all;
+// passes when the ladder shows no significant difference
+fn timing_test(samples: usize) -> bool {
+    let (a, b, m) = (2, 40, 1_000_003);
+    let p = Point {
+        x: 2,
+        y: 536_917,
+        infinity: false,
+    };
+    let curve = LadderCurve::new(a, b, m).unwrap();
+    let mut rng = rand::thread_rng();
+    let t_ladder = fixed_vs_random(|k| curve.multiply(&p, k), 0, samples, &mut rng);
+    let t_branching = fixed_vs_random(|k| scalar_mult(&p, k as i64, a, m), 0, samples, &mut rng);
+    println!(
+        "dudect, {} samples, k = 0 against random k, |t| > {} means a leak:",
+        samples, T_THRESHOLD
+    );
+    println!("  Montgomery ladder   t = {:8.2}", t_ladder);
+    println!("  double-and-add      t = {:8.2}", t_branching);
+    t_ladder.abs() <= T_THRESHOLD
+}
+
+// GLV on a curve with a fast endomorphism: the decomposition of a few
+// scalars, then random scalars multiplied by GLV and by scalar_mult, which
+// must agree, with the time and the operations each takes
+fn glv_benchmark(name: &str, g: &Point, n: i64, a: i64, b: i64, m: i64) {
+    let glv = match Glv::detect(g, n, a, b, m) {
+        Some(glv) => glv,
+        None => {
+            println!("{}: no endomorphism found", name);
+            return;
+        }
+    };
+    println!(
+        "{}: {:?}, lambda = {}, basis {:?}",
+        name, glv.endomorphism, glv.lambda, glv.basis
+    );
+    for k in [1, n - 1, n / 2, 123_456] {
+        let (k1, k2) = glv.decompose(k);
+        let sum = add(
+            &scalar_mult(g, k1, a, m),
+            &scalar_mult(&glv.apply(g), k2, a, m),
+            a,
+            m,
+        );
+        println!(
+            "  {:7} = {:5} + {:5} lambda, {}",
+            k,
+            k1,
+            k2,
+            if sum == scalar_mult(g, k, a, m) {
+                "agrees"
+            } else {
+                "DISAGREES"
+            }
+        );
+    }
+
+    let mut rng = rand::thread_rng();
+    let scalars: Vec<i64> = (0..20_000).map(|_| rng.gen_range(0..n)).collect();
+    let start = Instant::now();
+    let mut glv_ops = 0;
+    let glv_results: Vec<Point> = scalars
+        .iter()
+        .map(|&k| {
+            let (point, ops) = glv.multiply(g, k);
+            glv_ops += ops.total();
+            point
+        })
+        .collect();
+    let glv_time = start.elapsed();
+    let start = Instant::now();
+    let plain_results: Vec<Point> = scalars.iter().map(|&k| scalar_mult(g, k, a, m)).collect();
+    let plain_time = start.elapsed();
+    let plain_ops: u64 = scalars
+        .iter()
+        .map(|&k| double_and_add(g, &BigInt::from(k), a, m).1.total())
+        .sum();
+    let mismatches = glv_results
+        .iter()
+        .zip(&plain_results)
+        .filter(|(x, y)| x != y)
+        .count();
+    println!(
+        "  {} random scalars, {} mismatches: GLV {:?} and {:.1} ops each, \
+         scalar_mult {:?} and {:.1} ops each, {:.2}x faster",
+        scalars.len(),
+        mismatches,
+        glv_time,
+        glv_ops as f64 / scalars.len() as f64,
+        plain_time,
+        plain_ops as f64 / scalars.len() as f64,
+        plain_time.as_secs_f64() / glv_time.as_secs_f64()
+    );
+}
+
+// Comb tables of growing width for a base point of order n, each checked
+// against scalar_mult on random scalars and timed against it, then one
+// saved to disk and reloaded
+fn fixed_base_benchmark(g: &Point, n: i64, a: i64, m: i64) {
+    let bits = 64 - n.leading_zeros();
+    let mut rng = rand::thread_rng();
+    let scalars: Vec<i64> = (0..20_000).map(|_| rng.gen_range(0..n)).collect();
+    let start = Instant::now();
+    let expected: Vec<Point> = scalars.iter().map(|&k| scalar_mult(g, k, a, m)).collect();
+    let plain_time = start.elapsed();
+    println!(
+        "{} random {}-bit scalars, scalar_mult {:?}",
+        scalars.len(),
+        bits,
+        plain_time
+    );
+    for width in [1, 2, 4, 6, 8] {
+        let table = FixedBaseTable::new(g, a, m, bits, width).unwrap();
+        let start = Instant::now();
+        let results: Vec<(Point, _)> = scalars
+            .iter()
+            .map(|&k| table.multiply(k).unwrap())
+            .collect();
+        let time = start.elapsed();
+        let mismatches = results
+            .iter()
+            .zip(&expected)
+            .filter(|((x, _), y)| x != *y)
+            .count();
+        let ops: u64 = results.iter().map(|(_, ops)| ops.total()).sum();
+        println!(
+            "  comb width {}: {:3} entries from {:3} ops, {:4.1} ops each, {:?}, \
+             {:4.1}x faster, {} mismatches",
+            width,
+            table.len(),
+            table.precomputation_cost().total(),
+            ops as f64 / scalars.len() as f64,
+            time,
+            plain_time.as_secs_f64() / time.as_secs_f64(),
+            mismatches
+        );
+    }
+
+    let path = std::env::temp_dir().join("scalar_multiplication_comb.txt");
+    let table = FixedBaseTable::new(g, a, m, bits, 8).unwrap();
+    let reloaded = table.save(&path).and_then(|_| FixedBaseTable::load(&path));
+    let _ = std::fs::remove_file(&path);
+    match reloaded {
+        Ok(reloaded) => {
+            let agrees = scalars.iter().zip(&expected).all(|(&k, expected)| {
+                reloaded.multiply(k).unwrap().0 == *expected
+                    && reloaded.multiply(-k).unwrap().0 == negate(expected, m)
+            });
+            println!(
+                "  width 8 table saved and reloaded, {}",
+                if agrees { "agrees" } else { "DISAGREES" }
+            );
+        }
+        Err(e) => println!("  saving and reloading failed: {}", e),
+    }
+    println!(
+        "  [2^{}]P with a {}-bit table: {:?}",
+        bits,
+        bits,
+        table.multiply(1 << bits).map(|(point, _)| point)
+    );
+}
+
+// Σ k_i P_i over random points and 63-bit signed scalars by Straus, by
+// Pippenger and by msm's choice, each checked against the sum of
+// scalar_mult results, with the operations of each and of the naive sum
+fn msm_benchmark(g: &Point, a: i64, m: i64) {
+    let mut rng = rand::thread_rng();
+    for n in [1, 2, 8, 32, 128, 512, 2048] {
+        let points: Vec<Point> = (0..n)
+            .map(|_| scalar_mult(g, rng.gen_range(1..1 << 20), a, m))
+            .collect();
+        let scalars: Vec<i64> = (0..n).map(|_| rng.gen()).collect();
+        let mut naive_ops = 0;
+        let naive = points
+            .iter()
+            .zip(&scalars)
+            .fold(Point::at_infinity(), |sum, (p, &k)| {
+                let (product, ops) = double_and_add(p, &BigInt::from(k), a, m);
+                naive_ops += ops.total() + 1;
+                add(&sum, &product, a, m)
+            });
+        let expected = points
+            .iter()
+            .zip(&scalars)
+            .fold(Point::at_infinity(), |sum, (p, &k)| {
+                add(&sum, &scalar_mult(p, k, a, m), a, m)
+            });
+        let (sum, chosen_ops, method) = msm(&points, &scalars, a, m).unwrap();
+        let (straus_sum, straus_ops) = straus(&points, &scalars, 4, a, m);
+        let (pippenger_sum, pippenger_ops) = pippenger(&points, &scalars, 6, a, m);
+        for result in [&naive, &sum, &straus_sum, &pippenger_sum] {
+            assert_eq!(*result, expected);
+        }
+        println!(
+            "  n = {:4}: naive {:6} ops, Straus {:6}, Pippenger {:6}, {:?} {:6}",
+            n,
+            naive_ops,
+            straus_ops.total(),
+            pippenger_ops.total(),
+            method,
+            chosen_ops.total()
+        );
+    }
+    println!(
+        "  mismatched lengths: {:?}",
+        msm(std::slice::from_ref(g), &[1, 2], a, m).map(|(sum, _, _)| sum)
+    );
+}
+
+// Montgomery's trick against one mod_inv per value, and the batched
+// enumeration of the multiples of G against repeated affine additions,
+// each of which pays an inversion
+fn batch_benchmark(g: &Point, a: i64, m: i64) {
+    let mut rng = rand::thread_rng();
+    let values: Vec<i64> = (0..100_000).map(|_| rng.gen_range(1..m)).collect();
+    let start = Instant::now();
+    let one_by_one: Vec<i64> = values.iter().map(|&x| mod_inv(x, m)).collect();
+    let single_time = start.elapsed();
+    let start = Instant::now();
+    let batched = batch_invert(&values, m).unwrap();
+    let batch_time = start.elapsed();
+    println!(
+        "{} inverses: mod_inv {:?}, batch_invert {:?}, {:.1}x faster, {}",
+        values.len(),
+        single_time,
+        batch_time,
+        single_time.as_secs_f64() / batch_time.as_secs_f64(),
+        if batched == one_by_one {
+            "agrees"
+        } else {
+            "DISAGREES"
+        }
+    );
+    println!(
+        "  with a zero among them: {:?}",
+        batch_invert(&[3, 0, 5], m)
+    );
+
+    let count = 100_000;
+    let start = Instant::now();
+    let mut affine = Vec::with_capacity(count);
+    let mut current = g.clone();
+    for _ in 0..count {
+        affine.push(current.clone());
+        current = add(&current, g, a, m);
+    }
+    let affine_time = start.elapsed();
+    let start = Instant::now();
+    let jacobian = multiples(g, count, a, m);
+    let jacobian_time = start.elapsed();
+    println!(
+        "  P..{}P: affine {:?}, batched {:?}, {:.1}x faster, {}",
+        count,
+        affine_time,
+        jacobian_time,
+        affine_time.as_secs_f64() / jacobian_time.as_secs_f64(),
+        if jacobian == affine {
+            "agrees"
+        } else {
+            "DISAGREES"
+        }
+    );
+}
+
+// τ-NAF against double-and-add on a NIST-sized Koblitz curve: the group
+// order from the Lucas sequence, compared with the published n, then
+// random scalars multiplied both ways on the published generator and on
+// the base point derived from the smallest usable x
+fn koblitz_benchmark(
+    name: &str,
+    m: u32,
+    middle_terms: &[u32],
+    a: u8,
+    published_n: &str,
+    published_g: (&str, &str),
+) {
+    let curve = KoblitzCurve::new(m, middle_terms, a).unwrap();
+    let published_n = BigInt::parse_bytes(published_n.as_bytes(), 16).unwrap();
+    let coordinate = |hex: &str| {
+        let value = BigUint::parse_bytes(hex.as_bytes(), 16).unwrap();
+        curve.field.element(&value).unwrap()
+    };
+    println!(
+        "{}: #E = {} = {} * {}, {}",
+        name,
+        curve.order,
+        curve.cofactor,
+        curve.n,
+        if curve.n == published_n {
+            "n as published"
+        } else {
+            "n DIFFERS from the published value"
+        }
+    );
+    let g = BinaryPoint {
+        x: coordinate(published_g.0),
+        y: coordinate(published_g.1),
+        infinity: false,
+    };
+    println!(
+        "  published G on the curve: {}, of order n: {}",
+        curve.contains(&g),
+        curve.double_and_add(&g, &curve.n).infinity
+    );
+    let derived = curve.base_point().unwrap();
+    println!(
+        "  derived base point, cofactor times the first point with x ≥ 2: ({:x}, {:x})",
+        curve.field.value(&derived.x),
+        curve.field.value(&derived.y)
+    );
+
+    let mut rng = rand::thread_rng();
+    let n = curve.n.magnitude();
+    let mut scalars: Vec<BigInt> = vec![
+        BigInt::from(0),
+        BigInt::from(1),
+        &curve.n - 1,
+        curve.n.clone(),
+        BigInt::from(BigUint::from(u128::MAX) * n + 12_345u32),
+    ];
+    scalars.extend((0..20).map(|_| {
+        let bytes: Vec<u8> = (0..m.div_ceil(8)).map(|_| rng.gen()).collect();
+        BigInt::from(BigUint::from_bytes_le(&bytes) % n)
+    }));
+    let k = &scalars[5];
+    let digits = curve.tnaf(k);
+    println!(
+        "  k of {} bits: binary weight {}, τ-NAF length {} and weight {}",
+        k.bits(),
+        k.magnitude().count_ones(),
+        digits.len(),
+        digits.iter().filter(|&&d| d != 0).count()
+    );
+    println!(
+        "  partially reduced τ-NAF of the {}-bit (2^128 - 1)n + 12345: {} digits",
+        scalars[4].bits(),
+        curve.tnaf(&scalars[4]).len()
+    );
+
+    for (label, p) in [("G", &g), ("the derived point", &derived)] {
+        let start = Instant::now();
+        let expected: Vec<_> = scalars.iter().map(|k| curve.double_and_add(p, k)).collect();
+        let binary_time = start.elapsed();
+        let start = Instant::now();
+        let results: Vec<_> = scalars.iter().map(|k| curve.tnaf_mult(p, k)).collect();
+        let tnaf_time = start.elapsed();
+        assert_eq!(results, expected);
+        println!(
+            "  {} scalars on {}: double-and-add {:?}, τ-NAF {:?}, {:.1}x faster",
+            scalars.len(),
+            label,
+            binary_time,
+            tnaf_time,
+            binary_time.as_secs_f64() / tnaf_time.as_secs_f64()
+        );
+    }
+}
+
 fn main() {
+    // `cargo run --release -- dudect [samples]` runs only the timing test
+    // and fails when the ladder leaks
+    let args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("dudect") {
+        let samples = args.get(2).and_then(|s| s.parse().ok()).unwrap_or(200_000);
+        std::process::exit(if timing_test(samples) { 0 } else { 1 });
+    }
+
     let a = 4;
     let m = 19;
     let k = 7;
@@ -108,10 +505,166 @@ fn main() {
         infinity: false,
     };
 
-let np = scalar_mult(&p1, k, a, m);
-   if np.is_at_infinity() {
-       println!("The result is at infinity");
-   } else {
-       println!("The result is {:?}", np);
-   }
-}
\ No newline at end of file
+    let np = scalar_mult(&p1, k, a, m);
+    if np.is_at_infinity() {
+        println!("The result is at infinity");
+    } else {
+        println!("The result is {:?}", np);
+    }
+    // The same multiple by every method, then the operation counts for
+    // scalars of growing size on a curve through P = (3, 7) mod 1000003
+    let (a, m) = (4, 1_000_003);
+    let p = Point {
+        x: 3,
+        y: 7,
+        infinity: false,
+    };
+    let k = BigInt::from(-1_234_567_891_011i64);
+    let expected = scalar_mult(&p, -1_234_567_891_011, a, m);
+    println!(
+        "\nNAF of {}: {:?}",
+        1_234_567_891_011i64,
+        naf(k.magnitude())
+    );
+    let results = [
+        ("double-and-add", double_and_add(&p, &k, a, m)),
+        ("NAF", naf_mult(&p, &k, a, m)),
+        ("4-NAF", wnaf_mult(&p, &k, 4, a, m)),
+        ("sliding window 4", sliding_window_mult(&p, &k, 4, a, m)),
+    ];
+    for (name, (point, _)) in &results {
+        println!(
+            "[{}]P by {}: ({}, {}), {}",
+            k,
+            name,
+            point.x,
+            point.y,
+            if point.x == expected.x && point.y == expected.y {
+                "agrees with scalar_mult"
+            } else {
+                "DISAGREES with scalar_mult"
+            }
+        );
+    }
+
+    let scalars = [
+        "14159265358979323846",
+        "271828182845904523536028747135266249775",
+        "115792089237316195423570985008687907852837564279074904382605163141518161494337",
+    ];
+    for k in scalars {
+        let k: BigInt = k.parse().unwrap();
+        println!("\n{}-bit scalar:", k.bits());
+        let baseline = double_and_add(&p, &k, a, m);
+        let mut results = vec![
+            ("double-and-add".to_string(), baseline.clone()),
+            ("NAF".to_string(), naf_mult(&p, &k, a, m)),
+        ];
+        for w in 3..=6 {
+            results.push((format!("{}-NAF", w), wnaf_mult(&p, &k, w, a, m)));
+        }
+        for w in 3..=6 {
+            let result = sliding_window_mult(&p, &k, w, a, m);
+            results.push((format!("sliding window {}", w), result));
+        }
+        for (name, (point, ops)) in results {
+            assert_eq!((point.x, point.y), (baseline.0.x, baseline.0.y));
+            println!(
+                "  {:18} {}, {:5.1}% of double-and-add",
+                name,
+                ops,
+                100.0 * ops.total() as f64 / baseline.1.total() as f64
+            );
+        }
+    }
+
+    // The ladder against the plain loop on y² = x³ + 2x + 40 mod 1000003,
+    // of prime order 999023, for scalars up to the full 64 bits
+    let (a, b, m) = (2, 40, 1_000_003);
+    let p = Point {
+        x: 2,
+        y: 536_917,
+        infinity: false,
+    };
+    let curve = LadderCurve::new(a, b, m).unwrap();
+    println!();
+    for k in [0, 1, 2, 999_022, 999_023, 123_456_789, u64::MAX] {
+        let ladder = curve.multiply(&p, k);
+        let (expected, _) = double_and_add(&p, &BigInt::from(k), a, m);
+        assert_eq!(ladder.is_at_infinity(), expected.is_at_infinity());
+        assert!(ladder.is_at_infinity() || (ladder.x, ladder.y) == (expected.x, expected.y));
+        println!(
+            "ladder [{}]P = {}, agrees with double-and-add",
+            k,
+            if ladder.is_at_infinity() {
+                "O".to_string()
+            } else {
+                format!("({}, {})", ladder.x, ladder.y)
+            }
+        );
+    }
+    println!("timing: `cargo run --release -- dudect`, fails when the ladder leaks");
+
+    // y² = x³ + 5 mod 1000003 has prime order 999007, and on
+    // y² = x³ + 2x mod 1000037 the point below has prime order 500153
+    println!();
+    let g = Point {
+        x: 1,
+        y: 586_770,
+        infinity: false,
+    };
+    glv_benchmark("j = 0", &g, 999_007, 0, 5, 1_000_003);
+    let g = Point {
+        x: 301_864,
+        y: 331_917,
+        infinity: false,
+    };
+    glv_benchmark("j = 1728", &g, 500_153, 2, 0, 1_000_037);
+    glv_benchmark("y² = x³ + 2x + 40", &p, 999_023, 2, 40, 1_000_003);
+
+    // Fixed-base multiplication of P = (2, 536917) on y² = x³ + 2x + 40
+    println!();
+    fixed_base_benchmark(&p, 999_023, 2, 1_000_003);
+
+    // Multi-scalar multiplication on the same curve, Straus with 4-bit and
+    // Pippenger with 6-bit windows next to the automatic choice
+    println!("\nmulti-scalar multiplication, 63-bit scalars:");
+    msm_benchmark(&p, 2, 1_000_003);
+
+    // Batch inversion, and enumeration through the order 999023 of P so
+    // that the point at infinity passes through the normalization too
+    println!();
+    batch_benchmark(&p, 2, 1_000_003);
+    let subgroup = multiples(&p, 999_024, 2, 1_000_003);
+    println!(
+        "  [999023]P = {:?}, [999024]P = P: {}",
+        subgroup[999_022],
+        subgroup[999_023] == p
+    );
+
+    // The NIST Koblitz curves K-163, with a = 1 and f = z^163 + z^7 + z^6
+    // + z^3 + 1, and K-233, with a = 0 and f = z^233 + z^74 + 1
+    println!();
+    koblitz_benchmark(
+        "K-163",
+        163,
+        &[7, 6, 3],
+        1,
+        "4000000000000000000020108A2E0CC0D99F8A5EF",
+        (
+            "2FE13C0537BBC11ACAA07D793DE4E6D5E5C94EEE8",
+            "289070FB05D38FF58321F2E800536D538CCDAA3D9",
+        ),
+    );
+    koblitz_benchmark(
+        "K-233",
+        233,
+        &[74],
+        0,
+        "8000000000000000000000000000069D5BB915BCD46EFB1AD5F173ABDF",
+        (
+            "17232BA853A7E731AF129F22FF4149563A419C26BF50A4C9D6EEFAD6126",
+            "1DB537DECE819B7F70F555A67C427A8CD9BF18AEB9B56E0C11056FAE6A3",
+        ),
+    );
+}
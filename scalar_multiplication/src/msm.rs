@@ -0,0 +1,230 @@
+use crate::batch::{batch_to_affine, Jacobian, JacobianCounter};
+use crate::windowed::{Counter, OpCount};
+use crate::{negate, Point};
+
+// Which algorithm msm runs, chosen from the estimated operation counts
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Method {
+    Straus { window: u32 },
+    Pippenger { window: u32 },
+}
+
+// Bit length of the largest |k|
+fn scalar_bits(scalars: &[i64]) -> u32 {
+    scalars
+        .iter()
+        .map(|k| 64 - k.unsigned_abs().leading_zeros())
+        .max()
+        .unwrap_or(0)
+}
+
+// The window bits of |k| starting at bit `shift`
+fn digit(k: u64, shift: u32, window: u32) -> usize {
+    if shift >= 64 {
+        return 0;
+    }
+    ((k >> shift) & ((1 << window) - 1)) as usize
+}
+
+// Negative scalars become positive ones on the negated point
+fn signed(points: &[Point], scalars: &[i64], m: i64) -> Vec<(Point, u64)> {
+    points
+        .iter()
+        .zip(scalars)
+        .map(|(p, &k)| {
+            let point = if k < 0 { negate(p, m) } else { p.clone() };
+            (point, k.unsigned_abs())
+        })
+        .collect()
+}
+
+// Straus's interleaving, Shamir's trick with windows: every point gets a
+// table of its multiples up to 2^window - 1 and all of them share one
+// chain of doublings, each window adding one entry per point. About
+// n 2^window + b + nb / window operations for n scalars of b bits.
+pub fn straus(points: &[Point], scalars: &[i64], window: u32, a: i64, m: i64) -> (Point, OpCount) {
+    let terms = signed(points, scalars, m);
+    let bits = scalar_bits(scalars);
+    // Every table is built in Jacobian coordinates and all of them are made
+    // affine by one batch inversion
+    let mut jacobian = JacobianCounter::new(a, m);
+    let mut entries = Vec::with_capacity(terms.len() << window);
+    for (p, _) in &terms {
+        let p = Jacobian::from_affine(p);
+        let mut current = Jacobian::infinity();
+        for _ in 0..1 << window {
+            entries.push(current);
+            current = jacobian.add(&current, &p);
+        }
+    }
+    let entries = batch_to_affine(&entries, m);
+    let tables: Vec<&[Point]> = entries.chunks(1 << window).collect();
+    let mut counter = Counter::new(a, m);
+    counter.ops = jacobian.ops;
+    let mut result = Point::at_infinity();
+    for shift in (0..bits.div_ceil(window)).rev().map(|i| i * window) {
+        for _ in 0..window {
+            result = counter.double(&result);
+        }
+        for ((_, k), table) in terms.iter().zip(&tables) {
+            result = counter.add(&result, &table[digit(*k, shift, window)]);
+        }
+    }
+    (result, counter.ops)
+}
+
+// Pippenger's bucket method: for each window every point is added into the
+// bucket of its digit, and the running sum over the buckets from the top
+// down yields Σ d B_d in about 2^(window+1) additions. About
+// (b / window)(n + 2^(window+1) + window) operations, so the precomputation
+// no longer grows with n.
+pub fn pippenger(
+    points: &[Point],
+    scalars: &[i64],
+    window: u32,
+    a: i64,
+    m: i64,
+) -> (Point, OpCount) {
+    let terms = signed(points, scalars, m);
+    let bits = scalar_bits(scalars);
+    let mut counter = Counter::new(a, m);
+    let mut result = Point::at_infinity();
+    for shift in (0..bits.div_ceil(window)).rev().map(|i| i * window) {
+        for _ in 0..window {
+            result = counter.double(&result);
+        }
+        // Bucket d - 1 collects the points whose digit is d; digit 0 is skipped
+        let mut buckets = vec![Point::at_infinity(); (1 << window) - 1];
+        for (p, k) in &terms {
+            let d = digit(*k, shift, window);
+            if d > 0 {
+                buckets[d - 1] = counter.add(&buckets[d - 1], p);
+            }
+        }
+        let mut running = Point::at_infinity();
+        let mut sum = Point::at_infinity();
+        for bucket in buckets.iter().rev() {
+            running = counter.add(&running, bucket);
+            sum = counter.add(&sum, &running);
+        }
+        result = counter.add(&result, &sum);
+    }
+    (result, counter.ops)
+}
+
+// The cheaper of the two for n scalars of b bits, by the estimates above
+// with the best window for each
+pub fn choose_method(n: usize, bits: u32) -> Method {
+    let (n, b) = (n as f64, bits.max(1) as f64);
+    let straus_cost = |w: u32| n * (1u64 << w) as f64 + b + n * b / w as f64;
+    let pippenger_cost = |w: u32| (b / w as f64).ceil() * (n + (1u64 << (w + 1)) as f64 + w as f64);
+    let best = |cost: &dyn Fn(u32) -> f64| {
+        (1..=16)
+            .min_by(|&x, &y| cost(x).total_cmp(&cost(y)))
+            .unwrap()
+    };
+    let (straus_window, pippenger_window) = (best(&straus_cost), best(&pippenger_cost));
+    if straus_cost(straus_window) <= pippenger_cost(pippenger_window) {
+        Method::Straus {
+            window: straus_window,
+        }
+    } else {
+        Method::Pippenger {
+            window: pippenger_window,
+        }
+    }
+}
+
+// Σ k_i P_i by whichever method is cheaper for this many scalars
+pub fn msm(
+    points: &[Point],
+    scalars: &[i64],
+    a: i64,
+    m: i64,
+) -> Result<(Point, OpCount, Method), &'static str> {
+    if points.len() != scalars.len() {
+        return Err("Number of points and scalars must be equal");
+    }
+    let method = choose_method(points.len(), scalar_bits(scalars));
+    let (sum, ops) = match method {
+        Method::Straus { window } => straus(points, scalars, window, a, m),
+        Method::Pippenger { window } => pippenger(points, scalars, window, a, m),
+    };
+    Ok((sum, ops, method))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{add, scalar_mult};
+    use rand::Rng;
+
+    // y² = x³ + 2x + 40 mod 1000003, of prime order 999023
+    const A: i64 = 2;
+    const M: i64 = 1_000_003;
+    const G: Point = Point {
+        x: 2,
+        y: 536_917,
+        infinity: false,
+    };
+
+    fn naive(points: &[Point], scalars: &[i64]) -> Point {
+        points
+            .iter()
+            .zip(scalars)
+            .fold(Point::at_infinity(), |sum, (p, &k)| {
+                add(&sum, &scalar_mult(p, k, A, M), A, M)
+            })
+    }
+
+    fn assert_all_agree(points: &[Point], scalars: &[i64]) {
+        let expected = naive(points, scalars);
+        for window in [1, 3, 4, 6] {
+            assert_eq!(
+                straus(points, scalars, window, A, M).0,
+                expected,
+                "Straus, w = {}",
+                window
+            );
+            assert_eq!(
+                pippenger(points, scalars, window, A, M).0,
+                expected,
+                "Pippenger, w = {}",
+                window
+            );
+        }
+        assert_eq!(msm(points, scalars, A, M).unwrap().0, expected);
+    }
+
+    #[test]
+    fn random_signed_scalars() {
+        let mut rng = rand::thread_rng();
+        for n in [1, 2, 7, 40] {
+            let points: Vec<Point> = (0..n)
+                .map(|_| scalar_mult(&G, rng.gen_range(1..999_023), A, M))
+                .collect();
+            let scalars: Vec<i64> = (0..n).map(|_| rng.gen_range(-(1 << 40)..1 << 40)).collect();
+            assert_all_agree(&points, &scalars);
+        }
+    }
+
+    #[test]
+    fn empty_sum_is_infinity() {
+        assert_all_agree(&[], &[]);
+        assert!(msm(&[], &[], A, M).unwrap().0.is_at_infinity());
+    }
+
+    #[test]
+    fn points_at_infinity_zero_and_cancelling_terms() {
+        let g2 = scalar_mult(&G, 2, A, M);
+        let points = [Point::at_infinity(), G.clone(), g2.clone(), G.clone(), g2];
+        assert_all_agree(&points, &[5, 0, -3, 6, 3]);
+        assert_all_agree(&points, &[-1, 999_023, 1, -1, -1]);
+        assert_all_agree(&[Point::at_infinity(), Point::at_infinity()], &[7, -9]);
+    }
+
+    #[test]
+    fn rejects_mismatched_lengths() {
+        assert!(msm(std::slice::from_ref(&G), &[1, 2], A, M).is_err());
+    }
+}
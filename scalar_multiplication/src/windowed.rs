@@ -0,0 +1,259 @@
+use num_bigint::{BigInt, BigUint, Sign};
+use num_traits::{ToPrimitive, Zero};
+use std::fmt;
+
+use crate::batch::{batch_to_affine, Jacobian, JacobianCounter};
+use crate::{add, negate, Point};
+
+// Group operations spent on one scalar multiplication, precomputation included
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct OpCount {
+    pub doublings: u64,
+    pub additions: u64,
+}
+
+impl OpCount {
+    pub fn total(&self) -> u64 {
+        self.doublings + self.additions
+    }
+}
+
+impl fmt::Display for OpCount {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{:4} doublings + {:4} additions = {:4}",
+            self.doublings,
+            self.additions,
+            self.total()
+        )
+    }
+}
+
+// The curve arithmetic with a tally of the operations that do real work,
+// those where neither operand is the point at infinity
+pub(crate) struct Counter {
+    a: i64,
+    m: i64,
+    pub(crate) ops: OpCount,
+}
+
+impl Counter {
+    pub(crate) fn new(a: i64, m: i64) -> Self {
+        Counter {
+            a,
+            m,
+            ops: OpCount::default(),
+        }
+    }
+
+    pub(crate) fn double(&mut self, p: &Point) -> Point {
+        if !p.is_at_infinity() {
+            self.ops.doublings += 1;
+        }
+        add(p, p, self.a, self.m)
+    }
+
+    pub(crate) fn add(&mut self, p: &Point, q: &Point) -> Point {
+        if !p.is_at_infinity() && !q.is_at_infinity() {
+            self.ops.additions += 1;
+        }
+        add(p, q, self.a, self.m)
+    }
+
+    // P, 3P, 5P, ..., (2count - 1)P, one doubling and count - 1 additions in
+    // Jacobian coordinates made affine by a single batch inversion
+    fn odd_multiples(&mut self, p: &Point, count: usize) -> Vec<Point> {
+        let mut jacobian = JacobianCounter::new(self.a, self.m);
+        let p = Jacobian::from_affine(p);
+        let double = jacobian.double(&p);
+        let mut table = vec![p];
+        for i in 1..count {
+            let next = jacobian.add(&table[i - 1], &double);
+            table.push(next);
+        }
+        self.ops.doublings += jacobian.ops.doublings;
+        self.ops.additions += jacobian.ops.additions;
+        batch_to_affine(&table, self.m)
+    }
+}
+
+// A negative scalar multiplies the negated point by |k|
+fn magnitude(p: &Point, k: &BigInt, m: i64) -> (Point, BigUint) {
+    let point = if k.sign() == Sign::Minus {
+        negate(p, m)
+    } else {
+        p.clone()
+    };
+    (point, k.magnitude().clone())
+}
+
+// Width-w NAF of k, least significant digit first: every non-zero digit is
+// odd and below 2^(w-1) in absolute value, and any w consecutive digits
+// hold at most one non-zero, so about 1/(w+1) of the digits are non-zero.
+// w = 2 is the ordinary NAF.
+pub fn wnaf(k: &BigUint, w: u32) -> Vec<i64> {
+    let modulus = 1i64 << w;
+    let mut k = k.clone();
+    let mut digits = Vec::new();
+    while !k.is_zero() {
+        let digit = if k.bit(0) {
+            let residue = (&k % modulus as u64).to_i64().unwrap();
+            let digit = if residue >= modulus / 2 {
+                residue - modulus
+            } else {
+                residue
+            };
+            if digit > 0 {
+                k -= digit as u64;
+            } else {
+                k += (-digit) as u64;
+            }
+            digit
+        } else {
+            0
+        };
+        digits.push(digit);
+        k >>= 1;
+    }
+    digits
+}
+
+pub fn naf(k: &BigUint) -> Vec<i64> {
+    wnaf(k, 2)
+}
+
+// The existing right-to-left double-and-add, counted for comparison
+pub fn double_and_add(p: &Point, k: &BigInt, a: i64, m: i64) -> (Point, OpCount) {
+    let (mut addend, k) = magnitude(p, k, m);
+    let mut counter = Counter::new(a, m);
+    let mut result = Point::at_infinity();
+    for i in 0..k.bits() {
+        if k.bit(i) {
+            result = counter.add(&result, &addend);
+        }
+        if i + 1 < k.bits() {
+            addend = counter.double(&addend);
+        }
+    }
+    (result, counter.ops)
+}
+
+// Left-to-right evaluation of the width-w NAF with the odd multiples
+// P, 3P, ..., (2^(w-1) - 1)P; subtractions are free since -Q only flips y.
+// About n doublings and n/(w+1) additions for an n-bit scalar.
+pub fn wnaf_mult(p: &Point, k: &BigInt, w: u32, a: i64, m: i64) -> (Point, OpCount) {
+    let (point, k) = magnitude(p, k, m);
+    let mut counter = Counter::new(a, m);
+    let table = if w > 2 {
+        counter.odd_multiples(&point, 1 << (w - 2))
+    } else {
+        vec![point]
+    };
+    let mut result = Point::at_infinity();
+    for &digit in wnaf(&k, w).iter().rev() {
+        result = counter.double(&result);
+        if digit > 0 {
+            result = counter.add(&result, &table[digit as usize / 2]);
+        } else if digit < 0 {
+            result = counter.add(&result, &negate(&table[(-digit) as usize / 2], m));
+        }
+    }
+    (result, counter.ops)
+}
+
+pub fn naf_mult(p: &Point, k: &BigInt, a: i64, m: i64) -> (Point, OpCount) {
+    wnaf_mult(p, k, 2, a, m)
+}
+
+// Left-to-right sliding window: a run of zeros costs only doublings and
+// each window of at most w bits, starting and ending with a one, one
+// addition of a precomputed odd multiple up to (2^w - 1)P.
+pub fn sliding_window_mult(p: &Point, k: &BigInt, w: u32, a: i64, m: i64) -> (Point, OpCount) {
+    let (point, k) = magnitude(p, k, m);
+    let mut counter = Counter::new(a, m);
+    let table = counter.odd_multiples(&point, 1 << (w - 1));
+    let mut result = Point::at_infinity();
+    let mut i = k.bits() as i64 - 1;
+    while i >= 0 {
+        if !k.bit(i as u64) {
+            result = counter.double(&result);
+            i -= 1;
+            continue;
+        }
+        // The longest window k_i ... k_j ending in a one
+        let mut j = (i - w as i64 + 1).max(0);
+        while !k.bit(j as u64) {
+            j += 1;
+        }
+        let mut value = 0usize;
+        for bit in (j..=i).rev() {
+            result = counter.double(&result);
+            value = 2 * value + k.bit(bit as u64) as usize;
+        }
+        result = counter.add(&result, &table[value / 2]);
+        i = j - 1;
+    }
+    (result, counter.ops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scalar_mult;
+
+    // y² = x³ + 4x + 10 mod 1000003 through P = (3, 7)
+    const A: i64 = 4;
+    const M: i64 = 1_000_003;
+    const P: Point = Point {
+        x: 3,
+        y: 7,
+        infinity: false,
+    };
+
+    #[test]
+    fn odd_multiples_are_batch_normalized() {
+        let mut counter = Counter::new(A, M);
+        let table = counter.odd_multiples(&P, 16);
+        for (i, point) in table.iter().enumerate() {
+            assert_eq!(
+                *point,
+                scalar_mult(&P, 2 * i as i64 + 1, A, M),
+                "{}P",
+                2 * i + 1
+            );
+        }
+        assert_eq!(
+            counter.ops,
+            OpCount {
+                doublings: 1,
+                additions: 15
+            }
+        );
+    }
+
+    #[test]
+    fn windowed_methods_match_scalar_mult() {
+        for k in [1, -1, 2, 255, -1_234_567_891_011, i64::MAX] {
+            let expected = scalar_mult(&P, k, A, M);
+            let big = BigInt::from(k);
+            assert_eq!(double_and_add(&P, &big, A, M).0, expected);
+            for w in 2..=6 {
+                assert_eq!(
+                    wnaf_mult(&P, &big, w, A, M).0,
+                    expected,
+                    "{}-NAF, k = {}",
+                    w,
+                    k
+                );
+                assert_eq!(
+                    sliding_window_mult(&P, &big, w, A, M).0,
+                    expected,
+                    "window {}, k = {}",
+                    w,
+                    k
+                );
+            }
+        }
+    }
+}
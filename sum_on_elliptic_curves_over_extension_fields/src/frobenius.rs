@@ -0,0 +1,333 @@
+use num_bigint::BigInt;
+use num_traits::{One, Zero};
+use std::fmt;
+
+//how a curve over F_p is classified by its trace of frobenius
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CurveClass {
+    Ordinary,
+    //t ≡ 0 mod p, the curve has no points of order p over any extension
+    Supersingular,
+    //t = 1, so #E(F_p) = p and the ECDLP falls to Smart's attack
+    Anomalous,
+}
+
+impl fmt::Display for CurveClass {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CurveClass::Ordinary => write!(f, "ordinary"),
+            CurveClass::Supersingular => write!(f, "supersingular"),
+            CurveClass::Anomalous => write!(f, "anomalous"),
+        }
+    }
+}
+
+//the trace t = p + 1 - #E(F_p) of a curve y^2 = x^3 + ax + b over a prime field
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrobeniusTrace {
+    pub p: i64,
+    pub t: i64,
+}
+
+impl FrobeniusTrace {
+    //count the points of the curve and derive the trace from them
+    pub fn of_curve(a: i64, b: i64, p: i64) -> Result<Self, &'static str> {
+        if p <= 3 {
+            return Err("short weierstrass form needs a prime p > 3");
+        }
+        if (4 * pow_mod(a, 3, p) as i128 + 27 * pow_mod(b, 2, p) as i128) % p as i128 == 0 {
+            return Err("curve is singular");
+        }
+        Self::from_order(p, count_points(a, b, p))
+    }
+
+    //derive the trace from a known group order, rejecting orders outside the hasse interval
+    pub fn from_order(p: i64, order: i64) -> Result<Self, &'static str> {
+        let trace = FrobeniusTrace {
+            p,
+            t: p + 1 - order,
+        };
+        if !trace.satisfies_hasse_bound() {
+            return Err("group order lies outside the Hasse interval");
+        }
+        Ok(trace)
+    }
+
+    //hasse's theorem: |t| <= 2 sqrt(p), checked exactly as t^2 <= 4p
+    pub fn satisfies_hasse_bound(&self) -> bool {
+        (self.t as i128).pow(2) <= 4 * self.p as i128
+    }
+
+    //the interval [p + 1 - 2 sqrt(p), p + 1 + 2 sqrt(p)] that #E(F_p) must lie in
+    pub fn hasse_interval(&self) -> (i64, i64) {
+        let mut s = (4.0 * self.p as f64).sqrt() as i64;
+        while (s as i128 + 1).pow(2) <= 4 * self.p as i128 {
+            s += 1;
+        }
+        while (s as i128).pow(2) > 4 * self.p as i128 {
+            s -= 1;
+        }
+        (self.p + 1 - s, self.p + 1 + s)
+    }
+
+    pub fn classify(&self) -> CurveClass {
+        if self.t.rem_euclid(self.p) == 0 {
+            CurveClass::Supersingular
+        } else if self.t == 1 {
+            CurveClass::Anomalous
+        } else {
+            CurveClass::Ordinary
+        }
+    }
+
+    //s_k = α^k + β^k where α, β are the roots of T^2 - tT + p, via
+    //s_0 = 2, s_1 = t, s_k = t s_{k-1} - p s_{k-2}
+    pub fn trace_over_extension(&self, k: u32) -> BigInt {
+        let t = BigInt::from(self.t);
+        let p = BigInt::from(self.p);
+        let mut prev = BigInt::from(2);
+        let mut current = t.clone();
+        if k == 0 {
+            return prev;
+        }
+        for _ in 1..k {
+            let next = &t * &current - &p * &prev;
+            prev = current;
+            current = next;
+        }
+        current
+    }
+
+    //#E(F_{p^k}) = p^k + 1 - s_k, no enumeration over the extension needed
+    pub fn order_over_extension(&self, k: u32) -> BigInt {
+        BigInt::from(self.p).pow(k) + BigInt::one() - self.trace_over_extension(k)
+    }
+
+    pub fn zeta(&self) -> ZetaFunction {
+        ZetaFunction { trace: *self }
+    }
+}
+
+//Z(E/F_p, T) = exp(sum #E(F_{p^k}) T^k / k) = (1 - tT + pT^2) / ((1 - T)(1 - pT))
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZetaFunction {
+    trace: FrobeniusTrace,
+}
+
+impl ZetaFunction {
+    //coefficients of the numerator L(T) = 1 - tT + pT^2, lowest degree first
+    pub fn numerator(&self) -> [i64; 3] {
+        [1, -self.trace.t, self.trace.p]
+    }
+
+    //the first n coefficients of Z(T) as a power series, obtained by multiplying
+    //L(T) with the geometric series 1/(1 - T) and 1/(1 - pT)
+    pub fn series(&self, n: usize) -> Vec<BigInt> {
+        let p = BigInt::from(self.trace.p);
+        let mut geometric = vec![BigInt::zero(); n];
+        let mut p_power = BigInt::one();
+        let mut partial = BigInt::zero();
+        for coefficient in geometric.iter_mut() {
+            partial += &p_power;
+            *coefficient = partial.clone();
+            p_power *= &p;
+        }
+
+        let mut series = vec![BigInt::zero(); n];
+        for (i, l) in self.numerator().iter().enumerate() {
+            for j in 0..n.saturating_sub(i) {
+                series[i + j] += &geometric[j] * l;
+            }
+        }
+        series
+    }
+
+    pub fn order_over_extension(&self, k: u32) -> BigInt {
+        self.trace.order_over_extension(k)
+    }
+}
+
+impl fmt::Display for ZetaFunction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [_, linear, p] = self.numerator();
+        let sign = if linear < 0 { '-' } else { '+' };
+        write!(
+            f,
+            "(1 {} {}T + {}T^2) / ((1 - T)(1 - {}T))",
+            sign,
+            linear.abs(),
+            p,
+            p
+        )
+    }
+}
+
+//#E(F_p) = p + 1 + sum of legendre symbols of x^3 + ax + b
+pub fn count_points(a: i64, b: i64, p: i64) -> i64 {
+    let mut count = 1;
+    for x in 0..p {
+        let rhs = (pow_mod(x, 3, p) as i128 + a as i128 * x as i128 + b as i128)
+            .rem_euclid(p as i128) as i64;
+        if rhs == 0 {
+            count += 1;
+        } else if pow_mod(rhs, (p - 1) / 2, p) == 1 {
+            count += 2;
+        }
+    }
+    count
+}
+
+fn pow_mod(base: i64, exponent: i64, modulus: i64) -> i64 {
+    let modulus = modulus as i128;
+    let mut result = 1i128;
+    let mut base = (base as i128).rem_euclid(modulus);
+    let mut exponent = exponent;
+
+    while exponent > 0 {
+        if exponent % 2 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exponent /= 2;
+    }
+    result as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    //product in F_p[t]/(g) for a monic g, coefficients lowest degree first
+    fn mul(x: &[i64], y: &[i64], g: &[i64], p: i64) -> Vec<i64> {
+        let k = g.len() - 1;
+        let mut product = vec![0; 2 * k];
+        for (i, xi) in x.iter().enumerate() {
+            for (j, yj) in y.iter().enumerate() {
+                product[i + j] = (product[i + j] + xi * yj) % p;
+            }
+        }
+        for i in (k..2 * k).rev() {
+            let c = product[i];
+            for (j, gj) in g.iter().enumerate().take(k) {
+                product[i - k + j] = (product[i - k + j] - c * gj).rem_euclid(p);
+            }
+        }
+        product.truncate(k);
+        product
+    }
+
+    //#E(F_{p^k}) by trying every x and y in F_p[t]/(g), g irreducible of degree k
+    fn count_by_enumeration(a: i64, b: i64, p: i64, g: &[i64]) -> i64 {
+        let k = g.len() - 1;
+        let elements: Vec<Vec<i64>> = (0..p.pow(k as u32))
+            .map(|mut i| {
+                (0..k)
+                    .map(|_| {
+                        let c = i % p;
+                        i /= p;
+                        c
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut square_roots: HashMap<Vec<i64>, i64> = HashMap::new();
+        for y in &elements {
+            *square_roots.entry(mul(y, y, g, p)).or_default() += 1;
+        }
+        let mut count = 1;
+        for x in &elements {
+            let mut rhs = mul(&mul(x, x, g, p), x, g, p);
+            for (i, c) in rhs.iter_mut().enumerate() {
+                *c = (*c + a * x[i]) % p;
+            }
+            rhs[0] = (rhs[0] + b) % p;
+            count += square_roots.get(&rhs).copied().unwrap_or(0);
+        }
+        count
+    }
+
+    #[test]
+    fn extension_orders_match_enumeration() {
+        //t, t^2 + 2 as in F5x2, and t^3 + t + 1, irreducible over F_5
+        let moduli: [&[i64]; 3] = [&[0, 1], &[2, 0, 1], &[1, 1, 0, 1]];
+        for (a, b) in [(1, 1), (3, 2), (2, 4)] {
+            let zeta = FrobeniusTrace::of_curve(a, b, 5).unwrap().zeta();
+            for (k, g) in (1..=3).zip(moduli) {
+                assert_eq!(
+                    zeta.order_over_extension(k),
+                    BigInt::from(count_by_enumeration(a, b, 5, g)),
+                    "y^2 = x^3 + {}x + {} over F_5^{}",
+                    a,
+                    b,
+                    k
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn series_is_the_exponential_of_the_point_counts() {
+        //Z = exp(sum N_k T^k / k) means n z_n = sum_{k=1}^{n} N_k z_{n-k}
+        let trace = FrobeniusTrace::of_curve(1, 1, 5).unwrap();
+        let series = trace.zeta().series(8);
+        assert_eq!(series[0], BigInt::one());
+        for n in 1..8 {
+            let sum: BigInt = (1..=n)
+                .map(|k| trace.order_over_extension(k as u32) * &series[n - k])
+                .sum();
+            assert_eq!(BigInt::from(n) * &series[n], sum, "z_{}", n);
+        }
+    }
+
+    #[test]
+    fn hasse_bound() {
+        let trace = FrobeniusTrace::from_order(5, 9).unwrap();
+        assert_eq!(trace.hasse_interval(), (2, 10));
+        assert!(FrobeniusTrace::from_order(5, 2).is_ok());
+        assert!(FrobeniusTrace::from_order(5, 10).is_ok());
+        for order in [1, 11, 0, -3] {
+            assert_eq!(
+                FrobeniusTrace::from_order(5, order),
+                Err("group order lies outside the Hasse interval")
+            );
+        }
+        //every nonsingular curve over F_101 lands inside the interval
+        for a in 0..101 {
+            for b in 0..101 {
+                if let Ok(trace) = FrobeniusTrace::of_curve(a, b, 101) {
+                    let (low, high) = trace.hasse_interval();
+                    let order = 101 + 1 - trace.t;
+                    assert!(low <= order && order <= high);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn rejects_bad_curves() {
+        assert_eq!(
+            FrobeniusTrace::of_curve(1, 1, 3),
+            Err("short weierstrass form needs a prime p > 3")
+        );
+        assert_eq!(FrobeniusTrace::of_curve(0, 0, 5), Err("curve is singular"));
+        assert_eq!(FrobeniusTrace::of_curve(-3, 2, 7), Err("curve is singular"));
+    }
+
+    #[test]
+    fn classifies_curves() {
+        //y^2 = x^3 + x over F_7, p ≡ 3 mod 4, has p + 1 = 8 points
+        let supersingular = FrobeniusTrace::of_curve(1, 0, 7).unwrap();
+        assert_eq!(supersingular.t, 0);
+        assert_eq!(supersingular.classify(), CurveClass::Supersingular);
+
+        let anomalous = FrobeniusTrace::of_curve(3, 2, 5).unwrap();
+        assert_eq!(anomalous.t, 1);
+        assert_eq!(anomalous.classify(), CurveClass::Anomalous);
+
+        let ordinary = FrobeniusTrace::of_curve(1, 1, 5).unwrap();
+        assert_eq!(ordinary.t, -3);
+        assert_eq!(ordinary.classify(), CurveClass::Ordinary);
+        assert_eq!(ordinary.classify().to_string(), "ordinary");
+    }
+}
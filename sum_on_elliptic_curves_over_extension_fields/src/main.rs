@@ -1,3 +1,6 @@
+mod frobenius;
+
+use frobenius::FrobeniusTrace;
 use std::fmt;
 
 //A struct to represent elements
@@ -129,6 +132,17 @@ impl Point {
     }
 }
 
+//count the affine points of y^2 = x^3 + ax + b over F_{5^2} by brute force, plus infinity
+fn count_points_by_enumeration(a: F5x2, b: F5x2) -> usize {
+    let elements: Vec<F5x2> = (0..25).map(|i| F5x2::new(i % 5, i / 5)).collect();
+    let mut count = 1;
+    for &x in &elements {
+        let rhs = x.mul(x).mul(x).add(a.mul(x)).add(b);
+        count += elements.iter().filter(|&&y| y.mul(y) == rhs).count();
+    }
+    count
+}
+
 fn point_add(p: Point, q: Point, a: F5x2) -> Point {
     //check if either point is infinity
     if p.is_infinity() {
@@ -167,7 +181,7 @@ fn point_add(p: Point, q: Point, a: F5x2) -> Point {
 fn main() {
     // Define the elliptic curve parameters
     let a = F5x2::new(1, 0);
-    let _b = F5x2::new(1, 0);  // Prefixed with underscore to indicate intentional non-use
+    let b = F5x2::new(1, 0);
 
     // Define two points on the curve
     let p = Point::new(Some(F5x2::new(3, 4)), Some(F5x2::new(4, 3)));
@@ -180,4 +194,20 @@ fn main() {
     println!("point q : {}", q);
 
     println!("Result: {}", r);
-}
\ No newline at end of file
+
+    // The same curve over the prime field determines every extension through its trace
+    let trace = FrobeniusTrace::of_curve(1, 1, 5).unwrap();
+    println!("\nTrace of Frobenius over F_5: t = {}", trace.t);
+    println!("Hasse interval: {:?}", trace.hasse_interval());
+    println!("Curve is {}", trace.classify());
+    let zeta = trace.zeta();
+    println!("Zeta function: {}", zeta);
+    println!("Z(T) = {:?} + ...", zeta.series(5));
+    for k in 1..=6 {
+        println!("#E(F_5^{}) = {}", k, zeta.order_over_extension(k));
+    }
+    println!(
+        "#E(F_25) by enumerating F5x2: {}",
+        count_points_by_enumeration(a, b)
+    );
+}